@@ -0,0 +1,173 @@
+//! Parsing of the `#[someip(...)]` attributes.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, Ident, LitInt, Result};
+
+/// Attributes of a struct or enum.
+#[derive(Default)]
+pub(crate) struct Container {
+    /// Width of the length field preceding the whole struct or union
+    pub length_field: Option<u8>,
+    /// Members are TLV encoded
+    pub tlv: bool,
+    /// Base type of a C-like enum
+    pub base: Option<Ident>,
+    /// Selector field of a union
+    pub selector_field: Option<TokenStream>,
+    /// Size a union member or struct is padded to
+    pub padding: Option<usize>,
+    /// Alignment after every member with a length field
+    pub align: Option<TokenStream>,
+}
+
+/// Attributes of a struct field.
+#[derive(Default)]
+pub(crate) struct Member {
+    /// Width of the length field preceding the member
+    pub length_field: Option<u8>,
+    /// Alignment of the start of the member
+    pub align: Option<TokenStream>,
+    /// TLV data ID of the member
+    pub id: Option<u16>,
+    /// TLV wire type of the member, overriding the one derived from its type
+    pub wire_type: Option<u8>,
+}
+
+/// Attributes of an enum variant.
+#[derive(Default)]
+pub(crate) struct Variant {
+    /// Union selector value of the variant
+    pub selector: Option<u32>,
+}
+
+impl Container {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut container = Container::default();
+        parse_someip(attrs, |meta| {
+            if meta.path.is_ident("length_field") {
+                container.length_field = Some(length_field(&meta)?);
+            } else if meta.path.is_ident("tlv") {
+                container.tlv = true;
+            } else if meta.path.is_ident("base") {
+                let base: Ident = meta.value()?.parse()?;
+                if !["u8", "u16", "u32"].iter().any(|ty| base == ty) {
+                    return Err(syn::Error::new(
+                        base.span(),
+                        "expected `u8`, `u16` or `u32`",
+                    ));
+                }
+                container.base = Some(base);
+            } else if meta.path.is_ident("selector_field") {
+                let width: LitInt = meta.value()?.parse()?;
+                container.selector_field = Some(match width.base10_parse::<u8>()? {
+                    8 => quote!(::someip_wire::serialize::SelectorField::U8),
+                    16 => quote!(::someip_wire::serialize::SelectorField::U16),
+                    32 => quote!(::someip_wire::serialize::SelectorField::U32),
+                    _ => return Err(syn::Error::new(width.span(), "expected 8, 16 or 32")),
+                });
+            } else if meta.path.is_ident("align") {
+                container.align = Some(alignment(&meta)?);
+            } else if meta.path.is_ident("padding") {
+                let padding: LitInt = meta.value()?.parse()?;
+                container.padding = Some(padding.base10_parse()?);
+            } else {
+                return Err(meta.error("unknown someip container attribute"));
+            }
+            Ok(())
+        })?;
+        Ok(container)
+    }
+}
+
+impl Member {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut member = Member::default();
+        parse_someip(attrs, |meta| {
+            if meta.path.is_ident("length_field") {
+                member.length_field = Some(length_field(&meta)?);
+            } else if meta.path.is_ident("align") {
+                member.align = Some(alignment(&meta)?);
+            } else if meta.path.is_ident("wire_type") {
+                let wire_type: LitInt = meta.value()?.parse()?;
+                let value = wire_type.base10_parse::<u8>()?;
+                if value > 7 {
+                    return Err(syn::Error::new(wire_type.span(), "expected 0 to 7"));
+                }
+                member.wire_type = Some(value);
+            } else if meta.path.is_ident("id") {
+                let id: LitInt = meta.value()?.parse()?;
+                let value = id.base10_parse::<u16>()?;
+                if value > 0x0FFF {
+                    return Err(syn::Error::new(
+                        id.span(),
+                        "data IDs are limited to 12 bits",
+                    ));
+                }
+                member.id = Some(value);
+            } else {
+                return Err(meta.error("unknown someip field attribute"));
+            }
+            Ok(())
+        })?;
+        Ok(member)
+    }
+}
+
+impl Variant {
+    pub(crate) fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut variant = Variant::default();
+        parse_someip(attrs, |meta| {
+            if meta.path.is_ident("selector") {
+                let selector: LitInt = meta.value()?.parse()?;
+                variant.selector = Some(selector.base10_parse()?);
+            } else {
+                return Err(meta.error("unknown someip variant attribute"));
+            }
+            Ok(())
+        })?;
+        Ok(variant)
+    }
+}
+
+/// Calls `f` for every item of every `#[someip(...)]` attribute.
+fn parse_someip<F>(attrs: &[Attribute], mut f: F) -> Result<()>
+where
+    F: FnMut(ParseNestedMeta) -> Result<()>,
+{
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("someip")) {
+        attr.parse_nested_meta(&mut f)?;
+    }
+    Ok(())
+}
+
+/// Parses `length_field = 0 | 8 | 16 | 32`.
+fn length_field(meta: &ParseNestedMeta) -> Result<u8> {
+    let width: LitInt = meta.value()?.parse()?;
+    match width.base10_parse::<u8>()? {
+        value @ (0 | 8 | 16 | 32) => Ok(value),
+        _ => Err(syn::Error::new(width.span(), "expected 0, 8, 16 or 32")),
+    }
+}
+
+/// Parses `align = 8 | 16 | 32 | 64` into an `Alignment` expression.
+fn alignment(meta: &ParseNestedMeta) -> Result<TokenStream> {
+    let bits: LitInt = meta.value()?.parse()?;
+    match bits.base10_parse::<u32>()? {
+        value @ (8 | 16 | 32 | 64) => Ok(quote!(
+            const { ::someip_wire::serialize::Alignment::from_bits(#value).unwrap() }
+        )),
+        _ => Err(syn::Error::new(bits.span(), "expected 8, 16, 32 or 64")),
+    }
+}
+
+/// Returns the `LengthField` path of a length field width.
+pub(crate) fn length_field_path(width: u8) -> TokenStream {
+    match width {
+        0 => quote!(::someip_wire::serialize::LengthField::None),
+        8 => quote!(::someip_wire::serialize::LengthField::U8),
+        16 => quote!(::someip_wire::serialize::LengthField::U16),
+        _ => quote!(::someip_wire::serialize::LengthField::U32),
+    }
+}
@@ -0,0 +1,596 @@
+//! Code generation for the derive macros.
+
+use crate::attr::{self, length_field_path};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    Data, DataEnum, DeriveInput, Fields, GenericParam, Generics, Ident, Index, Lifetime,
+    LifetimeParam, LitStr, Member, PathArguments, Result, Type, parse_quote,
+};
+
+/// Expands `#[derive(SomeIpSerialize)]`.
+pub(crate) fn serialize(input: &DeriveInput) -> Result<TokenStream> {
+    let container = attr::Container::parse(&input.attrs)?;
+    let body = match &input.data {
+        Data::Struct(data) if container.tlv => serialize_tlv(&container, &data.fields)?,
+        Data::Struct(data) => serialize_struct(&container, &data.fields)?,
+        Data::Enum(data) if is_c_like(data) => serialize_c_like(&container, &input.ident, data)?,
+        Data::Enum(data) => serialize_union(&container, data)?,
+        Data::Union(_) => return Err(unsupported(input)),
+    };
+
+    let mut generics = input.generics.clone();
+    add_bounds(
+        &mut generics,
+        parse_quote!(::someip_wire::serialize::SomeIpSerialize),
+    );
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let ident = &input.ident;
+
+    Ok(quote! {
+        impl #impl_generics ::someip_wire::serialize::SomeIpSerialize for #ident #ty_generics
+        #where_clause
+        {
+            fn serialize(
+                &self,
+                writer: &mut ::someip_wire::serialize::Writer<'_>,
+            ) -> ::core::result::Result<(), ::someip_wire::error::Error> {
+                #body
+            }
+        }
+    })
+}
+
+/// Expands `#[derive(SomeIpDeserialize)]`.
+pub(crate) fn deserialize(input: &DeriveInput) -> Result<TokenStream> {
+    let container = attr::Container::parse(&input.attrs)?;
+    let body = match &input.data {
+        Data::Struct(data) if container.tlv => deserialize_tlv(&container, &data.fields)?,
+        Data::Struct(data) => deserialize_struct(&container, &data.fields)?,
+        Data::Enum(data) if is_c_like(data) => deserialize_c_like(&container, &input.ident, data)?,
+        Data::Enum(data) => deserialize_union(&container, data)?,
+        Data::Union(_) => return Err(unsupported(input)),
+    };
+
+    // Borrow from the payload for the first lifetime of the type, if any
+    let mut generics = input.generics.clone();
+    let lifetime = match input.generics.lifetimes().next() {
+        Some(param) => param.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'de", Span::call_site());
+            let param = LifetimeParam::new(lifetime.clone());
+            generics.params.insert(0, GenericParam::Lifetime(param));
+            lifetime
+        }
+    };
+    add_bounds(
+        &mut generics,
+        parse_quote!(::someip_wire::serialize::SomeIpDeserialize<#lifetime>),
+    );
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let ident = &input.ident;
+
+    Ok(quote! {
+        impl #impl_generics ::someip_wire::serialize::SomeIpDeserialize<#lifetime>
+            for #ident #ty_generics
+        #where_clause
+        {
+            fn deserialize(
+                reader: &mut ::someip_wire::serialize::Reader<#lifetime>,
+            ) -> ::core::result::Result<Self, ::someip_wire::error::Error> {
+                #body
+            }
+        }
+    })
+}
+
+fn unsupported(input: &DeriveInput) -> syn::Error {
+    syn::Error::new_spanned(
+        &input.ident,
+        "unions are not supported, use an enum instead",
+    )
+}
+
+/// Adds a trait bound to every type parameter.
+fn add_bounds(generics: &mut Generics, bound: syn::TypeParamBound) {
+    for param in generics.type_params_mut() {
+        param.bounds.push(bound.clone());
+    }
+}
+
+/// Returns true if the enum has only unit variants.
+fn is_c_like(data: &DataEnum) -> bool {
+    data.variants
+        .iter()
+        .all(|variant| matches!(variant.fields, Fields::Unit))
+}
+
+/// Returns the accessor of every field.
+fn members(fields: &Fields) -> impl Iterator<Item = (Member, &syn::Field)> {
+    fields.iter().enumerate().map(|(index, field)| {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+        (member, field)
+    })
+}
+
+/// Builds `Self { .. }`, `Self(..)` or `Self` from one expression per field.
+fn construct(fields: &Fields, values: Vec<TokenStream>) -> TokenStream {
+    match fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|field| &field.ident);
+            quote!(Self { #(#names: #values,)* })
+        }
+        Fields::Unnamed(_) => quote!(Self(#(#values,)*)),
+        Fields::Unit => quote!(Self),
+    }
+}
+
+/// Wraps the serialization of a struct into its length field and padding, if any.
+fn wrap_writer(container: &attr::Container, body: TokenStream) -> TokenStream {
+    let body = match container.length_field {
+        Some(width) => {
+            let length_field = length_field_path(width);
+            quote!(#length_field.write(writer, |writer| { #body }))
+        }
+        None => body,
+    };
+    match container.padding {
+        Some(size) => quote! {
+            ::someip_wire::serialize::Padding::new(#size).write(writer, |writer| { #body })
+        },
+        None => body,
+    }
+}
+
+/// Limits the reader to the padded size and length field of a struct, if any.
+fn wrap_reader(container: &attr::Container, body: TokenStream) -> TokenStream {
+    let body = match container.length_field {
+        Some(width) => {
+            let length_field = length_field_path(width);
+            quote! {
+                let mut __reader = #length_field.read(reader)?;
+                let reader = &mut __reader;
+                #body
+            }
+        }
+        None => body,
+    };
+    match container.padding {
+        Some(size) => quote! {
+            let mut __padded = ::someip_wire::serialize::Padding::new(#size).read(reader)?;
+            let reader = &mut __padded;
+            #body
+        },
+        None => body,
+    }
+}
+
+fn serialize_struct(container: &attr::Container, fields: &Fields) -> Result<TokenStream> {
+    let mut statements = Vec::new();
+    for (member, field) in members(fields) {
+        let attrs = attr::Member::parse(&field.attrs)?;
+        if attrs.id.is_some() || attrs.wire_type.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "TLV attributes require #[someip(tlv)]",
+            ));
+        }
+        if let Some(align) = attrs.align {
+            statements.push(quote!(#align.pad(writer)?;));
+        }
+        statements.push(match (attrs.length_field, &container.align) {
+            (Some(width), Some(alignment)) => {
+                let length_field = length_field_path(width);
+                quote!(#length_field.serialize_aligned(writer, &self.#member, #alignment)?;)
+            }
+            (Some(width), None) => {
+                let length_field = length_field_path(width);
+                quote!(#length_field.serialize(writer, &self.#member)?;)
+            }
+            (None, _) => quote!(writer.write(&self.#member)?;),
+        });
+    }
+    Ok(wrap_writer(
+        container,
+        quote! {
+            #(#statements)*
+            ::core::result::Result::Ok(())
+        },
+    ))
+}
+
+fn deserialize_struct(container: &attr::Container, fields: &Fields) -> Result<TokenStream> {
+    let mut values = Vec::new();
+    for field in fields {
+        let attrs = attr::Member::parse(&field.attrs)?;
+        if attrs.id.is_some() || attrs.wire_type.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "TLV attributes require #[someip(tlv)]",
+            ));
+        }
+        let align = attrs.align.map(|align| quote!(#align.skip(reader)?;));
+        let read = match (attrs.length_field, &container.align) {
+            (Some(width), Some(alignment)) => {
+                let length_field = length_field_path(width);
+                quote!(#length_field.deserialize_aligned(reader, #alignment)?)
+            }
+            (Some(width), None) => {
+                let length_field = length_field_path(width);
+                quote!(#length_field.deserialize(reader)?)
+            }
+            (None, _) => quote!(reader.read()?),
+        };
+        values.push(quote!({ #align #read }));
+    }
+    let value = construct(fields, values);
+    Ok(wrap_reader(
+        container,
+        quote!(::core::result::Result::Ok(#value)),
+    ))
+}
+
+/// Returns the inner type of an `Option<T>`.
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+/// Returns the wire type of a TLV member.
+///
+/// Base data types are recognized by name; all other types are complex data types
+/// preceded by the length field configured for the member, 32 bits by default.
+fn wire_type(ty: &Type, attrs: &attr::Member) -> Result<TokenStream> {
+    let wire_type = match attrs.wire_type {
+        Some(wire_type) => wire_type,
+        None => {
+            let name = match ty {
+                Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+                _ => None,
+            };
+            match (name.as_deref(), attrs.length_field) {
+                (Some("bool" | "u8" | "i8"), None) => 0,
+                (Some("u16" | "i16"), None) => 1,
+                (Some("u32" | "i32" | "f32"), None) => 2,
+                (Some("u64" | "i64" | "f64"), None) => 3,
+                (_, Some(8)) => 5,
+                (_, Some(16)) => 6,
+                (_, Some(32) | None) => 7,
+                (_, Some(_)) => {
+                    return Err(syn::Error::new_spanned(
+                        ty,
+                        "TLV members require a length field of 8, 16 or 32 bits",
+                    ));
+                }
+            }
+        }
+    };
+    let variant = match wire_type {
+        0 => quote!(Base8),
+        1 => quote!(Base16),
+        2 => quote!(Base32),
+        3 => quote!(Base64),
+        4 => quote!(Complex),
+        5 => quote!(Complex8),
+        6 => quote!(Complex16),
+        _ => quote!(Complex32),
+    };
+    Ok(quote!(::someip_wire::serialize::WireType::#variant))
+}
+
+/// Returns the data ID of a TLV member, checking that it is given, unique and that the
+/// member is not aligned.
+fn tlv_id(field: &syn::Field, attrs: &attr::Member, ids: &mut Vec<u16>) -> Result<u16> {
+    let Some(id) = attrs.id else {
+        return Err(syn::Error::new_spanned(
+            field,
+            "TLV members require #[someip(id = ..)]",
+        ));
+    };
+    if attrs.align.is_some() {
+        return Err(syn::Error::new_spanned(
+            field,
+            "TLV members cannot be aligned",
+        ));
+    }
+    if ids.contains(&id) {
+        return Err(syn::Error::new_spanned(
+            field,
+            format!("data ID {} is used by another member", id),
+        ));
+    }
+    ids.push(id);
+    Ok(id)
+}
+
+fn serialize_tlv(container: &attr::Container, fields: &Fields) -> Result<TokenStream> {
+    if container.align.is_some() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "TLV members cannot be aligned",
+        ));
+    }
+    let mut statements = Vec::new();
+    let mut ids = Vec::new();
+    for (member, field) in members(fields) {
+        let attrs = attr::Member::parse(&field.attrs)?;
+        let id = tlv_id(field, &attrs, &mut ids)?;
+        let inner = option_inner(&field.ty);
+        let wire_type = wire_type(inner.unwrap_or(&field.ty), &attrs)?;
+        let tag = quote!(::someip_wire::serialize::Tag::new(#wire_type, #id));
+        statements.push(match inner {
+            Some(_) => quote! {
+                if let ::core::option::Option::Some(value) = &self.#member {
+                    format.serialize(writer, #tag, value)?;
+                }
+            },
+            None => quote!(format.serialize(writer, #tag, &self.#member)?;),
+        });
+    }
+    Ok(wrap_writer(
+        container,
+        quote! {
+            let format = ::someip_wire::serialize::TlvFormat::default();
+            #(#statements)*
+            ::core::result::Result::Ok(())
+        },
+    ))
+}
+
+fn deserialize_tlv(container: &attr::Container, fields: &Fields) -> Result<TokenStream> {
+    if container.align.is_some() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "TLV members cannot be aligned",
+        ));
+    }
+    let mut declarations = Vec::new();
+    let mut arms = Vec::new();
+    let mut values = Vec::new();
+    let mut ids = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let attrs = attr::Member::parse(&field.attrs)?;
+        let id = tlv_id(field, &attrs, &mut ids)?;
+        let variable = format_ident!("__member_{}", index);
+        let ty = &field.ty;
+        arms.push(quote! {
+            #id => #variable = ::core::option::Option::Some(member.deserialize()?),
+        });
+        match option_inner(ty) {
+            Some(_) => {
+                declarations.push(quote!(let mut #variable: #ty = ::core::option::Option::None;));
+                values.push(quote!(#variable));
+            }
+            None => {
+                declarations.push(quote! {
+                    let mut #variable: ::core::option::Option<#ty> = ::core::option::Option::None;
+                });
+                values.push(quote! {
+                    #variable.ok_or(::someip_wire::error::Error::MissingMember(#id))?
+                });
+            }
+        }
+    }
+    let value = construct(fields, values);
+    Ok(wrap_reader(
+        container,
+        quote! {
+            #(#declarations)*
+            let members = reader.limit(reader.remaining())?;
+            for member in ::someip_wire::serialize::TlvFormat::default().members(members) {
+                let member = member?;
+                match member.data_id() {
+                    #(#arms)*
+                    _ => {}
+                }
+            }
+            ::core::result::Result::Ok(#value)
+        },
+    ))
+}
+
+/// Returns the base type of a C-like enum, `u8` by default.
+fn base_type(container: &attr::Container) -> TokenStream {
+    match &container.base {
+        Some(base) => quote!(#base),
+        None => quote!(u8),
+    }
+}
+
+fn check_c_like(container: &attr::Container, data: &DataEnum) -> Result<()> {
+    if container.tlv
+        || container.length_field.is_some()
+        || container.padding.is_some()
+        || container.align.is_some()
+        || data.variants.is_empty()
+    {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "C-like enums need at least one variant and only support #[someip(base = ..)]",
+        ));
+    }
+    Ok(())
+}
+
+/// Fails compilation if a discriminant does not fit the base type, as the `as` cast
+/// would silently truncate it.
+fn check_discriminants(base: &TokenStream, ident: &Ident, data: &DataEnum) -> TokenStream {
+    let checks = data.variants.iter().map(|variant| {
+        let variant = &variant.ident;
+        let message = LitStr::new(
+            &format!("discriminant of `{}` does not fit the base type", variant),
+            variant.span(),
+        );
+        quote! {
+            let value = #ident::#variant as i128;
+            assert!(value >= #base::MIN as i128 && value <= #base::MAX as i128, #message);
+        }
+    });
+    quote! {
+        const _: () = {
+            #(#checks)*
+        };
+    }
+}
+
+fn serialize_c_like(
+    container: &attr::Container,
+    ident: &Ident,
+    data: &DataEnum,
+) -> Result<TokenStream> {
+    check_c_like(container, data)?;
+    let base = base_type(container);
+    let check = check_discriminants(&base, ident, data);
+    let variants = data.variants.iter().map(|variant| &variant.ident);
+    Ok(quote! {
+        #check
+        let value: #base = match self {
+            #(Self::#variants => Self::#variants as #base,)*
+        };
+        writer.write(&value)
+    })
+}
+
+fn deserialize_c_like(
+    container: &attr::Container,
+    ident: &Ident,
+    data: &DataEnum,
+) -> Result<TokenStream> {
+    check_c_like(container, data)?;
+    let base = base_type(container);
+    let check = check_discriminants(&base, ident, data);
+    let variants = data.variants.iter().map(|variant| &variant.ident);
+    Ok(quote! {
+        #check
+        let value: #base = reader.read()?;
+        #(
+            if value == Self::#variants as #base {
+                return ::core::result::Result::Ok(Self::#variants);
+            }
+        )*
+        ::core::result::Result::Err(::someip_wire::error::Error::InvalidEnumValue(value as u32))
+    })
+}
+
+/// Returns the `UnionFormat` of a variant enum.
+fn union_format(container: &attr::Container) -> Result<TokenStream> {
+    if container.tlv || container.base.is_some() || container.align.is_some() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "variant enums only support length_field, selector_field and padding",
+        ));
+    }
+    let length_field = length_field_path(container.length_field.unwrap_or(32));
+    let selector_field = container
+        .selector_field
+        .clone()
+        .unwrap_or(quote!(::someip_wire::serialize::SelectorField::U32));
+    let padding = container
+        .padding
+        .map(|padding| quote!(.with_padding(#padding)));
+    Ok(quote! {
+        ::someip_wire::serialize::UnionFormat::new(#length_field, #selector_field) #padding
+    })
+}
+
+/// Returns the selector of every variant, counting from 1 by default.
+///
+/// Selectors must be unique, and 0 is reserved for a union without a member.
+fn selectors(data: &DataEnum) -> Result<Vec<u32>> {
+    let mut selectors = Vec::new();
+    for (index, variant) in data.variants.iter().enumerate() {
+        let attrs = attr::Variant::parse(&variant.attrs)?;
+        if !matches!(&variant.fields, Fields::Unit)
+            && !matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1)
+        {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "union variants must be unit variants or hold exactly one unnamed field",
+            ));
+        }
+        let selector = attrs.selector.unwrap_or(index as u32 + 1);
+        if selector == 0 {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "selector 0 is reserved for a union without a member",
+            ));
+        }
+        if selectors.contains(&selector) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("selector {} is used by another variant", selector),
+            ));
+        }
+        selectors.push(selector);
+    }
+    Ok(selectors)
+}
+
+fn serialize_union(container: &attr::Container, data: &DataEnum) -> Result<TokenStream> {
+    let format = union_format(container)?;
+    let selectors = selectors(data)?;
+    let mut selector_arms = Vec::new();
+    let mut member_arms = Vec::new();
+    for (variant, selector) in data.variants.iter().zip(selectors) {
+        let ident = &variant.ident;
+        match variant.fields {
+            Fields::Unit => {
+                selector_arms.push(quote!(Self::#ident => #selector,));
+                member_arms.push(quote!(Self::#ident => ::core::result::Result::Ok(()),));
+            }
+            _ => {
+                selector_arms.push(quote!(Self::#ident(_) => #selector,));
+                member_arms.push(quote!(Self::#ident(value) => writer.write(value),));
+            }
+        }
+    }
+    Ok(quote! {
+        let selector = match self {
+            #(#selector_arms)*
+        };
+        #format.write(writer, selector, |writer| match self {
+            #(#member_arms)*
+        })
+    })
+}
+
+fn deserialize_union(container: &attr::Container, data: &DataEnum) -> Result<TokenStream> {
+    let format = union_format(container)?;
+    let selectors = selectors(data)?;
+    let arms = data
+        .variants
+        .iter()
+        .zip(selectors)
+        .map(|(variant, selector)| {
+            let ident = &variant.ident;
+            match variant.fields {
+                Fields::Unit => quote!(#selector => ::core::result::Result::Ok(Self::#ident),),
+                _ => quote!(#selector => ::core::result::Result::Ok(Self::#ident(reader.read()?)),),
+            }
+        });
+    Ok(quote! {
+        #format.read(reader, |selector, reader| match selector {
+            #(#arms)*
+            _ => ::core::result::Result::Err(
+                ::someip_wire::error::Error::InvalidSelector(selector),
+            ),
+        })
+    })
+}
@@ -0,0 +1,126 @@
+//! # SOME/IP-wire derive
+//!
+//! Derive macros implementing the `SomeIpSerialize` and `SomeIpDeserialize` traits of
+//! [`someip-wire`](https://docs.rs/someip-wire) for payload types. Enable the `derive`
+//! feature of `someip-wire` rather than depending on this crate directly.
+//!
+//! ## Structs
+//!
+//! Fields are serialized in declaration order. Field attributes:
+//!
+//! - `#[someip(length_field = 0 | 8 | 16 | 32)]`: precede the field with a length field,
+//!   for dynamic length arrays, strings and structs
+//! - `#[someip(align = 8 | 16 | 32 | 64)]`: pad before the field so that it starts at a
+//!   boundary of the given bits
+//!
+//! Container attributes:
+//!
+//! - `#[someip(length_field = ..)]`: precede the whole struct with a length field
+//! - `#[someip(align = 8 | 16 | 32 | 64)]`: pad after every field with a length field
+//!   so that the next field starts at a boundary of the given bits
+//! - `#[someip(padding = N)]`: pad the struct to `N` bytes, such as the elements of an
+//!   array of structs
+//!
+//! With `#[someip(tlv)]`, every field is TLV encoded and requires a data ID,
+//! `#[someip(id = N)]`. The wire type follows from the type of the field: base data
+//! types use wire types 0 to 3, all other types a length field of the width given by
+//! `length_field`, 32 bits by default. `#[someip(wire_type = N)]` overrides it. Fields of
+//! type `Option<T>` are optional; missing required members fail deserialization. Data IDs
+//! must be unique within the struct.
+//!
+//! ## Enums
+//!
+//! Enums with only unit variants are serialized as their discriminant, using the base
+//! type given by `#[someip(base = u8 | u16 | u32)]`, `u8` by default. Every discriminant
+//! must fit the base type:
+//!
+//! ```compile_fail
+//! use someip_wire::serialize::SomeIpSerialize;
+//!
+//! #[derive(SomeIpSerialize)]
+//! #[someip(base = u8)]
+//! enum Gear {
+//!     Park = 0,
+//!     Drive = 300,
+//! }
+//! ```
+//!
+//! All other enums are serialized as unions. Every variant is a unit variant or holds
+//! exactly one unnamed field, and has a unique selector value given by
+//! `#[someip(selector = N)]` or counting from 1. Selector 0 is reserved for a union
+//! without a member. Container attributes: `length_field` (32 bits by default),
+//! `selector_field = 8 | 16 | 32` (32 bits by default) and `padding = N`.
+//!
+//! ```compile_fail
+//! use someip_wire::serialize::SomeIpSerialize;
+//!
+//! #[derive(SomeIpSerialize)]
+//! enum Value {
+//!     Small(u8),
+//!     #[someip(selector = 1)]
+//!     Large(u32),
+//! }
+//! ```
+//!
+//! ## Example
+//!
+//! ```rust
+//! use someip_wire::serialize::{self, SomeIpDeserialize, SomeIpSerialize};
+//!
+//! #[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+//! #[someip(base = u8)]
+//! enum Gear {
+//!     Park = 0,
+//!     Drive = 3,
+//! }
+//!
+//! #[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+//! struct Status<'a> {
+//!     gear: Gear,
+//!     speed: u16,
+//!     #[someip(length_field = 8)]
+//!     driver: &'a str,
+//! }
+//!
+//! let status = Status { gear: Gear::Drive, speed: 50, driver: "Kim" };
+//! let mut buffer = [0u8; 16];
+//! let len = serialize::to_slice(&status, &mut buffer).unwrap();
+//! assert_eq!(&buffer[..len], &[3, 0, 50, 7, 0xEF, 0xBB, 0xBF, b'K', b'i', b'm', 0]);
+//! assert_eq!(serialize::from_slice::<Status>(&buffer[..len]), Ok(status));
+//! ```
+//!
+//! Like length fields, alignments are given in bits; any other value is rejected:
+//!
+//! ```compile_fail
+//! use someip_wire::serialize::SomeIpSerialize;
+//!
+//! #[derive(SomeIpSerialize)]
+//! struct Status {
+//!     #[someip(align = 4)]
+//!     speed: u16,
+//! }
+//! ```
+
+mod attr;
+mod expand;
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, parse_macro_input};
+
+/// Derives `someip_wire::serialize::SomeIpSerialize`.
+#[proc_macro_derive(SomeIpSerialize, attributes(someip))]
+pub fn derive_serialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::serialize(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `someip_wire::serialize::SomeIpDeserialize`.
+#[proc_macro_derive(SomeIpDeserialize, attributes(someip))]
+pub fn derive_deserialize(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand::deserialize(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
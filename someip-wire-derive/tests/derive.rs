@@ -0,0 +1,269 @@
+use someip_wire::error::Error;
+use someip_wire::serialize::{self, ArrayRef, Reader, SomeIpDeserialize, SomeIpSerialize};
+
+#[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+#[someip(base = u16)]
+enum Mode {
+    Off,
+    Eco = 0x0100,
+    Sport,
+}
+
+#[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+struct Point(i16, i16);
+
+#[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+#[someip(length_field = 16)]
+struct Vehicle<'a> {
+    mode: Mode,
+    active: bool,
+    #[someip(length_field = 8)]
+    name: &'a str,
+    #[someip(align = 64)]
+    position: Point,
+    #[someip(length_field = 32)]
+    raw: &'a [u8],
+}
+
+#[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+struct Wrapper<T> {
+    value: T,
+}
+
+#[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+struct Grid<'a> {
+    corners: [Point; 2],
+    #[someip(length_field = 16)]
+    cells: ArrayRef<'a, u16>,
+}
+
+#[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+#[someip(align = 32, padding = 12)]
+struct Entry<'a> {
+    #[someip(length_field = 8)]
+    data: &'a [u8],
+    id: u16,
+}
+
+#[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+#[someip(tlv)]
+struct Settings<'a> {
+    #[someip(id = 1)]
+    volume: u8,
+    #[someip(id = 2, length_field = 16)]
+    station: &'a str,
+    #[someip(id = 3)]
+    balance: Option<i16>,
+}
+
+#[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+struct Sample {
+    flag: u8,
+    #[someip(align = 32)]
+    value: u16,
+}
+
+#[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+#[someip(tlv)]
+struct Recording {
+    #[someip(id = 1)]
+    volume: u8,
+    #[someip(id = 2, length_field = 8)]
+    sample: Sample,
+}
+
+#[derive(Debug, PartialEq, SomeIpSerialize, SomeIpDeserialize)]
+#[someip(length_field = 8, selector_field = 8, padding = 4)]
+enum Value {
+    Empty,
+    Small(u8),
+    #[someip(selector = 10)]
+    Large(u32),
+}
+
+#[test]
+fn test_c_like_enum() {
+    let mut buffer = [0u8; 2];
+    assert_eq!(serialize::to_slice(&Mode::Eco, &mut buffer), Ok(2));
+    assert_eq!(buffer, [0x01, 0x00]);
+    assert_eq!(serialize::from_slice(&[0x01, 0x01]), Ok(Mode::Sport));
+    assert_eq!(serialize::from_slice(&[0x00, 0x00]), Ok(Mode::Off));
+    assert_eq!(
+        serialize::from_slice::<Mode>(&[0x00, 0x01]),
+        Err(Error::InvalidEnumValue(1))
+    );
+}
+
+#[test]
+fn test_struct() {
+    let vehicle = Vehicle {
+        mode: Mode::Sport,
+        active: true,
+        name: "ab",
+        position: Point(-1, 2),
+        raw: &[0xAA, 0xBB],
+    };
+    let mut buffer = [0xFFu8; 32];
+    let len = serialize::to_slice(&vehicle, &mut buffer).unwrap();
+    assert_eq!(
+        &buffer[..len],
+        &[
+            0x00, 0x18, // struct length field
+            0x01, 0x01, // mode
+            0x01, // active
+            0x06, 0xEF, 0xBB, 0xBF, 0x61, 0x62, 0x00, // name
+            0x00, 0x00, 0x00, 0x00, // padding to 8 bytes within the payload
+            0xFF, 0xFF, 0x00, 0x02, // position
+            0x00, 0x00, 0x00, 0x02, 0xAA, 0xBB, // raw
+        ][..]
+    );
+    assert_eq!(serialize::from_slice(&buffer[..len]), Ok(vehicle));
+    assert_eq!(
+        serialize::from_slice::<Vehicle>(&buffer[..len - 1]),
+        Err(Error::Truncated)
+    );
+
+    let wrapper = Wrapper { value: 0x1234u16 };
+    assert_eq!(serialize::to_slice(&wrapper, &mut buffer), Ok(2));
+    assert_eq!(serialize::from_slice(&buffer[..2]), Ok(wrapper));
+}
+
+#[test]
+fn test_tlv_struct() {
+    let settings = Settings {
+        volume: 7,
+        station: "X",
+        balance: None,
+    };
+    let mut buffer = [0u8; 32];
+    let len = serialize::to_slice(&settings, &mut buffer).unwrap();
+    assert_eq!(
+        &buffer[..len],
+        &[
+            0x00, 0x01, 0x07, // volume
+            0x60, 0x02, 0x00, 0x05, 0xEF, 0xBB, 0xBF, 0x58, 0x00, // station
+        ][..]
+    );
+    assert_eq!(serialize::from_slice(&buffer[..len]), Ok(settings));
+
+    // Members in a different order, with an unknown member added by a newer version
+    let buffer = [
+        0x10, 0x03, 0xFF, 0xFE, // balance
+        0x20, 0x09, 0x00, 0x00, 0x00, 0x01, // unknown data ID 9
+        0x60, 0x02, 0x00, 0x04, 0xEF, 0xBB, 0xBF, 0x00, // station
+        0x00, 0x01, 0x03, // volume
+    ];
+    assert_eq!(
+        serialize::from_slice(&buffer),
+        Ok(Settings {
+            volume: 3,
+            station: "",
+            balance: Some(-2),
+        })
+    );
+    assert_eq!(
+        serialize::from_slice::<Settings>(&buffer[..10]),
+        Err(Error::MissingMember(1))
+    );
+}
+
+#[test]
+fn test_tlv_member_alignment() {
+    let recording = Recording {
+        volume: 7,
+        sample: Sample {
+            flag: 1,
+            value: 0x1234,
+        },
+    };
+    let mut buffer = [0u8; 16];
+    let len = serialize::to_slice(&recording, &mut buffer).unwrap();
+    assert_eq!(
+        &buffer[..len],
+        &[
+            0x00, 0x01, 0x07, // volume
+            0x50, 0x02, 0x04, 0x01, 0x00, 0x12, 0x34, // sample, aligned within the payload
+        ][..]
+    );
+    assert_eq!(serialize::from_slice(&buffer[..len]), Ok(recording));
+}
+
+#[test]
+fn test_union_enum() {
+    let mut buffer = [0u8; 16];
+    let len = serialize::to_slice(&Value::Small(5), &mut buffer).unwrap();
+    assert_eq!(&buffer[..len], &[0x04, 0x02, 0x05, 0x00, 0x00, 0x00]);
+    assert_eq!(serialize::from_slice(&buffer[..len]), Ok(Value::Small(5)));
+
+    let len = serialize::to_slice(&Value::Large(1), &mut buffer).unwrap();
+    assert_eq!(&buffer[..len], &[0x04, 0x0A, 0x00, 0x00, 0x00, 0x01]);
+    assert_eq!(serialize::from_slice(&buffer[..len]), Ok(Value::Large(1)));
+
+    let len = serialize::to_slice(&Value::Empty, &mut buffer).unwrap();
+    assert_eq!(&buffer[..len], &[0x04, 0x01, 0x00, 0x00, 0x00, 0x00]);
+    assert_eq!(serialize::from_slice(&buffer[..len]), Ok(Value::Empty));
+
+    assert_eq!(
+        serialize::from_slice::<Value>(&[0x00, 0x03]),
+        Err(Error::InvalidSelector(3))
+    );
+}
+
+#[test]
+fn test_alignment() {
+    let entries = [
+        Entry {
+            data: &[0xAA],
+            id: 1,
+        },
+        Entry {
+            data: &[0xBB, 0xCC, 0xDD, 0xEE],
+            id: 2,
+        },
+    ];
+    let mut buffer = [0xFFu8; 24];
+    assert_eq!(serialize::to_slice(&entries[..], &mut buffer), Ok(24));
+    assert_eq!(
+        buffer,
+        [
+            0x01, 0xAA, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // first
+            0x04, 0xBB, 0xCC, 0xDD, 0xEE, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, // second
+        ]
+    );
+
+    let mut reader = Reader::new(&buffer);
+    assert_eq!(
+        reader.read(),
+        Ok(Entry {
+            data: &[0xAA],
+            id: 1
+        })
+    );
+    assert_eq!(reader.position(), 12);
+    assert_eq!(reader.read::<Entry>().unwrap().id, 2);
+    assert!(reader.is_empty());
+
+    let oversized = Entry {
+        data: &[0; 8],
+        id: 3,
+    };
+    assert_eq!(
+        serialize::to_slice(&oversized, &mut buffer),
+        Err(Error::PaddingOverflow(14))
+    );
+}
+
+#[test]
+fn test_arrays() {
+    let payload = [
+        0x00, 0x01, 0x00, 0x02, 0xFF, 0xFF, 0xFF, 0xFE, // corners
+        0x00, 0x04, 0x00, 0x0A, 0x00, 0x0B, // cells
+    ];
+    let grid: Grid = serialize::from_slice(&payload).unwrap();
+    assert_eq!(grid.corners, [Point(1, 2), Point(-1, -2)]);
+    assert!(grid.cells.iter().eq([0x0A, 0x0B]));
+
+    let mut buffer = [0u8; 16];
+    assert_eq!(serialize::to_slice(&grid, &mut buffer), Ok(payload.len()));
+    assert_eq!(&buffer[..payload.len()], &payload);
+}
@@ -24,6 +24,110 @@ pub enum Error {
     ///
     /// Valid values are defined in the SOME/IP specification.
     InvalidReturnCode(u8),
+
+    /// The length of the SOME/IP-SD entries array is not a multiple of the entry size.
+    ///
+    /// Every SD entry is exactly 16 bytes long.
+    InvalidEntriesLength(u32),
+
+    /// The length field of a SOME/IP-SD option is not valid.
+    ///
+    /// The length field must at least cover the reserved byte.
+    InvalidOptionLength(u16),
+
+    /// The entry type byte is not a valid SOME/IP-SD entry type for the expected entry.
+    ///
+    /// Valid values are defined in the SOME/IP-SD specification.
+    InvalidEntryType(u8),
+
+    /// The L4 protocol byte of a SOME/IP-SD endpoint option is neither TCP nor UDP.
+    InvalidL4Protocol(u8),
+
+    /// A string of a SOME/IP-SD configuration option is malformed.
+    ///
+    /// Keys must be non-empty printable US-ASCII without `=`, values must be UTF-8,
+    /// and every string must fit its 8-bit length prefix.
+    InvalidConfigurationString,
+
+    /// An SD entry references options beyond the end of the options array.
+    ///
+    /// Carries the index of the first option of the offending option run.
+    OptionIndexOutOfBounds(u8),
+
+    /// The caller-provided storage of a state machine has no free slot left.
+    StorageFull,
+
+    /// An SD entry cannot be placed into a message.
+    ///
+    /// Each option run holds at most 15 options, and an entry together with its
+    /// options must fit into a single message of the configured size.
+    EntryTooLarge,
+
+    /// The length of a SOME/IP-TP segment is not valid.
+    ///
+    /// All segments except the last one must carry a multiple of 16 bytes.
+    InvalidSegmentLength(u32),
+
+    /// A message reassembled from SOME/IP-TP segments exceeds the maximum size.
+    ///
+    /// Carries the length required by the offending segment.
+    MessageTooLarge(u32),
+
+    /// SOME/IP-TP segments of the same message contradict each other.
+    ///
+    /// For example, a segment reaches beyond the end announced by the last segment.
+    InconsistentSegments,
+
+    /// A serialized boolean is neither 0 nor 1.
+    InvalidBoolean(u8),
+
+    /// Serialized data does not fit the configured length field or fixed length.
+    ///
+    /// Carries the length of the data in bytes.
+    LengthFieldOverflow(u32),
+
+    /// A serialized string is malformed.
+    ///
+    /// Strings must start with a byte order mark and contain valid UTF-8 or UTF-16.
+    InvalidString,
+
+    /// The type selector of a union does not identify a known member.
+    InvalidSelector(u32),
+
+    /// The data ID of a TLV member does not fit the 12 bits of the tag.
+    InvalidDataId(u16),
+
+    /// A TLV member does not match its wire type.
+    ///
+    /// Base data types must have the size given by the wire type, and wire type 4
+    /// requires a configured length field.
+    InvalidWireType(u8),
+
+    /// A serialized enumeration value does not match any variant.
+    InvalidEnumValue(u32),
+
+    /// A required member of a TLV encoded struct is missing.
+    ///
+    /// Carries the data ID of the member.
+    MissingMember(u16),
+
+    /// A physical value is outside of its valid range or not representable by its raw
+    /// value.
+    ValueOutOfRange,
+
+    /// The shape of a multidimensional array does not match its elements or its
+    /// configured dimensions.
+    InvalidArrayShape,
+
+    /// Serialized data exceeds the fixed size it is padded to.
+    ///
+    /// Carries the length of the data in bytes.
+    PaddingOverflow(u32),
+
+    /// The data of a SOME/IP-SD option does not fit the 16-bit option length field.
+    ///
+    /// Carries the length of the option data in bytes.
+    OptionTooLarge(u32),
 }
 
 impl fmt::Display for Error {
@@ -41,6 +145,75 @@ impl fmt::Display for Error {
             Error::InvalidReturnCode(byte) => {
                 write!(f, "invalid return code: 0x{:02X}", byte)
             }
+            Error::InvalidEntriesLength(length) => {
+                write!(f, "invalid SD entries array length: {}", length)
+            }
+            Error::InvalidOptionLength(length) => {
+                write!(f, "invalid SD option length: {}", length)
+            }
+            Error::InvalidEntryType(byte) => {
+                write!(f, "invalid SD entry type: 0x{:02X}", byte)
+            }
+            Error::InvalidL4Protocol(byte) => {
+                write!(f, "invalid L4 protocol: 0x{:02X}", byte)
+            }
+            Error::InvalidConfigurationString => {
+                write!(f, "invalid SD configuration string")
+            }
+            Error::OptionIndexOutOfBounds(index) => {
+                write!(f, "SD option run starting at index {} is out of bounds", index)
+            }
+            Error::StorageFull => {
+                write!(f, "no free slot left in the provided storage")
+            }
+            Error::EntryTooLarge => {
+                write!(f, "SD entry does not fit into a single message")
+            }
+            Error::InvalidSegmentLength(length) => {
+                write!(f, "invalid TP segment length: {}", length)
+            }
+            Error::MessageTooLarge(length) => {
+                write!(f, "reassembled TP message too large: {} bytes", length)
+            }
+            Error::InconsistentSegments => {
+                write!(f, "inconsistent TP segments")
+            }
+            Error::InvalidBoolean(byte) => {
+                write!(f, "invalid boolean value: 0x{:02X}", byte)
+            }
+            Error::LengthFieldOverflow(length) => {
+                write!(f, "{} bytes exceed the length field", length)
+            }
+            Error::InvalidString => {
+                write!(f, "malformed string")
+            }
+            Error::InvalidSelector(selector) => {
+                write!(f, "invalid union selector: {}", selector)
+            }
+            Error::InvalidDataId(data_id) => {
+                write!(f, "invalid TLV data ID: 0x{:04X}", data_id)
+            }
+            Error::InvalidWireType(wire_type) => {
+                write!(f, "invalid TLV wire type: {}", wire_type)
+            }
+            Error::InvalidEnumValue(value) => {
+                write!(f, "invalid enumeration value: {}", value)
+            }
+            Error::MissingMember(data_id) => {
+                write!(f, "missing TLV member with data ID 0x{:04X}", data_id)
+            }
+            Error::ValueOutOfRange => {
+                write!(f, "value out of range")
+            }
+            Error::InvalidArrayShape => {
+                write!(f, "invalid array shape")
+            }
+            Error::PaddingOverflow(length) => {
+                write!(f, "{} bytes exceed the padded size", length)
+            }
+            Error::OptionTooLarge(length) => {
+                write!(f, "{} bytes exceed the option length field", length)
+            }
         }
     }
 }
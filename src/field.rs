@@ -35,3 +35,201 @@ pub mod header {
     /// Length of the SOME/IP header (16 bytes, excluding payload)
     pub const HEADER_LENGTH: usize = RETURN_CODE.end;
 }
+
+/// SOME/IP-SD header field definitions.
+///
+/// This module contains constants defining the byte ranges for each field
+/// in the SOME/IP-SD header. Offsets are relative to the start of the
+/// SOME/IP payload.
+pub mod sd {
+    use crate::field::Field;
+
+    /// Flags field (byte 0): Reboot, Unicast and Explicit Initial Data Control flags
+    pub const FLAGS: Field = 0..1;
+    /// Reserved field (bytes 1-3)
+    pub const RESERVED: Field = 1..4;
+    /// Length of entries array field (bytes 4-7): Length of the entries array in bytes
+    pub const ENTRIES_LENGTH: Field = 4..8;
+
+    /// Reboot flag bit within the flags field
+    pub const REBOOT_FLAG: u8 = 0x80;
+    /// Unicast flag bit within the flags field
+    pub const UNICAST_FLAG: u8 = 0x40;
+    /// Explicit Initial Data Control flag bit within the flags field
+    pub const EXPLICIT_INITIAL_DATA_CONTROL_FLAG: u8 = 0x20;
+
+    /// Get the entries array field range given the length of the entries array
+    pub const fn entries(entries_length: usize) -> Field {
+        ENTRIES_LENGTH.end..(ENTRIES_LENGTH.end + entries_length)
+    }
+
+    /// Get the length of options array field range given the length of the entries array
+    pub const fn options_length(entries_length: usize) -> Field {
+        let start = entries(entries_length).end;
+        start..(start + 4)
+    }
+
+    /// Get the options array field range given the lengths of the entries and options arrays
+    pub const fn options(entries_length: usize, options_length: usize) -> Field {
+        let start = self::options_length(entries_length).end;
+        start..(start + options_length)
+    }
+
+    /// Length of the SOME/IP-SD header with empty entries and options arrays (12 bytes)
+    pub const HEADER_LENGTH: usize = options_length(0).end;
+}
+
+/// SOME/IP-SD entry field definitions.
+///
+/// Offsets are relative to the start of a single 16-byte entry.
+pub mod sd_entry {
+    use crate::field::Field;
+
+    /// Type field (byte 0): FindService, OfferService, SubscribeEventgroup, etc.
+    pub const TYPE: Field = 0..1;
+    /// Index of first option run (byte 1)
+    pub const INDEX_FIRST_OPTIONS: Field = 1..2;
+    /// Index of second option run (byte 2)
+    pub const INDEX_SECOND_OPTIONS: Field = 2..3;
+    /// Number of options field (byte 3): first run in the high nibble, second run in the low nibble
+    pub const NUM_OPTIONS: Field = 3..4;
+    /// Service ID field (bytes 4-5)
+    pub const SERVICE_ID: Field = 4..6;
+    /// Instance ID field (bytes 6-7)
+    pub const INSTANCE_ID: Field = 6..8;
+    /// Major Version field (byte 8)
+    pub const MAJOR_VERSION: Field = 8..9;
+    /// TTL field (bytes 9-11): Lifetime of the entry in seconds
+    pub const TTL: Field = 9..12;
+    /// Minor Version field (bytes 12-15): Only present in service entries
+    pub const MINOR_VERSION: Field = 12..16;
+    /// Reserved field (byte 12): Only present in eventgroup entries
+    pub const RESERVED: Field = 12..13;
+    /// Flags and Counter field (byte 13): Initial Data Requested flag and 4-bit counter
+    pub const COUNTER: Field = 13..14;
+    /// Eventgroup ID field (bytes 14-15): Only present in eventgroup entries
+    pub const EVENTGROUP_ID: Field = 14..16;
+
+    /// Initial Data Requested flag bit within the counter field
+    pub const INITIAL_DATA_REQUESTED_FLAG: u8 = 0x80;
+    /// Counter bits within the counter field
+    pub const COUNTER_MASK: u8 = 0x0F;
+
+    /// Length of an SD entry (16 bytes)
+    pub const ENTRY_LENGTH: usize = 16;
+}
+
+/// SOME/IP-SD option field definitions.
+///
+/// Offsets are relative to the start of a single option.
+pub mod sd_option {
+    use crate::field::Field;
+
+    /// Length field (bytes 0-1): Length of the option in bytes, starting after the type field
+    pub const LENGTH: Field = 0..2;
+    /// Type field (byte 2): Configuration, IPv4 Endpoint, etc.
+    pub const TYPE: Field = 2..3;
+    /// Reserved field (byte 3): Holds the discardable flag in its highest bit
+    pub const RESERVED: Field = 3..4;
+
+    /// Discardable flag bit within the reserved field
+    pub const DISCARDABLE_FLAG: u8 = 0x80;
+
+    /// Get the option data field range given the value of the length field
+    ///
+    /// The length field covers the reserved byte, so the data is one byte shorter.
+    pub const fn data(length: usize) -> Field {
+        RESERVED.end..(RESERVED.end + length.saturating_sub(1))
+    }
+
+    /// Length of the option header up to and including the reserved field (4 bytes)
+    pub const HEADER_LENGTH: usize = RESERVED.end;
+}
+
+/// SOME/IP-SD load balancing option field definitions.
+///
+/// Offsets are relative to the option data, i.e. the bytes following the reserved field
+/// of the option header.
+pub mod sd_load_balancing_option {
+    use crate::field::Field;
+
+    /// Priority field (bytes 0-1): Lower values mean higher priority
+    pub const PRIORITY: Field = 0..2;
+    /// Weight field (bytes 2-3): Relative weight among instances of equal priority
+    pub const WEIGHT: Field = 2..4;
+
+    /// Value of the option length field (reserved byte + 4 data bytes)
+    pub const LENGTH: u16 = 0x0005;
+}
+
+/// SOME/IP-SD IPv4 endpoint, multicast and SD endpoint option field definitions.
+///
+/// Offsets are relative to the option data, i.e. the bytes following the reserved field
+/// of the option header.
+pub mod sd_ipv4_option {
+    use crate::field::Field;
+
+    /// IPv4 Address field (bytes 0-3)
+    pub const ADDRESS: Field = 0..4;
+    /// Reserved field (byte 4)
+    pub const RESERVED: Field = 4..5;
+    /// L4 Protocol field (byte 5): TCP or UDP
+    pub const L4_PROTOCOL: Field = 5..6;
+    /// Port Number field (bytes 6-7)
+    pub const PORT: Field = 6..8;
+
+    /// Value of the option length field (reserved byte + 8 data bytes)
+    pub const LENGTH: u16 = 0x0009;
+}
+
+/// SOME/IP-SD IPv6 endpoint, multicast and SD endpoint option field definitions.
+///
+/// Offsets are relative to the option data, i.e. the bytes following the reserved field
+/// of the option header.
+pub mod sd_ipv6_option {
+    use crate::field::Field;
+
+    /// IPv6 Address field (bytes 0-15)
+    pub const ADDRESS: Field = 0..16;
+    /// Reserved field (byte 16)
+    pub const RESERVED: Field = 16..17;
+    /// L4 Protocol field (byte 17): TCP or UDP
+    pub const L4_PROTOCOL: Field = 17..18;
+    /// Port Number field (bytes 18-19)
+    pub const PORT: Field = 18..20;
+
+    /// Value of the option length field (reserved byte + 20 data bytes)
+    pub const LENGTH: u16 = 0x0015;
+}
+
+/// SOME/IP-TP header field definitions.
+///
+/// Offsets are relative to the start of the SOME/IP payload of a TP segment.
+pub mod tp {
+    use crate::field::Field;
+
+    /// Offset field (bytes 0-3): 28-bit offset in units of 16 bytes, 3 reserved bits
+    /// and the More Segments flag
+    pub const OFFSET: Field = 0..4;
+
+    /// Mask of the offset within the offset field, yielding the offset in bytes
+    pub const OFFSET_MASK: u32 = 0xFFFF_FFF0;
+    /// Mask of the reserved bits within the offset field
+    pub const RESERVED_MASK: u32 = 0x0000_000E;
+    /// More Segments flag bit within the offset field
+    pub const MORE_SEGMENTS_FLAG: u32 = 0x0000_0001;
+
+    /// TP flag bit within the SOME/IP message type field
+    pub const TP_FLAG: u8 = 0x20;
+
+    /// Unit of the offset and of the length of all but the last segment (16 bytes)
+    pub const OFFSET_UNIT: usize = 16;
+
+    /// Get the segment data field range given the length of the segment data
+    pub const fn segment(length: usize) -> Field {
+        OFFSET.end..(OFFSET.end + length)
+    }
+
+    /// Length of the SOME/IP-TP header (4 bytes)
+    pub const HEADER_LENGTH: usize = OFFSET.end;
+}
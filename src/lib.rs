@@ -21,10 +21,22 @@
 //! This separation keeps the crate focused, lightweight, and universally applicable
 //! across different SOME/IP service implementations.
 //!
+//! The one payload format that is defined by the protocol itself, SOME/IP Service
+//! Discovery (Message ID `0xFFFF.8100`), is supported through the `sd` module.
+//! Likewise, the segmentation header of the SOME/IP Transport Protocol, which precedes
+//! the payload of messages with the TP flag set, is handled by the `tp` module.
+//!
+//! `Repr::data` always stays raw. To write payload handlers on top of it, the
+//! `serialize` module provides the SOME/IP serialization rules for the basic data types.
+//! Payloads with a fixed layout can instead be accessed in place through views declared
+//! with the `payload_view!` macro.
+//!
 //! ## Features
 //!
 //! - `no_std` compatible by default
 //! - Zero-allocation parsing and serialization
+//! - Optional `alloc` feature for growable SOME/IP-TP reassembly buffers
+//! - Optional `derive` feature for `#[derive(SomeIpSerialize, SomeIpDeserialize)]`
 //! - Support for all SOME/IP message types
 //! - Clean enum-based API for return codes and message types
 //! - Wire format using simple u8 for efficiency
@@ -143,6 +155,9 @@
 //! - `packet`: Contains the `Packet` type for low-level packet access (wire format)
 //! - `prelude`: Re-exports commonly used types for convenient imports
 //! - `repr`: Contains the `Repr` type for high-level SOME/IP representation
+//! - `serialize`: Contains the `SomeIpSerialize` and `SomeIpDeserialize` traits for payload data
+//! - `sd`: Contains the `SdPacket` and `SdRepr` types for SOME/IP Service Discovery payloads
+//! - `tp`: Contains the `TpPacket` and `TpRepr` types for SOME/IP-TP segments
 //! - `types`: Contains SOME/IP type definitions (MessageId, RequestId, ReturnCode, MessageType)
 //!
 //! ## Architecture
@@ -157,6 +172,9 @@
 #![cfg_attr(not(test), no_std)]
 #![warn(missing_docs)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 /// Error types for SOME/IP packet parsing and serialization.
 pub mod error;
 /// Field definitions and byte ranges for the SOME/IP header.
@@ -167,6 +185,12 @@ pub mod packet;
 pub mod prelude;
 /// High-level SOME/IP message representation.
 pub mod repr;
+/// SOME/IP Service Discovery (SOME/IP-SD) payload parsing and serialization.
+pub mod sd;
+/// SOME/IP payload serialization of service interface data types.
+pub mod serialize;
+/// SOME/IP Transport Protocol (SOME/IP-TP) segmentation.
+pub mod tp;
 /// SOME/IP type definitions (MessageId, RequestId, MessageType, ReturnCode).
 pub mod types;
 
@@ -157,7 +157,7 @@ impl<T: AsRef<[u8]>> Packet<T> {
     /// * `Range<usize>` - The range of the payload data.
     pub fn payload_data_range(&self) -> core::ops::Range<usize> {
         let payload_len = self.payload_length().saturating_sub(8); // Subtract 8 header bytes from SOME/IP length field
-        field::header::RETURN_CODE.end..field::header::RETURN_CODE.end + payload_len as usize
+        field::header::RETURN_CODE.end..field::header::RETURN_CODE.end + payload_len
     }
 
     /// Returns the length of the payload data.
@@ -166,7 +166,7 @@ impl<T: AsRef<[u8]>> Packet<T> {
     ///
     /// * `usize` - The length of the payload data.
     pub fn payload_data_length(&self) -> usize {
-        self.payload_length().saturating_sub(8) as usize // Subtract 8 header bytes from SOME/IP length field
+        self.payload_length().saturating_sub(8) // Subtract 8 header bytes from SOME/IP length field
     }
 }
 
@@ -268,7 +268,7 @@ impl<'a, T: AsRef<[u8]> + ?Sized> Packet<&'a T> {
 }
 
 #[allow(dead_code)]
-impl<'a, T: AsRef<[u8]> + AsMut<[u8]> + ?Sized> Packet<&'a mut T> {
+impl<T: AsRef<[u8]> + AsMut<[u8]> + ?Sized> Packet<&mut T> {
     /// Returns a mutable reference to the payload data,
     ///
     /// # Returns
@@ -294,7 +294,7 @@ impl<'a, T: AsRef<[u8]> + AsMut<[u8]> + ?Sized> Packet<&'a mut T> {
     }
 }
 
-impl<'a, T: AsRef<[u8]> + ?Sized> fmt::Display for Packet<&'a T> {
+impl<T: AsRef<[u8]> + ?Sized> fmt::Display for Packet<&T> {
     /// Formats the packet as a string
     ///
     /// # Arguments
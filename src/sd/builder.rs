@@ -0,0 +1,293 @@
+//! SD message builder module
+//!
+//! This module contains the `MessageBuilder`, which collects SD entries together with
+//! their options and packs them into as few SD messages as fit a maximum message size.
+
+use crate::error::Error;
+use crate::field;
+use crate::sd::entry::{EntryPacket, EntryRepr, OptionRun};
+use crate::sd::option::{OptionPacket, OptionRepr};
+use crate::sd::repr::SdRepr;
+
+/// An entry waiting to be placed into a message, together with its options.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PendingEntry<'o> {
+    /// The entry; its option runs are replaced when it is placed into a message
+    pub entry: EntryRepr,
+    /// Options referenced by the first option run (at most 15)
+    pub first_options: &'o [OptionRepr<'o>],
+    /// Options referenced by the second option run (at most 15)
+    pub second_options: &'o [OptionRepr<'o>],
+}
+
+/// Packs SD entries and their options into messages of a limited size.
+///
+/// Entries are queued with [`MessageBuilder::push`] and taken out again, message by
+/// message, with [`MessageBuilder::next_message`]. Entries are sent in the order they
+/// were queued: a message ends at the first entry that does not fit into it, even if
+/// later entries would.
+///
+/// Within a message, identical options are shared between entries where the option runs
+/// allow it. A run is a contiguous range of the options array, so a run that already
+/// appears in the array is referenced as is, and a run starting with the options at the
+/// end of the array only appends its remaining options. Other options are emitted again,
+/// e.g. the runs `[A, B]` and `[A, C]` need four options.
+///
+/// The maximum length is the size of the UDP payload, i.e. it includes the SOME/IP
+/// header and the SD header. Entries are kept in caller-provided storage; a second
+/// caller-provided slice holds the options of the message being built.
+///
+/// # Example
+///
+/// ```rust
+/// use core::net::Ipv4Addr;
+/// use someip_wire::sd::builder::MessageBuilder;
+/// use someip_wire::sd::{Ipv4EndpointRepr, L4Protocol, OptionRepr, ServiceEntryRepr};
+///
+/// let endpoint = [OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+///     address: Ipv4Addr::new(192, 168, 0, 1),
+///     protocol: L4Protocol::Udp,
+///     port: 30000,
+/// })];
+/// let mut entries = [None; 8];
+/// let mut options = [None; 8];
+/// let mut builder = MessageBuilder::new(1400, &mut entries, &mut options);
+/// for instance_id in 1..=3 {
+///     let offer = ServiceEntryRepr::offer_service(0x1234, instance_id, 1, 0, 3);
+///     builder.push(offer.into(), &endpoint, &[]).unwrap();
+/// }
+///
+/// let mut buffer = [0u8; 1400];
+/// let message = builder.next_message(&mut buffer).unwrap().unwrap();
+/// assert_eq!(message.entries().count(), 3);
+/// assert_eq!(message.options().count(), 1);
+/// assert!(builder.is_empty());
+/// ```
+#[derive(Debug)]
+pub struct MessageBuilder<'a, 'o> {
+    max_length: usize,
+    entries: &'a mut [Option<PendingEntry<'o>>],
+    options: &'a mut [Option<OptionRepr<'o>>],
+}
+
+impl<'a, 'o> MessageBuilder<'a, 'o> {
+    /// Create a new, empty message builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_length` - Maximum UDP payload size of a message, including all headers
+    /// * `entries` - Storage for the queued entries
+    /// * `options` - Storage for the options of a single message
+    pub fn new(
+        max_length: usize,
+        entries: &'a mut [Option<PendingEntry<'o>>],
+        options: &'a mut [Option<OptionRepr<'o>>],
+    ) -> Self {
+        MessageBuilder {
+            max_length,
+            entries,
+            options,
+        }
+    }
+
+    /// Returns the maximum length of the entries and options arrays of a message
+    pub fn capacity(&self) -> usize {
+        self.max_length
+            .saturating_sub(field::header::HEADER_LENGTH + field::sd::HEADER_LENGTH)
+    }
+
+    /// Returns the number of queued entries
+    pub fn len(&self) -> usize {
+        self.entries.iter().flatten().count()
+    }
+
+    /// Checks if no entries are queued
+    pub fn is_empty(&self) -> bool {
+        self.entries.iter().all(Option::is_none)
+    }
+
+    /// Queues an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The entry to send; its option runs are replaced
+    /// * `first_options` - Options referenced by the first option run
+    /// * `second_options` - Options referenced by the second option run
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::EntryTooLarge` if an option run is too long or the entry does not
+    /// fit into an empty message, `Error::OptionTooLarge` if an option does not fit its
+    /// length field, or `Error::StorageFull` if no slot is free.
+    pub fn push(
+        &mut self,
+        entry: EntryRepr,
+        first_options: &'o [OptionRepr<'o>],
+        second_options: &'o [OptionRepr<'o>],
+    ) -> Result<(), Error> {
+        let max_count = OptionRun::MAX_COUNT as usize;
+        if first_options.len() > max_count || second_options.len() > max_count {
+            return Err(Error::EntryTooLarge);
+        }
+        for option in first_options.iter().chain(second_options) {
+            option.check_length()?;
+        }
+        let length = field::sd_entry::ENTRY_LENGTH
+            + first_options
+                .iter()
+                .chain(second_options)
+                .map(OptionRepr::buffer_len)
+                .sum::<usize>();
+        if length > self.capacity() {
+            return Err(Error::EntryTooLarge);
+        }
+        if first_options.len() + second_options.len() > self.options.len() {
+            return Err(Error::StorageFull);
+        }
+
+        let slot = self
+            .entries
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(Error::StorageFull)?;
+        *slot = Some(PendingEntry {
+            entry,
+            first_options,
+            second_options,
+        });
+        Ok(())
+    }
+
+    /// Builds the next message from the queued entries.
+    ///
+    /// Queued entries are placed into the message in order, up to the first entry that
+    /// does not fit, and removed from the queue. Call repeatedly until it returns None
+    /// to send all entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - Scratch buffer holding the entries and options arrays
+    ///
+    /// # Returns
+    ///
+    /// Returns the next message, None if no entries are queued, or
+    /// `Error::BufferTooShort` if not even a single entry fits into `buffer`.
+    pub fn next_message<'b>(&mut self, buffer: &'b mut [u8]) -> Result<Option<SdRepr<'b>>, Error> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+
+        let capacity = self.capacity().min(buffer.len());
+        let mut option_count = 0;
+        let mut options_length = 0;
+        let mut entries_length = 0;
+        let mut entry_count = 0;
+        for slot in self.entries.iter_mut() {
+            let Some(pending) = slot else {
+                break;
+            };
+
+            let placed_count = option_count;
+            let placed = place(self.options, &mut option_count, pending.first_options).and_then(
+                |(first, first_length)| {
+                    place(self.options, &mut option_count, pending.second_options).map(
+                        |(second, second_length)| (first, second, first_length + second_length),
+                    )
+                },
+            );
+            let Some((first, second, length)) = placed.filter(|(_, _, length)| {
+                entries_length + field::sd_entry::ENTRY_LENGTH + options_length + length <= capacity
+            }) else {
+                option_count = placed_count;
+                break;
+            };
+
+            let end = entries_length + field::sd_entry::ENTRY_LENGTH;
+            pending
+                .entry
+                .with_options(first, second)
+                .emit(&mut EntryPacket::new_unchecked(
+                    &mut buffer[entries_length..end],
+                ));
+            entries_length = end;
+            options_length += length;
+            entry_count += 1;
+            *slot = None;
+        }
+
+        // `push` ensures that every entry fits into an empty message of full capacity
+        if entries_length == 0 {
+            return Err(Error::BufferTooShort);
+        }
+
+        // Queued entries stay at the front of the storage, so that `push` appends
+        self.entries.rotate_left(entry_count);
+
+        let mut offset = entries_length;
+        for option in self.options[..option_count].iter().flatten() {
+            let end = offset + option.buffer_len();
+            option.emit(&mut OptionPacket::new_unchecked(&mut buffer[offset..end]));
+            offset = end;
+        }
+
+        let buffer = &*buffer;
+        Ok(Some(SdRepr {
+            reboot: false,
+            unicast: true,
+            explicit_initial_data_control: false,
+            entries: &buffer[..entries_length],
+            options: &buffer[entries_length..offset],
+        }))
+    }
+}
+
+/// Places an option run into the options of a message.
+///
+/// The run reuses placed options if it appears in them, or if its beginning matches the
+/// last placed options. Returns the option run referencing the options and the number of
+/// bytes added to the options array, or None if the options storage is exhausted.
+fn place<'o>(
+    options: &mut [Option<OptionRepr<'o>>],
+    count: &mut usize,
+    run: &[OptionRepr<'o>],
+) -> Option<(OptionRun, usize)> {
+    if run.is_empty() {
+        return Some((OptionRun::new(0, 0), 0));
+    }
+
+    let existing = options[..*count].windows(run.len()).position(|window| {
+        window
+            .iter()
+            .zip(run)
+            .all(|(placed, option)| placed.as_ref() == Some(option))
+    });
+    if let Some(index) = existing {
+        return Some((OptionRun::new(index as u8, run.len() as u8), 0));
+    }
+
+    let placed = &options[..*count];
+    let overlap = (1..=run.len().min(*count))
+        .rev()
+        .find(|&len| {
+            placed[*count - len..]
+                .iter()
+                .zip(&run[..len])
+                .all(|(placed, option)| placed.as_ref() == Some(option))
+        })
+        .unwrap_or(0);
+
+    let index = *count - overlap;
+    let added = &run[overlap..];
+    let end = *count + added.len();
+    if end > options.len() || index > u8::MAX as usize {
+        return None;
+    }
+    for (slot, option) in options[*count..end].iter_mut().zip(added) {
+        *slot = Some(*option);
+    }
+    *count = end;
+    Some((
+        OptionRun::new(index as u8, run.len() as u8),
+        added.iter().map(OptionRepr::buffer_len).sum(),
+    ))
+}
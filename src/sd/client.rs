@@ -0,0 +1,268 @@
+//! SD client module
+//!
+//! This module contains the `ServiceFinder`, a sans-IO state machine that searches for a
+//! service and tracks the availability of its instances from received offers.
+
+use crate::error::Error;
+use crate::sd::entry::{EntryRepr, ServiceEntryRepr, TTL_INFINITE};
+use crate::sd::repr::SdRepr;
+use crate::sd::timing::{Phase, Scheduler, Timing};
+use core::fmt::{self, Display};
+use core::time::Duration;
+
+/// An available service instance, as last offered by a server.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ServiceInstance {
+    /// The last received OfferService entry of the instance
+    pub offer: ServiceEntryRepr,
+    /// Time at which the offer expires, or None for an infinite TTL
+    pub expires_at: Option<Duration>,
+}
+
+/// Change of availability of a service instance
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ServiceEvent {
+    /// The instance has been offered for the first time (or again after going down)
+    Up(ServiceEntryRepr),
+    /// The instance has been stopped or its offer has expired
+    Down(ServiceEntryRepr),
+}
+
+impl Display for ServiceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceEvent::Up(offer) => write!(
+                f,
+                "Service 0x{:04X}.0x{:04X} up",
+                offer.service_id, offer.instance_id
+            ),
+            ServiceEvent::Down(offer) => write!(
+                f,
+                "Service 0x{:04X}.0x{:04X} down",
+                offer.service_id, offer.instance_id
+            ),
+        }
+    }
+}
+
+/// Searches for a service and tracks its available instances.
+///
+/// Like the [`ServiceAnnouncer`](crate::sd::server::ServiceAnnouncer), the finder does
+/// not perform any I/O and does not read a clock. FindService messages are sent in the
+/// Initial Wait and Repetition phases only; as soon as a matching offer is received,
+/// the finder enters the Main phase and stops searching. The cyclic delay of the
+/// timing parameters is not used.
+///
+/// Available instances are stored in a caller-provided slice, one slot per instance.
+///
+/// # Example
+///
+/// ```rust
+/// use core::time::Duration;
+/// use someip_wire::sd::ServiceEntryRepr;
+/// use someip_wire::sd::client::{ServiceEvent, ServiceFinder};
+/// use someip_wire::sd::entry::{ANY_INSTANCE, ANY_MAJOR_VERSION, ANY_MINOR_VERSION};
+/// use someip_wire::sd::timing::Timing;
+///
+/// let find = ServiceEntryRepr::find_service(
+///     0x1234,
+///     ANY_INSTANCE,
+///     ANY_MAJOR_VERSION,
+///     ANY_MINOR_VERSION,
+///     3,
+/// );
+/// let mut storage = [None; 4];
+/// let mut finder = ServiceFinder::new(find, Timing::default(), &mut storage);
+/// finder.start(Duration::ZERO, 0);
+///
+/// let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 1, 0, 3);
+/// let event = finder.process_entry(Duration::from_millis(5), &offer).unwrap();
+/// assert_eq!(event, Some(ServiceEvent::Up(offer)));
+///
+/// let event = finder.expire(Duration::from_secs(4));
+/// assert_eq!(event, Some(ServiceEvent::Down(offer)));
+/// ```
+#[derive(Debug)]
+pub struct ServiceFinder<'a> {
+    find: ServiceEntryRepr,
+    scheduler: Scheduler,
+    instances: &'a mut [Option<ServiceInstance>],
+}
+
+impl<'a> ServiceFinder<'a> {
+    /// Create a new finder in the Down phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `find` - The FindService entry to send; wildcards select which offers are tracked
+    /// * `timing` - Timing parameters of the phases
+    /// * `instances` - Storage for the available instances
+    pub fn new(
+        find: ServiceEntryRepr,
+        timing: Timing,
+        instances: &'a mut [Option<ServiceInstance>],
+    ) -> Self {
+        ServiceFinder {
+            find,
+            scheduler: Scheduler::new(Timing {
+                cyclic_offer_delay: Duration::ZERO,
+                ..timing
+            }),
+            instances,
+        }
+    }
+
+    /// Returns the current phase
+    pub fn phase(&self) -> Phase {
+        self.scheduler.phase()
+    }
+
+    /// Returns the timing parameters
+    pub fn timing(&self) -> &Timing {
+        self.scheduler.timing()
+    }
+
+    /// Returns the FindService entry
+    pub fn entry(&self) -> &ServiceEntryRepr {
+        &self.find
+    }
+
+    /// Returns an iterator over the available instances
+    pub fn instances(&self) -> impl Iterator<Item = &ServiceInstance> + '_ {
+        self.instances.iter().flatten()
+    }
+
+    /// Returns the time at which [`ServiceFinder::poll`] or [`ServiceFinder::expire`]
+    /// must be called next, or None if nothing is scheduled.
+    pub fn poll_at(&self) -> Option<Duration> {
+        let expiry = self
+            .instances()
+            .filter_map(|instance| instance.expires_at)
+            .min();
+        match (self.scheduler.poll_at(), expiry) {
+            (Some(deadline), Some(expiry)) => Some(deadline.min(expiry)),
+            (deadline, expiry) => deadline.or(expiry),
+        }
+    }
+
+    /// Marks the service as requested.
+    ///
+    /// If an instance is already available, the finder directly enters the Main phase;
+    /// otherwise it enters the Initial Wait phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    /// * `random` - A random value selecting the Initial Wait delay
+    pub fn start(&mut self, now: Duration, random: u32) {
+        if self.instances().next().is_some() {
+            self.scheduler.enter_main(now);
+        } else {
+            self.scheduler.start(now, random);
+        }
+    }
+
+    /// Marks the service as no longer requested and returns to the Down phase.
+    ///
+    /// Available instances are still tracked until they are stopped or expire.
+    pub fn stop(&mut self) {
+        self.scheduler.stop();
+    }
+
+    /// Advances the state machine to `now`.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    /// * `buffer` - Scratch buffer holding the returned message
+    ///
+    /// # Returns
+    ///
+    /// Returns the FindService message to send if one is due, or an `Error` if the
+    /// message does not fit into `buffer`.
+    pub fn poll<'b>(
+        &mut self,
+        now: Duration,
+        buffer: &'b mut [u8],
+    ) -> Result<Option<SdRepr<'b>>, Error> {
+        if !self.scheduler.poll(now) {
+            return Ok(None);
+        }
+        SdRepr::from_entries(&[EntryRepr::Service(self.find)], &[], buffer).map(Some)
+    }
+
+    /// Processes a received service entry.
+    ///
+    /// Entries that are not offers of the searched service are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    /// * `entry` - The received entry
+    ///
+    /// # Returns
+    ///
+    /// Returns the resulting availability change, if any, or `Error::StorageFull` if a
+    /// new instance was offered and no slot is free.
+    pub fn process_entry(
+        &mut self,
+        now: Duration,
+        entry: &ServiceEntryRepr,
+    ) -> Result<Option<ServiceEvent>, Error> {
+        if entry.is_find_service() || !self.find.matches(entry) {
+            return Ok(None);
+        }
+        if entry.is_offer_service()
+            && matches!(self.phase(), Phase::InitialWait | Phase::Repetition)
+        {
+            self.scheduler.enter_main(now);
+        }
+
+        let slot = self.instances.iter_mut().find(|slot| {
+            slot.is_some_and(|instance| {
+                instance.offer.service_id == entry.service_id
+                    && instance.offer.instance_id == entry.instance_id
+            })
+        });
+
+        if entry.is_stop_offer_service() {
+            return Ok(slot
+                .and_then(Option::take)
+                .map(|instance| ServiceEvent::Down(instance.offer)));
+        }
+
+        let instance = ServiceInstance {
+            offer: *entry,
+            expires_at: match entry.ttl {
+                TTL_INFINITE => None,
+                ttl => Some(now + Duration::from_secs(ttl as u64)),
+            },
+        };
+        if let Some(slot) = slot {
+            *slot = Some(instance);
+            return Ok(None);
+        }
+        match self.instances.iter_mut().find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(instance);
+                Ok(Some(ServiceEvent::Up(*entry)))
+            }
+            None => Err(Error::StorageFull),
+        }
+    }
+
+    /// Removes an instance whose offer has expired at `now`.
+    ///
+    /// Call repeatedly until it returns None to remove all expired instances.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `ServiceEvent::Down` event of the removed instance, if any.
+    pub fn expire(&mut self, now: Duration) -> Option<ServiceEvent> {
+        let slot = self.instances.iter_mut().find(|slot| {
+            slot.is_some_and(|instance| instance.expires_at.is_some_and(|at| at <= now))
+        })?;
+        slot.take()
+            .map(|instance| ServiceEvent::Down(instance.offer))
+    }
+}
@@ -0,0 +1,968 @@
+//! SD entry module
+//!
+//! This module contains the `EntryPacket` type, which is a read/write wrapper around a single
+//! 16-byte SOME/IP-SD entry, and the typed representations of the entries it can hold.
+
+use crate::error::Error;
+use crate::field;
+use crate::packet::Result;
+use byteorder::{ByteOrder, NetworkEndian};
+use core::fmt::{self, Display};
+
+/// Instance ID matching all instances of a service (used in FindService entries)
+pub const ANY_INSTANCE: u16 = 0xFFFF;
+/// Major version matching any major version (used in FindService entries)
+pub const ANY_MAJOR_VERSION: u8 = 0xFF;
+/// Minor version matching any minor version (used in FindService entries)
+pub const ANY_MINOR_VERSION: u32 = 0xFFFF_FFFF;
+/// TTL value meaning the entry is valid until the next reboot
+pub const TTL_INFINITE: u32 = 0x00FF_FFFF;
+
+/// A read/write wrapper around a single SOME/IP-SD entry buffer.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EntryPacket<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+impl<T: AsRef<[u8]>> EntryPacket<T> {
+    /// Creates a new unchecked `EntryPacket`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - A buffer containing the entry data.
+    ///
+    /// # Returns
+    ///
+    /// * `EntryPacket` - A new `EntryPacket` instance.
+    pub const fn new_unchecked(buffer: T) -> EntryPacket<T> {
+        EntryPacket { buffer }
+    }
+
+    /// Creates a new checked `EntryPacket`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - A buffer containing the entry data.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<EntryPacket>` - A new `EntryPacket` instance if the buffer is valid.
+    pub fn new_checked(buffer: T) -> Result<EntryPacket<T>> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    /// Checks the length of the entry.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Ok if the buffer holds a full 16-byte entry, otherwise an error.
+    pub fn check_len(&self) -> Result<()> {
+        if self.buffer.as_ref().len() < field::sd_entry::ENTRY_LENGTH {
+            Err(Error::BufferTooShort)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the inner buffer.
+    ///
+    /// # Returns
+    ///
+    /// * `T` - The inner buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// Returns the Type (raw u8)
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The raw entry type byte
+    pub fn entry_type(&self) -> u8 {
+        self.buffer.as_ref()[field::sd_entry::TYPE.start]
+    }
+
+    /// Returns the Index of the first option run
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The index of the first option in the options array
+    pub fn index_first_options(&self) -> u8 {
+        self.buffer.as_ref()[field::sd_entry::INDEX_FIRST_OPTIONS.start]
+    }
+
+    /// Returns the Index of the second option run
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The index of the second option run in the options array
+    pub fn index_second_options(&self) -> u8 {
+        self.buffer.as_ref()[field::sd_entry::INDEX_SECOND_OPTIONS.start]
+    }
+
+    /// Returns the Number of options in the first option run
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The number of options (4 bits)
+    pub fn num_first_options(&self) -> u8 {
+        self.buffer.as_ref()[field::sd_entry::NUM_OPTIONS.start] >> 4
+    }
+
+    /// Returns the Number of options in the second option run
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The number of options (4 bits)
+    pub fn num_second_options(&self) -> u8 {
+        self.buffer.as_ref()[field::sd_entry::NUM_OPTIONS.start] & 0x0F
+    }
+
+    /// Returns the Service ID
+    ///
+    /// # Returns
+    ///
+    /// * `u16` - The Service ID of the entry
+    pub fn service_id(&self) -> u16 {
+        NetworkEndian::read_u16(&self.buffer.as_ref()[field::sd_entry::SERVICE_ID])
+    }
+
+    /// Returns the Instance ID
+    ///
+    /// # Returns
+    ///
+    /// * `u16` - The Instance ID of the entry
+    pub fn instance_id(&self) -> u16 {
+        NetworkEndian::read_u16(&self.buffer.as_ref()[field::sd_entry::INSTANCE_ID])
+    }
+
+    /// Returns the Major Version
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The Major Version of the entry
+    pub fn major_version(&self) -> u8 {
+        self.buffer.as_ref()[field::sd_entry::MAJOR_VERSION.start]
+    }
+
+    /// Returns the TTL
+    ///
+    /// # Returns
+    ///
+    /// * `u32` - The lifetime of the entry in seconds (24 bits)
+    pub fn ttl(&self) -> u32 {
+        NetworkEndian::read_u24(&self.buffer.as_ref()[field::sd_entry::TTL])
+    }
+
+    /// Returns the Minor Version
+    ///
+    /// Only meaningful for service entries.
+    ///
+    /// # Returns
+    ///
+    /// * `u32` - The Minor Version of the entry
+    pub fn minor_version(&self) -> u32 {
+        NetworkEndian::read_u32(&self.buffer.as_ref()[field::sd_entry::MINOR_VERSION])
+    }
+
+    /// Returns the Initial Data Requested flag
+    ///
+    /// Only meaningful for eventgroup entries.
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the subscriber requests the initial values of fields
+    pub fn initial_data_requested(&self) -> bool {
+        self.buffer.as_ref()[field::sd_entry::COUNTER.start]
+            & field::sd_entry::INITIAL_DATA_REQUESTED_FLAG
+            != 0
+    }
+
+    /// Returns the Counter
+    ///
+    /// Only meaningful for eventgroup entries.
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The counter distinguishing otherwise identical subscriptions (4 bits)
+    pub fn counter(&self) -> u8 {
+        self.buffer.as_ref()[field::sd_entry::COUNTER.start] & field::sd_entry::COUNTER_MASK
+    }
+
+    /// Returns the Eventgroup ID
+    ///
+    /// Only meaningful for eventgroup entries.
+    ///
+    /// # Returns
+    ///
+    /// * `u16` - The Eventgroup ID of the entry
+    pub fn eventgroup_id(&self) -> u16 {
+        NetworkEndian::read_u16(&self.buffer.as_ref()[field::sd_entry::EVENTGROUP_ID])
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> EntryPacket<T> {
+    /// Sets the Type (raw u8)
+    ///
+    /// # Arguments
+    ///
+    /// * `entry_type` - The new entry type byte to set
+    pub fn set_entry_type(&mut self, entry_type: u8) {
+        self.buffer.as_mut()[field::sd_entry::TYPE.start] = entry_type;
+    }
+
+    /// Sets the Index of the first option run
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The new index to set
+    pub fn set_index_first_options(&mut self, index: u8) {
+        self.buffer.as_mut()[field::sd_entry::INDEX_FIRST_OPTIONS.start] = index;
+    }
+
+    /// Sets the Index of the second option run
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The new index to set
+    pub fn set_index_second_options(&mut self, index: u8) {
+        self.buffer.as_mut()[field::sd_entry::INDEX_SECOND_OPTIONS.start] = index;
+    }
+
+    /// Sets the Number of options in the first option run
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The new number of options (only the lower 4 bits are used)
+    pub fn set_num_first_options(&mut self, count: u8) {
+        let byte = &mut self.buffer.as_mut()[field::sd_entry::NUM_OPTIONS.start];
+        *byte = (*byte & 0x0F) | ((count & 0x0F) << 4);
+    }
+
+    /// Sets the Number of options in the second option run
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The new number of options (only the lower 4 bits are used)
+    pub fn set_num_second_options(&mut self, count: u8) {
+        let byte = &mut self.buffer.as_mut()[field::sd_entry::NUM_OPTIONS.start];
+        *byte = (*byte & 0xF0) | (count & 0x0F);
+    }
+
+    /// Sets the Service ID
+    ///
+    /// # Arguments
+    ///
+    /// * `service_id` - The new Service ID to set
+    pub fn set_service_id(&mut self, service_id: u16) {
+        NetworkEndian::write_u16(
+            &mut self.buffer.as_mut()[field::sd_entry::SERVICE_ID],
+            service_id,
+        );
+    }
+
+    /// Sets the Instance ID
+    ///
+    /// # Arguments
+    ///
+    /// * `instance_id` - The new Instance ID to set
+    pub fn set_instance_id(&mut self, instance_id: u16) {
+        NetworkEndian::write_u16(
+            &mut self.buffer.as_mut()[field::sd_entry::INSTANCE_ID],
+            instance_id,
+        );
+    }
+
+    /// Sets the Major Version
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The new Major Version to set
+    pub fn set_major_version(&mut self, version: u8) {
+        self.buffer.as_mut()[field::sd_entry::MAJOR_VERSION.start] = version;
+    }
+
+    /// Sets the TTL
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - The new lifetime in seconds (only the lower 24 bits are used)
+    pub fn set_ttl(&mut self, ttl: u32) {
+        NetworkEndian::write_u24(
+            &mut self.buffer.as_mut()[field::sd_entry::TTL],
+            ttl & 0x00FF_FFFF,
+        );
+    }
+
+    /// Sets the Minor Version
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - The new Minor Version to set
+    pub fn set_minor_version(&mut self, version: u32) {
+        NetworkEndian::write_u32(
+            &mut self.buffer.as_mut()[field::sd_entry::MINOR_VERSION],
+            version,
+        );
+    }
+
+    /// Sets the Initial Data Requested flag and the Counter, clearing the reserved bits
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_data_requested` - Whether initial field values are requested
+    /// * `counter` - The new counter (only the lower 4 bits are used)
+    pub fn set_counter(&mut self, initial_data_requested: bool, counter: u8) {
+        let mut byte = counter & field::sd_entry::COUNTER_MASK;
+        if initial_data_requested {
+            byte |= field::sd_entry::INITIAL_DATA_REQUESTED_FLAG;
+        }
+        let buffer = self.buffer.as_mut();
+        buffer[field::sd_entry::RESERVED.start] = 0;
+        buffer[field::sd_entry::COUNTER.start] = byte;
+    }
+
+    /// Sets the Eventgroup ID
+    ///
+    /// # Arguments
+    ///
+    /// * `eventgroup_id` - The new Eventgroup ID to set
+    pub fn set_eventgroup_id(&mut self, eventgroup_id: u16) {
+        NetworkEndian::write_u16(
+            &mut self.buffer.as_mut()[field::sd_entry::EVENTGROUP_ID],
+            eventgroup_id,
+        );
+    }
+}
+
+/// Entry Type for SOME/IP-SD entries
+///
+/// StopOfferService, StopSubscribeEventgroup and SubscribeEventgroupNack are not separate
+/// types on the wire: they are the OfferService, SubscribeEventgroup and
+/// SubscribeEventgroupAck entries with a TTL of zero.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EntryType {
+    /// Searches for service instances (0x00)
+    FindService,
+    /// Offers a service instance, or stops offering it when the TTL is zero (0x01)
+    OfferService,
+    /// Subscribes to an eventgroup, or stops the subscription when the TTL is zero (0x06)
+    SubscribeEventgroup,
+    /// Acknowledges a subscription, or rejects it when the TTL is zero (0x07)
+    SubscribeEventgroupAck,
+}
+
+impl EntryType {
+    /// Convert from wire format (u8) to EntryType
+    ///
+    /// Returns None if the value doesn't correspond to a known entry type
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0x00 => Some(EntryType::FindService),
+            0x01 => Some(EntryType::OfferService),
+            0x06 => Some(EntryType::SubscribeEventgroup),
+            0x07 => Some(EntryType::SubscribeEventgroupAck),
+            _ => None,
+        }
+    }
+
+    /// Convert to wire format (u8)
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            EntryType::FindService => 0x00,
+            EntryType::OfferService => 0x01,
+            EntryType::SubscribeEventgroup => 0x06,
+            EntryType::SubscribeEventgroupAck => 0x07,
+        }
+    }
+
+    /// Check if this is a service entry type (FindService or OfferService)
+    pub const fn is_service(&self) -> bool {
+        matches!(self, EntryType::FindService | EntryType::OfferService)
+    }
+
+    /// Check if this is an eventgroup entry type (SubscribeEventgroup or SubscribeEventgroupAck)
+    pub const fn is_eventgroup(&self) -> bool {
+        matches!(
+            self,
+            EntryType::SubscribeEventgroup | EntryType::SubscribeEventgroupAck
+        )
+    }
+}
+
+// Convenience: convert to u8
+impl From<EntryType> for u8 {
+    fn from(entry_type: EntryType) -> Self {
+        entry_type.as_u8()
+    }
+}
+
+impl Display for EntryType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryType::FindService => write!(f, "FindService"),
+            EntryType::OfferService => write!(f, "OfferService"),
+            EntryType::SubscribeEventgroup => write!(f, "SubscribeEventgroup"),
+            EntryType::SubscribeEventgroupAck => write!(f, "SubscribeEventgroupAck"),
+        }
+    }
+}
+
+/// A run of consecutive options in the options array referenced by an entry.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct OptionRun {
+    /// Index of the first option of the run in the options array
+    pub index: u8,
+    /// Number of options in the run (4 bits)
+    pub count: u8,
+}
+
+impl OptionRun {
+    /// Maximum number of options in a run, limited by the 4-bit count field
+    pub const MAX_COUNT: u8 = 15;
+
+    /// Create a new option run.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Index of the first option in the options array
+    /// * `count` - Number of options in the run (only the lower 4 bits are used on the wire)
+    pub const fn new(index: u8, count: u8) -> Self {
+        OptionRun { index, count }
+    }
+
+    /// Check if the run references no options
+    pub const fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+/// A high-level representation of a SOME/IP-SD service entry.
+///
+/// Service entries are used for FindService, OfferService and StopOfferService.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ServiceEntryRepr {
+    /// Entry type (FindService or OfferService)
+    pub entry_type: EntryType,
+    /// First option run
+    pub first_options: OptionRun,
+    /// Second option run
+    pub second_options: OptionRun,
+    /// Service ID (16 bits)
+    pub service_id: u16,
+    /// Instance ID (16 bits), [`ANY_INSTANCE`] in a FindService matches all instances
+    pub instance_id: u16,
+    /// Major version (8 bits)
+    pub major_version: u8,
+    /// Lifetime in seconds (24 bits), zero stops an offer
+    pub ttl: u32,
+    /// Minor version (32 bits)
+    pub minor_version: u32,
+}
+
+impl ServiceEntryRepr {
+    /// Create a FindService entry without option references.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_id` - The service to search for
+    /// * `instance_id` - The instance to search for, or [`ANY_INSTANCE`]
+    /// * `major_version` - The major version, or [`ANY_MAJOR_VERSION`]
+    /// * `minor_version` - The minor version, or [`ANY_MINOR_VERSION`]
+    /// * `ttl` - Lifetime of the entry in seconds
+    pub const fn find_service(
+        service_id: u16,
+        instance_id: u16,
+        major_version: u8,
+        minor_version: u32,
+        ttl: u32,
+    ) -> Self {
+        ServiceEntryRepr {
+            entry_type: EntryType::FindService,
+            first_options: OptionRun::new(0, 0),
+            second_options: OptionRun::new(0, 0),
+            service_id,
+            instance_id,
+            major_version,
+            ttl,
+            minor_version,
+        }
+    }
+
+    /// Create an OfferService entry without option references.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_id` - The offered service
+    /// * `instance_id` - The offered instance
+    /// * `major_version` - The major version of the offered service
+    /// * `minor_version` - The minor version of the offered service
+    /// * `ttl` - Lifetime of the offer in seconds, zero to stop the offer
+    pub const fn offer_service(
+        service_id: u16,
+        instance_id: u16,
+        major_version: u8,
+        minor_version: u32,
+        ttl: u32,
+    ) -> Self {
+        ServiceEntryRepr {
+            entry_type: EntryType::OfferService,
+            first_options: OptionRun::new(0, 0),
+            second_options: OptionRun::new(0, 0),
+            service_id,
+            instance_id,
+            major_version,
+            ttl,
+            minor_version,
+        }
+    }
+
+    /// Create a StopOfferService entry matching this offer (the same entry with a TTL of zero).
+    pub const fn to_stop_offer(&self) -> Self {
+        ServiceEntryRepr {
+            entry_type: EntryType::OfferService,
+            ttl: 0,
+            ..*self
+        }
+    }
+
+    /// Check if this is a FindService entry
+    pub const fn is_find_service(&self) -> bool {
+        matches!(self.entry_type, EntryType::FindService)
+    }
+
+    /// Check if this is an OfferService entry with a non-zero TTL
+    pub const fn is_offer_service(&self) -> bool {
+        matches!(self.entry_type, EntryType::OfferService) && self.ttl != 0
+    }
+
+    /// Check if this is a StopOfferService entry (OfferService with a TTL of zero)
+    pub const fn is_stop_offer_service(&self) -> bool {
+        matches!(self.entry_type, EntryType::OfferService) && self.ttl == 0
+    }
+
+    /// Check if `other` refers to the service searched for by this entry.
+    ///
+    /// The instance ID and versions of this entry may be wildcards ([`ANY_INSTANCE`],
+    /// [`ANY_MAJOR_VERSION`], [`ANY_MINOR_VERSION`]); the entry types and TTLs are ignored.
+    pub const fn matches(&self, other: &ServiceEntryRepr) -> bool {
+        self.service_id == other.service_id
+            && (self.instance_id == ANY_INSTANCE || self.instance_id == other.instance_id)
+            && (self.major_version == ANY_MAJOR_VERSION
+                || self.major_version == other.major_version)
+            && (self.minor_version == ANY_MINOR_VERSION
+                || self.minor_version == other.minor_version)
+    }
+
+    /// Parse an SD entry into a service entry representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - The entry buffer to parse
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ServiceEntryRepr` on success, or an `Error` if the entry is
+    /// malformed or is not a service entry.
+    pub fn parse<T>(packet: &EntryPacket<T>) -> core::result::Result<ServiceEntryRepr, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        packet.check_len()?;
+
+        let entry_type_byte = packet.entry_type();
+        let entry_type = EntryType::from_u8(entry_type_byte)
+            .filter(EntryType::is_service)
+            .ok_or(Error::InvalidEntryType(entry_type_byte))?;
+
+        Ok(ServiceEntryRepr {
+            entry_type,
+            first_options: OptionRun::new(packet.index_first_options(), packet.num_first_options()),
+            second_options: OptionRun::new(
+                packet.index_second_options(),
+                packet.num_second_options(),
+            ),
+            service_id: packet.service_id(),
+            instance_id: packet.instance_id(),
+            major_version: packet.major_version(),
+            ttl: packet.ttl(),
+            minor_version: packet.minor_version(),
+        })
+    }
+
+    /// Emits the service entry into the provided entry buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A mutable reference to the entry where the representation will be written.
+    pub fn emit<T>(&self, packet: &mut EntryPacket<&mut T>)
+    where
+        T: AsRef<[u8]> + AsMut<[u8]> + ?Sized,
+    {
+        packet.set_entry_type(self.entry_type.as_u8());
+        packet.set_index_first_options(self.first_options.index);
+        packet.set_index_second_options(self.second_options.index);
+        packet.set_num_first_options(self.first_options.count);
+        packet.set_num_second_options(self.second_options.count);
+        packet.set_service_id(self.service_id);
+        packet.set_instance_id(self.instance_id);
+        packet.set_major_version(self.major_version);
+        packet.set_ttl(self.ttl);
+        packet.set_minor_version(self.minor_version);
+    }
+}
+
+impl Display for ServiceEntryRepr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: service={:04X}.{:04X}, version={}.{}, ttl={}",
+            self.entry_type,
+            self.service_id,
+            self.instance_id,
+            self.major_version,
+            self.minor_version,
+            self.ttl
+        )
+    }
+}
+
+/// A high-level representation of a SOME/IP-SD eventgroup entry.
+///
+/// Eventgroup entries are used for SubscribeEventgroup, StopSubscribeEventgroup,
+/// SubscribeEventgroupAck and SubscribeEventgroupNack.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct EventgroupEntryRepr {
+    /// Entry type (SubscribeEventgroup or SubscribeEventgroupAck)
+    pub entry_type: EntryType,
+    /// First option run
+    pub first_options: OptionRun,
+    /// Second option run
+    pub second_options: OptionRun,
+    /// Service ID (16 bits)
+    pub service_id: u16,
+    /// Instance ID (16 bits)
+    pub instance_id: u16,
+    /// Major version (8 bits)
+    pub major_version: u8,
+    /// Lifetime in seconds (24 bits), zero stops a subscription or rejects it
+    pub ttl: u32,
+    /// Initial Data Requested flag
+    pub initial_data_requested: bool,
+    /// Counter distinguishing otherwise identical subscriptions (4 bits)
+    pub counter: u8,
+    /// Eventgroup ID (16 bits)
+    pub eventgroup_id: u16,
+}
+
+impl EventgroupEntryRepr {
+    /// Create a SubscribeEventgroup entry without option references.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_id` - The service the eventgroup belongs to
+    /// * `instance_id` - The service instance
+    /// * `major_version` - The major version of the service
+    /// * `eventgroup_id` - The eventgroup to subscribe to
+    /// * `counter` - The subscription counter (4 bits)
+    /// * `ttl` - Lifetime of the subscription in seconds, zero to stop the subscription
+    pub const fn subscribe(
+        service_id: u16,
+        instance_id: u16,
+        major_version: u8,
+        eventgroup_id: u16,
+        counter: u8,
+        ttl: u32,
+    ) -> Self {
+        EventgroupEntryRepr {
+            entry_type: EntryType::SubscribeEventgroup,
+            first_options: OptionRun::new(0, 0),
+            second_options: OptionRun::new(0, 0),
+            service_id,
+            instance_id,
+            major_version,
+            ttl,
+            initial_data_requested: false,
+            counter,
+            eventgroup_id,
+        }
+    }
+
+    /// Create a StopSubscribeEventgroup entry matching this subscription (TTL of zero).
+    pub const fn to_stop_subscribe(&self) -> Self {
+        EventgroupEntryRepr {
+            entry_type: EntryType::SubscribeEventgroup,
+            ttl: 0,
+            ..*self
+        }
+    }
+
+    /// Create a SubscribeEventgroupAck entry answering this subscription.
+    ///
+    /// The acknowledgement carries the same TTL and no option references; multicast
+    /// endpoint options have to be attached by the caller.
+    pub const fn to_ack(&self) -> Self {
+        EventgroupEntryRepr {
+            entry_type: EntryType::SubscribeEventgroupAck,
+            first_options: OptionRun::new(0, 0),
+            second_options: OptionRun::new(0, 0),
+            initial_data_requested: false,
+            ..*self
+        }
+    }
+
+    /// Create a SubscribeEventgroupNack entry rejecting this subscription (Ack with a TTL of zero).
+    pub const fn to_nack(&self) -> Self {
+        EventgroupEntryRepr {
+            ttl: 0,
+            ..self.to_ack()
+        }
+    }
+
+    /// Check if this is a SubscribeEventgroup entry with a non-zero TTL
+    pub const fn is_subscribe(&self) -> bool {
+        matches!(self.entry_type, EntryType::SubscribeEventgroup) && self.ttl != 0
+    }
+
+    /// Check if this is a StopSubscribeEventgroup entry (SubscribeEventgroup with a TTL of zero)
+    pub const fn is_stop_subscribe(&self) -> bool {
+        matches!(self.entry_type, EntryType::SubscribeEventgroup) && self.ttl == 0
+    }
+
+    /// Check if this is a SubscribeEventgroupAck entry with a non-zero TTL
+    pub const fn is_ack(&self) -> bool {
+        matches!(self.entry_type, EntryType::SubscribeEventgroupAck) && self.ttl != 0
+    }
+
+    /// Check if this is a SubscribeEventgroupNack entry (SubscribeEventgroupAck with a TTL of zero)
+    pub const fn is_nack(&self) -> bool {
+        matches!(self.entry_type, EntryType::SubscribeEventgroupAck) && self.ttl == 0
+    }
+
+    /// Check if this entry answers the given subscription (same service, instance,
+    /// major version, eventgroup and counter).
+    pub const fn answers(&self, subscription: &EventgroupEntryRepr) -> bool {
+        matches!(self.entry_type, EntryType::SubscribeEventgroupAck)
+            && self.service_id == subscription.service_id
+            && self.instance_id == subscription.instance_id
+            && self.major_version == subscription.major_version
+            && self.eventgroup_id == subscription.eventgroup_id
+            && self.counter == subscription.counter
+    }
+
+    /// Parse an SD entry into an eventgroup entry representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - The entry buffer to parse
+    ///
+    /// # Returns
+    ///
+    /// Returns an `EventgroupEntryRepr` on success, or an `Error` if the entry is
+    /// malformed or is not an eventgroup entry.
+    pub fn parse<T>(packet: &EntryPacket<T>) -> core::result::Result<EventgroupEntryRepr, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        packet.check_len()?;
+
+        let entry_type_byte = packet.entry_type();
+        let entry_type = EntryType::from_u8(entry_type_byte)
+            .filter(EntryType::is_eventgroup)
+            .ok_or(Error::InvalidEntryType(entry_type_byte))?;
+
+        Ok(EventgroupEntryRepr {
+            entry_type,
+            first_options: OptionRun::new(packet.index_first_options(), packet.num_first_options()),
+            second_options: OptionRun::new(
+                packet.index_second_options(),
+                packet.num_second_options(),
+            ),
+            service_id: packet.service_id(),
+            instance_id: packet.instance_id(),
+            major_version: packet.major_version(),
+            ttl: packet.ttl(),
+            initial_data_requested: packet.initial_data_requested(),
+            counter: packet.counter(),
+            eventgroup_id: packet.eventgroup_id(),
+        })
+    }
+
+    /// Emits the eventgroup entry into the provided entry buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A mutable reference to the entry where the representation will be written.
+    pub fn emit<T>(&self, packet: &mut EntryPacket<&mut T>)
+    where
+        T: AsRef<[u8]> + AsMut<[u8]> + ?Sized,
+    {
+        packet.set_entry_type(self.entry_type.as_u8());
+        packet.set_index_first_options(self.first_options.index);
+        packet.set_index_second_options(self.second_options.index);
+        packet.set_num_first_options(self.first_options.count);
+        packet.set_num_second_options(self.second_options.count);
+        packet.set_service_id(self.service_id);
+        packet.set_instance_id(self.instance_id);
+        packet.set_major_version(self.major_version);
+        packet.set_ttl(self.ttl);
+        packet.set_counter(self.initial_data_requested, self.counter);
+        packet.set_eventgroup_id(self.eventgroup_id);
+    }
+}
+
+impl Display for EventgroupEntryRepr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: service={:04X}.{:04X}, eventgroup={:04X}, major_version={}, counter={}, ttl={}",
+            self.entry_type,
+            self.service_id,
+            self.instance_id,
+            self.eventgroup_id,
+            self.major_version,
+            self.counter,
+            self.ttl
+        )
+    }
+}
+
+/// A high-level representation of any SOME/IP-SD entry.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EntryRepr {
+    /// FindService, OfferService or StopOfferService entry
+    Service(ServiceEntryRepr),
+    /// SubscribeEventgroup, StopSubscribeEventgroup, SubscribeEventgroupAck or
+    /// SubscribeEventgroupNack entry
+    Eventgroup(EventgroupEntryRepr),
+}
+
+impl EntryRepr {
+    /// Returns the entry type
+    pub const fn entry_type(&self) -> EntryType {
+        match self {
+            EntryRepr::Service(entry) => entry.entry_type,
+            EntryRepr::Eventgroup(entry) => entry.entry_type,
+        }
+    }
+
+    /// Returns the first option run
+    pub const fn first_options(&self) -> OptionRun {
+        match self {
+            EntryRepr::Service(entry) => entry.first_options,
+            EntryRepr::Eventgroup(entry) => entry.first_options,
+        }
+    }
+
+    /// Returns the second option run
+    pub const fn second_options(&self) -> OptionRun {
+        match self {
+            EntryRepr::Service(entry) => entry.second_options,
+            EntryRepr::Eventgroup(entry) => entry.second_options,
+        }
+    }
+
+    /// Returns a copy of the entry referencing the given option runs
+    pub const fn with_options(&self, first_options: OptionRun, second_options: OptionRun) -> Self {
+        match *self {
+            EntryRepr::Service(entry) => EntryRepr::Service(ServiceEntryRepr {
+                first_options,
+                second_options,
+                ..entry
+            }),
+            EntryRepr::Eventgroup(entry) => EntryRepr::Eventgroup(EventgroupEntryRepr {
+                first_options,
+                second_options,
+                ..entry
+            }),
+        }
+    }
+
+    /// Returns the Service ID
+    pub const fn service_id(&self) -> u16 {
+        match self {
+            EntryRepr::Service(entry) => entry.service_id,
+            EntryRepr::Eventgroup(entry) => entry.service_id,
+        }
+    }
+
+    /// Returns the Instance ID
+    pub const fn instance_id(&self) -> u16 {
+        match self {
+            EntryRepr::Service(entry) => entry.instance_id,
+            EntryRepr::Eventgroup(entry) => entry.instance_id,
+        }
+    }
+
+    /// Returns the TTL in seconds
+    pub const fn ttl(&self) -> u32 {
+        match self {
+            EntryRepr::Service(entry) => entry.ttl,
+            EntryRepr::Eventgroup(entry) => entry.ttl,
+        }
+    }
+
+    /// Parse an SD entry into the matching entry representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - The entry buffer to parse
+    ///
+    /// # Returns
+    ///
+    /// Returns an `EntryRepr` on success, or an `Error` if the entry is malformed
+    /// or of an unknown type.
+    pub fn parse<T>(packet: &EntryPacket<T>) -> core::result::Result<EntryRepr, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        packet.check_len()?;
+
+        let entry_type_byte = packet.entry_type();
+        match EntryType::from_u8(entry_type_byte) {
+            Some(entry_type) if entry_type.is_service() => {
+                ServiceEntryRepr::parse(packet).map(EntryRepr::Service)
+            }
+            Some(_) => EventgroupEntryRepr::parse(packet).map(EntryRepr::Eventgroup),
+            None => Err(Error::InvalidEntryType(entry_type_byte)),
+        }
+    }
+
+    /// Emits the entry into the provided entry buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A mutable reference to the entry where the representation will be written.
+    pub fn emit<T>(&self, packet: &mut EntryPacket<&mut T>)
+    where
+        T: AsRef<[u8]> + AsMut<[u8]> + ?Sized,
+    {
+        match self {
+            EntryRepr::Service(entry) => entry.emit(packet),
+            EntryRepr::Eventgroup(entry) => entry.emit(packet),
+        }
+    }
+}
+
+impl From<ServiceEntryRepr> for EntryRepr {
+    fn from(entry: ServiceEntryRepr) -> Self {
+        EntryRepr::Service(entry)
+    }
+}
+
+impl From<EventgroupEntryRepr> for EntryRepr {
+    fn from(entry: EventgroupEntryRepr) -> Self {
+        EntryRepr::Eventgroup(entry)
+    }
+}
+
+impl Display for EntryRepr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntryRepr::Service(entry) => entry.fmt(f),
+            EntryRepr::Eventgroup(entry) => entry.fmt(f),
+        }
+    }
+}
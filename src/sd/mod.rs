@@ -0,0 +1,1419 @@
+//! SOME/IP Service Discovery (SOME/IP-SD)
+//!
+//! SD messages are regular SOME/IP messages with the Message ID `0xFFFF.8100`, whose
+//! payload carries an SD header followed by an entries array and an options array.
+//! This module parses and emits that payload, using the same `Packet`/`Repr` split
+//! as the SOME/IP header.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use someip_wire::prelude::*;
+//! use someip_wire::sd::{self, SdPacket, SdRepr};
+//!
+//! let buffer = [
+//!     0xFF, 0xFF, 0x81, 0x00, // Message ID (SD)
+//!     0x00, 0x00, 0x00, 0x14, // Length (8 header bytes + 12 SD bytes)
+//!     0x00, 0x00, 0x00, 0x01, // Request ID
+//!     0x01,                   // Protocol version
+//!     0x01,                   // Interface version
+//!     0x02,                   // Message type (Notification)
+//!     0x00,                   // Return code (E_OK)
+//!     0xC0, 0x00, 0x00, 0x00, // Flags (Reboot, Unicast) + Reserved
+//!     0x00, 0x00, 0x00, 0x00, // Length of entries array
+//!     0x00, 0x00, 0x00, 0x00, // Length of options array
+//! ];
+//!
+//! let packet = Packet::new_checked(&buffer[..]).unwrap();
+//! let repr = Repr::parse(&packet).unwrap();
+//! assert_eq!(repr.message_id, sd::MESSAGE_ID);
+//!
+//! let sd_packet = SdPacket::new_checked(repr.data).unwrap();
+//! let sd_repr = SdRepr::parse(&sd_packet).unwrap();
+//! assert!(sd_repr.reboot);
+//! assert!(sd_repr.unicast);
+//! assert_eq!(sd_repr.entries().count(), 0);
+//! ```
+
+use crate::types::MessageId;
+
+/// Packing of entries and options into size-limited SD messages.
+pub mod builder;
+/// Client-side state machine finding services.
+pub mod client;
+/// Wrapper around a single SD entry.
+pub mod entry;
+/// Wrapper around a single SD option.
+pub mod option;
+/// Low-level access to the SD header and arrays.
+pub mod packet;
+/// Reboot detection of SD peers.
+pub mod reboot;
+/// High-level SD message representation.
+pub mod repr;
+/// Server-side state machine offering a service.
+pub mod server;
+/// Client-side state machine subscribing to eventgroups.
+pub mod subscription;
+/// Timing parameters and phases of the SD state machines.
+pub mod timing;
+
+pub use entry::{
+    EntryPacket, EntryRepr, EntryType, EventgroupEntryRepr, OptionRun, ServiceEntryRepr,
+};
+pub use option::{
+    ConfigurationItem, ConfigurationRepr, Ipv4EndpointRepr, Ipv6EndpointRepr, L4Protocol,
+    LoadBalancingRepr, OptionPacket, OptionRepr, OptionType, select_instance,
+};
+pub use packet::SdPacket;
+pub use repr::{ResolvedOptions, SdRepr};
+
+/// Message ID used by all SOME/IP-SD messages (`0xFFFF.8100`).
+pub const MESSAGE_ID: MessageId = MessageId {
+    service_id: 0xFFFF,
+    method_id: 0x8100,
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::field;
+    use crate::sd::builder::MessageBuilder;
+    use crate::sd::client::{ServiceEvent, ServiceFinder};
+    use crate::sd::entry::{ANY_INSTANCE, ANY_MAJOR_VERSION, ANY_MINOR_VERSION, TTL_INFINITE};
+    use crate::sd::reboot::{Channel, RebootDetector, RebootEvent, SessionState};
+    use crate::sd::server::ServiceAnnouncer;
+    use crate::sd::subscription::{SubscriptionEvent, SubscriptionManager, SubscriptionState};
+    use crate::sd::timing::{Phase, Timing};
+    use crate::sd::{
+        ConfigurationItem, ConfigurationRepr, EntryPacket, EntryRepr, EntryType,
+        EventgroupEntryRepr, Ipv4EndpointRepr, Ipv6EndpointRepr, L4Protocol, LoadBalancingRepr,
+        OptionPacket, OptionRepr, OptionRun, SdPacket, SdRepr, ServiceEntryRepr, select_instance,
+    };
+    use crate::types::{ClientId, RequestId};
+    use core::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+    use core::time::Duration;
+
+    static SD_PAYLOAD: [u8; 40] = [
+        0xC0, 0x00, 0x00, 0x00, // Flags (Reboot, Unicast) + Reserved
+        0x00, 0x00, 0x00, 0x10, // Length of entries array
+        0x01, 0x00, 0x00, 0x10, // Type (OfferService), Index 1st, Index 2nd, # of opts
+        0x12, 0x34, 0x00, 0x01, // Service ID, Instance ID
+        0x01, 0x00, 0x00, 0x03, // Major Version, TTL
+        0x00, 0x00, 0x00, 0x0A, // Minor Version
+        0x00, 0x00, 0x00, 0x0C, // Length of options array
+        0x00, 0x09, 0x04, 0x00, // Length, Type (IPv4 Endpoint), Reserved
+        0xC0, 0xA8, 0x00, 0x01, // IPv4 Address
+        0x00, 0x11, 0x75, 0x30, // Reserved, L4 Protocol (UDP), Port
+    ];
+
+    #[test]
+    fn test_sd_parse() {
+        let packet = SdPacket::new_checked(&SD_PAYLOAD[..]).unwrap();
+        let repr = SdRepr::parse(&packet).unwrap();
+
+        assert!(repr.reboot);
+        assert!(repr.unicast);
+        assert!(!repr.explicit_initial_data_control);
+        assert_eq!(repr.entries, &SD_PAYLOAD[8..24]);
+        assert_eq!(repr.options, &SD_PAYLOAD[28..40]);
+
+        let mut entries = repr.entries();
+        let entry = entries.next().unwrap();
+        assert_eq!(entry.entry_type(), 0x01);
+        assert_eq!(entry.index_first_options(), 0);
+        assert_eq!(entry.num_first_options(), 1);
+        assert_eq!(entry.num_second_options(), 0);
+        assert_eq!(entry.service_id(), 0x1234);
+        assert_eq!(entry.instance_id(), 0x0001);
+        assert_eq!(entry.major_version(), 0x01);
+        assert_eq!(entry.ttl(), 3);
+        assert!(entries.next().is_none());
+
+        let mut options = repr.options();
+        let option = options.next().unwrap();
+        assert_eq!(option.length(), 9);
+        assert_eq!(option.option_type(), 0x04);
+        assert!(!option.discardable());
+        assert_eq!(option.data(), &SD_PAYLOAD[32..40]);
+        assert!(options.next().is_none());
+    }
+
+    #[test]
+    fn test_sd_round_trip() {
+        let packet = SdPacket::new_checked(&SD_PAYLOAD[..]).unwrap();
+        let repr = SdRepr::parse(&packet).unwrap();
+        assert_eq!(repr.buffer_len(), SD_PAYLOAD.len());
+
+        let mut buffer = [0xFFu8; 40];
+        repr.emit(&mut SdPacket::new_unchecked(&mut buffer));
+        assert_eq!(buffer, SD_PAYLOAD);
+
+        let packet = SdPacket::new_checked(&buffer[..]).unwrap();
+        assert_eq!(SdRepr::parse(&packet).unwrap(), repr);
+    }
+
+    #[test]
+    fn test_sd_error_buffer_too_short() {
+        let buffer = [0u8; 8];
+        assert_eq!(
+            SdPacket::new_checked(&buffer[..]),
+            Err(Error::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_sd_error_truncated() {
+        // Options array claims 13 bytes but only 12 are present
+        let mut buffer = SD_PAYLOAD;
+        buffer[27] = 0x0D;
+        let packet = SdPacket::new_unchecked(&buffer[..]);
+        assert_eq!(SdRepr::parse(&packet), Err(Error::Truncated));
+
+        // Entries array runs past the end of the buffer
+        let mut buffer = SD_PAYLOAD;
+        buffer[7] = 0x40;
+        let packet = SdPacket::new_unchecked(&buffer[..]);
+        assert_eq!(packet.check_len(), Err(Error::Truncated));
+    }
+
+    #[test]
+    fn test_sd_error_invalid_entries_length() {
+        let buffer = [
+            0x00, 0x00, 0x00, 0x00, // Flags + Reserved
+            0x00, 0x00, 0x00, 0x04, // Length of entries array (not a multiple of 16)
+            0x01, 0x02, 0x03, 0x04, // Partial entry
+            0x00, 0x00, 0x00, 0x00, // Length of options array
+        ];
+        let packet = SdPacket::new_unchecked(&buffer[..]);
+        assert_eq!(SdRepr::parse(&packet), Err(Error::InvalidEntriesLength(4)));
+    }
+
+    #[test]
+    fn test_sd_error_invalid_option_length() {
+        let buffer = [
+            0x00, 0x00, 0x00, 0x00, // Flags + Reserved
+            0x00, 0x00, 0x00, 0x00, // Length of entries array
+            0x00, 0x00, 0x00, 0x04, // Length of options array
+            0x00, 0x00, 0x04, 0x00, // Option with a zero length field
+        ];
+        let packet = SdPacket::new_unchecked(&buffer[..]);
+        assert_eq!(SdRepr::parse(&packet), Err(Error::InvalidOptionLength(0)));
+    }
+
+    fn entry_round_trip_test_with_bytes(repr: ServiceEntryRepr, expected_bytes: &[u8; 16]) {
+        let mut buffer = [0u8; 16];
+        repr.emit(&mut EntryPacket::new_unchecked(&mut buffer));
+        assert_eq!(&buffer, expected_bytes);
+
+        let packet = EntryPacket::new_checked(&buffer[..]).unwrap();
+        assert_eq!(ServiceEntryRepr::parse(&packet).unwrap(), repr);
+    }
+
+    #[test]
+    fn test_service_entry_offer_service() {
+        let packet = SdPacket::new_checked(&SD_PAYLOAD[..]).unwrap();
+        let repr = SdRepr::parse(&packet).unwrap();
+        let entry = ServiceEntryRepr::parse(&repr.entries().next().unwrap()).unwrap();
+
+        assert_eq!(
+            entry,
+            ServiceEntryRepr {
+                entry_type: EntryType::OfferService,
+                first_options: OptionRun::new(0, 1),
+                second_options: OptionRun::new(0, 0),
+                service_id: 0x1234,
+                instance_id: 0x0001,
+                major_version: 0x01,
+                ttl: 3,
+                minor_version: 0x0A,
+            }
+        );
+        assert!(entry.is_offer_service());
+        assert!(!entry.is_stop_offer_service());
+        assert!(!entry.is_find_service());
+    }
+
+    #[test]
+    fn test_service_entry_round_trip_find_service() {
+        let repr = ServiceEntryRepr::find_service(
+            0x1234,
+            ANY_INSTANCE,
+            ANY_MAJOR_VERSION,
+            ANY_MINOR_VERSION,
+            3,
+        );
+        assert!(repr.is_find_service());
+        entry_round_trip_test_with_bytes(
+            repr,
+            &[
+                0x00, 0x00, 0x00, 0x00, // Type (FindService), Index 1st, Index 2nd, # of opts
+                0x12, 0x34, 0xFF, 0xFF, // Service ID, Instance ID
+                0xFF, 0x00, 0x00, 0x03, // Major Version, TTL
+                0xFF, 0xFF, 0xFF, 0xFF, // Minor Version
+            ],
+        );
+    }
+
+    #[test]
+    fn test_service_entry_round_trip_stop_offer() {
+        let offer = ServiceEntryRepr {
+            first_options: OptionRun::new(1, 2),
+            second_options: OptionRun::new(4, 1),
+            ..ServiceEntryRepr::offer_service(0xABCD, 0x0002, 0x03, 0x0000_0010, 0x00FF_FFFF)
+        };
+        let stop = offer.to_stop_offer();
+        assert!(stop.is_stop_offer_service());
+        assert!(!stop.is_offer_service());
+        entry_round_trip_test_with_bytes(
+            stop,
+            &[
+                0x01, 0x01, 0x04, 0x21, // Type (OfferService), Index 1st/2nd, # of opts
+                0xAB, 0xCD, 0x00, 0x02, // Service ID, Instance ID
+                0x03, 0x00, 0x00, 0x00, // Major Version, TTL (0 = StopOffer)
+                0x00, 0x00, 0x00, 0x10, // Minor Version
+            ],
+        );
+    }
+
+    #[test]
+    fn test_service_entry_invalid_type() {
+        let buffer = [
+            0x06, 0x00, 0x00, 0x00, // Type (SubscribeEventgroup)
+            0x12, 0x34, 0x00, 0x01, // Service ID, Instance ID
+            0x01, 0x00, 0x00, 0x03, // Major Version, TTL
+            0x00, 0x00, 0x00, 0x01, // Reserved, Counter, Eventgroup ID
+        ];
+        let packet = EntryPacket::new_unchecked(&buffer[..]);
+        assert_eq!(
+            ServiceEntryRepr::parse(&packet),
+            Err(Error::InvalidEntryType(0x06))
+        );
+
+        let packet = EntryPacket::new_unchecked(&buffer[..8]);
+        assert_eq!(ServiceEntryRepr::parse(&packet), Err(Error::BufferTooShort));
+    }
+
+    #[test]
+    fn test_eventgroup_entry_round_trip_subscribe() {
+        let repr = EventgroupEntryRepr {
+            first_options: OptionRun::new(0, 1),
+            initial_data_requested: true,
+            ..EventgroupEntryRepr::subscribe(0x1234, 0x0001, 0x01, 0x0010, 0x02, 5)
+        };
+        assert!(repr.is_subscribe());
+        assert!(!repr.is_stop_subscribe());
+
+        let expected = [
+            0x06, 0x00, 0x00, 0x10, // Type (Subscribe), Index 1st/2nd, # of opts
+            0x12, 0x34, 0x00, 0x01, // Service ID, Instance ID
+            0x01, 0x00, 0x00, 0x05, // Major Version, TTL
+            0x00, 0x82, 0x00, 0x10, // Reserved, Flags + Counter, Eventgroup ID
+        ];
+        let mut buffer = [0xFFu8; 16];
+        repr.emit(&mut EntryPacket::new_unchecked(&mut buffer));
+        assert_eq!(buffer, expected);
+
+        let packet = EntryPacket::new_checked(&buffer[..]).unwrap();
+        assert_eq!(EventgroupEntryRepr::parse(&packet).unwrap(), repr);
+        assert_eq!(
+            EntryRepr::parse(&packet).unwrap(),
+            EntryRepr::Eventgroup(repr)
+        );
+    }
+
+    #[test]
+    fn test_eventgroup_entry_classification() {
+        let subscribe = EventgroupEntryRepr::subscribe(0x1234, 0x0001, 0x01, 0x0010, 0x02, 5);
+
+        let stop = subscribe.to_stop_subscribe();
+        assert!(stop.is_stop_subscribe());
+        assert!(!stop.is_subscribe());
+
+        let ack = subscribe.to_ack();
+        assert_eq!(ack.entry_type, EntryType::SubscribeEventgroupAck);
+        assert!(ack.is_ack());
+        assert!(!ack.is_nack());
+        assert!(ack.answers(&subscribe));
+
+        let nack = subscribe.to_nack();
+        assert!(nack.is_nack());
+        assert!(!nack.is_ack());
+        assert!(nack.answers(&subscribe));
+
+        let other = EventgroupEntryRepr {
+            counter: 0x03,
+            ..subscribe
+        };
+        assert!(!ack.answers(&other));
+        assert!(!subscribe.answers(&subscribe));
+    }
+
+    #[test]
+    fn test_entry_repr_parse() {
+        let packet = SdPacket::new_checked(&SD_PAYLOAD[..]).unwrap();
+        let repr = SdRepr::parse(&packet).unwrap();
+        let entry = EntryRepr::parse(&repr.entries().next().unwrap()).unwrap();
+        assert_eq!(entry.entry_type(), EntryType::OfferService);
+        assert_eq!(entry.service_id(), 0x1234);
+        assert_eq!(entry.first_options(), OptionRun::new(0, 1));
+
+        let buffer = [0x02u8; 16];
+        let packet = EntryPacket::new_unchecked(&buffer[..]);
+        assert_eq!(
+            EntryRepr::parse(&packet),
+            Err(Error::InvalidEntryType(0x02))
+        );
+        assert_eq!(
+            EventgroupEntryRepr::parse(&packet),
+            Err(Error::InvalidEntryType(0x02))
+        );
+    }
+
+    fn option_round_trip_test_with_bytes(repr: OptionRepr, expected_bytes: &[u8]) {
+        let mut buffer = [0xFFu8; 64];
+        let buffer = &mut buffer[..repr.buffer_len()];
+        repr.emit(&mut OptionPacket::new_unchecked(&mut *buffer));
+        assert_eq!(&buffer[..], expected_bytes);
+
+        let packet = OptionPacket::new_checked(&buffer[..]).unwrap();
+        assert_eq!(OptionRepr::parse(&packet).unwrap(), repr);
+    }
+
+    #[test]
+    fn test_option_ipv4_endpoint() {
+        let packet = SdPacket::new_checked(&SD_PAYLOAD[..]).unwrap();
+        let repr = SdRepr::parse(&packet).unwrap();
+        let option = OptionRepr::parse(&repr.options().next().unwrap()).unwrap();
+
+        let endpoint = Ipv4EndpointRepr {
+            address: Ipv4Addr::new(192, 168, 0, 1),
+            protocol: L4Protocol::Udp,
+            port: 30000,
+        };
+        assert_eq!(option, OptionRepr::Ipv4Endpoint(endpoint));
+        assert_eq!(endpoint.socket_addr().to_string(), "192.168.0.1:30000");
+        option_round_trip_test_with_bytes(option, &SD_PAYLOAD[28..40]);
+    }
+
+    #[test]
+    fn test_option_round_trip_ipv4_multicast_and_sd_endpoint() {
+        let endpoint = Ipv4EndpointRepr {
+            address: Ipv4Addr::new(239, 0, 0, 1),
+            protocol: L4Protocol::Udp,
+            port: 30490,
+        };
+        option_round_trip_test_with_bytes(
+            OptionRepr::Ipv4Multicast(endpoint),
+            &[
+                0x00, 0x09, 0x14, 0x00, // Length, Type (IPv4 Multicast), Reserved
+                0xEF, 0x00, 0x00, 0x01, // IPv4 Address
+                0x00, 0x11, 0x77, 0x1A, // Reserved, L4 Protocol (UDP), Port
+            ],
+        );
+        option_round_trip_test_with_bytes(
+            OptionRepr::Ipv4SdEndpoint(endpoint),
+            &[
+                0x00, 0x09, 0x24, 0x00, // Length, Type (IPv4 SD Endpoint), Reserved
+                0xEF, 0x00, 0x00, 0x01, // IPv4 Address
+                0x00, 0x11, 0x77, 0x1A, // Reserved, L4 Protocol (UDP), Port
+            ],
+        );
+    }
+
+    #[test]
+    fn test_option_round_trip_ipv6() {
+        let endpoint = Ipv6EndpointRepr {
+            address: Ipv6Addr::new(0xFD00, 0, 0, 0, 0, 0, 0, 0x0001),
+            protocol: L4Protocol::Tcp,
+            port: 30501,
+        };
+        let address = [
+            0xFD, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, //
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, //
+        ];
+        for (repr, option_type) in [
+            (OptionRepr::Ipv6Endpoint(endpoint), 0x06),
+            (OptionRepr::Ipv6Multicast(endpoint), 0x16),
+            (OptionRepr::Ipv6SdEndpoint(endpoint), 0x26),
+        ] {
+            let mut expected = [0u8; 24];
+            expected[..4].copy_from_slice(&[0x00, 0x15, option_type, 0x00]);
+            expected[4..20].copy_from_slice(&address);
+            expected[20..].copy_from_slice(&[0x00, 0x06, 0x77, 0x25]);
+            option_round_trip_test_with_bytes(repr, &expected);
+        }
+    }
+
+    #[test]
+    fn test_option_unknown_and_errors() {
+        let unknown = OptionRepr::Unknown {
+            option_type: 0x42,
+            discardable: true,
+            data: &[0x01, 0x02],
+        };
+        option_round_trip_test_with_bytes(
+            unknown,
+            &[
+                0x00, 0x03, 0x42, 0x80, // Length, Type (unknown), Discardable flag
+                0x01, 0x02, // Data
+            ],
+        );
+
+        let buffer = [
+            0x00, 0x09, 0x04, 0x00, // Length, Type (IPv4 Endpoint), Reserved
+            0xC0, 0xA8, 0x00, 0x01, // IPv4 Address
+            0x00, 0x01, 0x75, 0x30, // Reserved, L4 Protocol (ICMP), Port
+        ];
+        let packet = OptionPacket::new_unchecked(&buffer[..]);
+        assert_eq!(
+            OptionRepr::parse(&packet),
+            Err(Error::InvalidL4Protocol(0x01))
+        );
+
+        let buffer = [
+            0x00, 0x05, 0x04, 0x00, // Length (too short), Type (IPv4 Endpoint), Reserved
+            0xC0, 0xA8, 0x00, 0x01, // IPv4 Address
+        ];
+        let packet = OptionPacket::new_unchecked(&buffer[..]);
+        assert_eq!(
+            OptionRepr::parse(&packet),
+            Err(Error::InvalidOptionLength(5))
+        );
+    }
+
+    #[test]
+    fn test_option_configuration() {
+        let buffer = [
+            0x00, 0x1B, 0x01, 0x00, // Length, Type (Configuration), Reserved
+            0x09, b'v', b'e', b'r', b's', b'i', b'o', b'n', b'=', b'3', // "version=3"
+            0x06, b'h', b'o', b's', b't', b'=', b'a', // "host=a"
+            0x07, b'p', b'r', b'i', b'm', b'a', b'r', b'y', // "primary"
+            0x00, // Terminator
+        ];
+        let packet = OptionPacket::new_checked(&buffer[..]).unwrap();
+        let OptionRepr::Configuration(config) = OptionRepr::parse(&packet).unwrap() else {
+            panic!("Expected Configuration option");
+        };
+
+        let mut items = config.items();
+        assert_eq!(items.next(), Some(ConfigurationItem::new("version", "3")));
+        assert_eq!(items.next(), Some(ConfigurationItem::new("host", "a")));
+        assert_eq!(items.next(), Some(ConfigurationItem::flag("primary")));
+        assert_eq!(items.next(), None);
+        assert_eq!(config.get("host").unwrap().value, Some("a"));
+        assert_eq!(config.get("missing"), None);
+
+        let mut data = [0u8; 32];
+        let emitted = ConfigurationRepr::emit_items(
+            &[
+                ConfigurationItem::new("version", "3"),
+                ConfigurationItem::new("host", "a"),
+                ConfigurationItem::flag("primary"),
+            ],
+            &mut data,
+        )
+        .unwrap();
+        assert_eq!(emitted, config);
+        option_round_trip_test_with_bytes(OptionRepr::Configuration(emitted), &buffer);
+    }
+
+    #[test]
+    fn test_option_configuration_errors() {
+        // Empty key
+        let data = [0x02, b'=', b'x', 0x00];
+        assert_eq!(
+            ConfigurationRepr::new_checked(&data),
+            Err(Error::InvalidConfigurationString)
+        );
+
+        // String runs past the end of the option
+        let data = [0x05, b'k', b'=', b'v'];
+        assert_eq!(ConfigurationRepr::new_checked(&data), Err(Error::Truncated));
+
+        let mut buffer = [0u8; 4];
+        assert_eq!(
+            ConfigurationRepr::emit_items(&[ConfigurationItem::new("key", "value")], &mut buffer),
+            Err(Error::BufferTooShort)
+        );
+        assert_eq!(
+            ConfigurationRepr::emit_items(&[ConfigurationItem::flag("a=b")], &mut buffer),
+            Err(Error::InvalidConfigurationString)
+        );
+    }
+
+    #[test]
+    fn test_option_too_large() {
+        let data = vec![0u8; OptionRepr::MAX_DATA_LENGTH + 1];
+        assert_eq!(
+            ConfigurationRepr::new_checked(&data),
+            Err(Error::OptionTooLarge(65535))
+        );
+        let config = ConfigurationRepr::new_checked(&data[1..]).unwrap();
+        assert_eq!(OptionRepr::Configuration(config).length(), u16::MAX);
+
+        let unknown = OptionRepr::Unknown {
+            option_type: 0x77,
+            discardable: true,
+            data: &data,
+        };
+        assert_eq!(unknown.check_length(), Err(Error::OptionTooLarge(65535)));
+        let mut buffer = vec![0u8; 2 * data.len()];
+        assert_eq!(
+            SdRepr::from_entries(&[], &[unknown], &mut buffer),
+            Err(Error::OptionTooLarge(65535))
+        );
+
+        let mut entries = [None; 1];
+        let mut options = [None; 1];
+        let mut builder = MessageBuilder::new(usize::MAX, &mut entries, &mut options);
+        let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 0x01, 0x0A, 3);
+        assert_eq!(
+            builder.push(offer.into(), &[unknown], &[]),
+            Err(Error::OptionTooLarge(65535))
+        );
+    }
+
+    #[test]
+    fn test_option_round_trip_load_balancing() {
+        option_round_trip_test_with_bytes(
+            OptionRepr::LoadBalancing(LoadBalancingRepr {
+                priority: 0x0001,
+                weight: 0x0032,
+            }),
+            &[
+                0x00, 0x05, 0x02, 0x00, // Length, Type (Load Balancing), Reserved
+                0x00, 0x01, 0x00, 0x32, // Priority, Weight
+            ],
+        );
+    }
+
+    #[test]
+    fn test_select_instance() {
+        let offer = |instance_id| ServiceEntryRepr::offer_service(0x1234, instance_id, 1, 0, 3);
+        let load_balancing = |priority, weight| Some(LoadBalancingRepr { priority, weight });
+        let candidates = [
+            (offer(0x0001), load_balancing(2, 100)),
+            (offer(0x0002), load_balancing(1, 10)),
+            (offer(0x0003), load_balancing(1, 30)),
+            (offer(0x0004), None),
+            (offer(0x0005).to_stop_offer(), load_balancing(0, 10)),
+            (
+                ServiceEntryRepr::offer_service(0x5678, 0x0006, 1, 0, 3),
+                load_balancing(0, 10),
+            ),
+        ];
+
+        // Only instances 2 and 3 share the best priority, weighted 10:30
+        assert_eq!(
+            select_instance(&candidates, 0x1234, 0).unwrap().instance_id,
+            0x0002
+        );
+        assert_eq!(
+            select_instance(&candidates, 0x1234, 9).unwrap().instance_id,
+            0x0002
+        );
+        assert_eq!(
+            select_instance(&candidates, 0x1234, 10)
+                .unwrap()
+                .instance_id,
+            0x0003
+        );
+        assert_eq!(
+            select_instance(&candidates, 0x1234, 39)
+                .unwrap()
+                .instance_id,
+            0x0003
+        );
+        assert_eq!(
+            select_instance(&candidates, 0x1234, 40)
+                .unwrap()
+                .instance_id,
+            0x0002
+        );
+
+        // Offers without a load balancing option have the lowest priority
+        assert_eq!(
+            select_instance(&candidates[3..], 0x1234, 7)
+                .unwrap()
+                .instance_id,
+            0x0004
+        );
+
+        // Zero weights fall back to the first instance of the best priority
+        let candidates = [
+            (offer(0x0007), load_balancing(1, 0)),
+            (offer(0x0008), load_balancing(1, 0)),
+        ];
+        assert_eq!(
+            select_instance(&candidates, 0x1234, 5).unwrap().instance_id,
+            0x0007
+        );
+
+        assert_eq!(select_instance(&candidates, 0x9999, 0), None);
+    }
+
+    #[test]
+    fn test_resolve_option_runs() {
+        let buffer = [
+            0x00, 0x00, 0x00, 0x00, // Flags + Reserved
+            0x00, 0x00, 0x00, 0x20, // Length of entries array
+            0x01, 0x00, 0x02, 0x11, // Type (OfferService), Index 1st/2nd, # of opts
+            0x12, 0x34, 0x00, 0x01, // Service ID, Instance ID
+            0x01, 0x00, 0x00, 0x03, // Major Version, TTL
+            0x00, 0x00, 0x00, 0x00, // Minor Version
+            0x06, 0x01, 0x00, 0x20, // Type (Subscribe), Index 1st/2nd, # of opts
+            0x12, 0x34, 0x00, 0x01, // Service ID, Instance ID
+            0x01, 0x00, 0x00, 0x03, // Major Version, TTL
+            0x00, 0x00, 0x00, 0x01, // Reserved, Counter, Eventgroup ID
+            0x00, 0x00, 0x00, 0x20, // Length of options array
+            0x00, 0x09, 0x04, 0x00, // Length, Type (IPv4 Endpoint), Reserved
+            0xC0, 0xA8, 0x00, 0x01, // IPv4 Address
+            0x00, 0x11, 0x75, 0x30, // Reserved, L4 Protocol (UDP), Port
+            0x00, 0x05, 0x02, 0x00, // Length, Type (Load Balancing), Reserved
+            0x00, 0x01, 0x00, 0x0A, // Priority, Weight
+            0x00, 0x09, 0x14, 0x00, // Length, Type (IPv4 Multicast), Reserved
+            0xEF, 0x00, 0x00, 0x01, // IPv4 Address
+            0x00, 0x11, 0x77, 0x1A, // Reserved, L4 Protocol (UDP), Port
+        ];
+        let packet = SdPacket::new_checked(&buffer[..]).unwrap();
+        let repr = SdRepr::parse(&packet).unwrap();
+        assert_eq!(repr.option_count(), 3);
+
+        let mut entries = repr.resolved_entries();
+
+        let (entry, options) = entries.next().unwrap().unwrap();
+        assert_eq!(entry.entry_type(), EntryType::OfferService);
+        let types: Vec<u8> = options.iter().map(|option| option.option_type()).collect();
+        assert_eq!(types, [0x04, 0x14]);
+        let mut first = options.first.clone();
+        assert_eq!(first.next().unwrap().option_type(), 0x04);
+        assert!(first.next().is_none());
+
+        let (entry, options) = entries.next().unwrap().unwrap();
+        assert_eq!(entry.entry_type(), EntryType::SubscribeEventgroup);
+        let types: Vec<u8> = options.iter().map(|option| option.option_type()).collect();
+        assert_eq!(types, [0x02, 0x14]);
+
+        assert!(entries.next().is_none());
+    }
+
+    #[test]
+    fn test_resolve_option_index_out_of_bounds() {
+        let packet = SdPacket::new_checked(&SD_PAYLOAD[..]).unwrap();
+        let repr = SdRepr::parse(&packet).unwrap();
+
+        assert!(repr.option_run(OptionRun::new(0, 1)).is_ok());
+        assert!(repr.option_run(OptionRun::new(7, 0)).is_ok());
+        assert_eq!(
+            repr.option_run(OptionRun::new(1, 1)).unwrap_err(),
+            Error::OptionIndexOutOfBounds(1)
+        );
+        assert_eq!(
+            repr.option_run(OptionRun::new(0, 2)).unwrap_err(),
+            Error::OptionIndexOutOfBounds(0)
+        );
+
+        let entry = EntryRepr::Service(ServiceEntryRepr {
+            second_options: OptionRun::new(3, 1),
+            ..ServiceEntryRepr::offer_service(0x1234, 0x0001, 1, 0, 3)
+        });
+        assert_eq!(
+            repr.resolve(&entry).unwrap_err(),
+            Error::OptionIndexOutOfBounds(3)
+        );
+    }
+
+    fn test_timing() -> Timing {
+        Timing {
+            initial_delay_min: Duration::from_millis(10),
+            initial_delay_max: Duration::from_millis(50),
+            repetitions_base_delay: Duration::from_millis(100),
+            repetitions_max: 3,
+            cyclic_offer_delay: Duration::from_millis(1000),
+        }
+    }
+
+    #[test]
+    fn test_service_announcer_phases() {
+        let endpoint = [OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+            address: Ipv4Addr::new(192, 168, 0, 1),
+            protocol: L4Protocol::Udp,
+            port: 30000,
+        })];
+        let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 0x01, 0x0A, 3);
+        let mut announcer = ServiceAnnouncer::new(offer, &endpoint, test_timing()).unwrap();
+        let mut buffer = [0u8; 64];
+
+        assert_eq!(announcer.phase(), Phase::Down);
+        assert_eq!(announcer.poll_at(), None);
+        assert_eq!(announcer.poll(Duration::ZERO, &mut buffer), Ok(None));
+
+        // Initial Wait: 10 ms + 25 ms selected by the random value
+        let start = Duration::from_secs(1);
+        announcer.start(start, 25_000);
+        assert_eq!(announcer.phase(), Phase::InitialWait);
+        assert_eq!(announcer.poll_at(), Some(start + Duration::from_millis(35)));
+        assert_eq!(announcer.poll(start, &mut buffer), Ok(None));
+
+        let mut now = announcer.poll_at().unwrap();
+        let message = announcer.poll(now, &mut buffer).unwrap().unwrap();
+        assert!(message.unicast);
+        assert!(!message.reboot);
+        assert_eq!(message.entries, &SD_PAYLOAD[8..24]);
+        assert_eq!(message.options, &SD_PAYLOAD[28..40]);
+
+        // Repetition: delays of 100, 200 and 400 ms
+        for delay in [100, 200, 400] {
+            assert_eq!(announcer.phase(), Phase::Repetition);
+            assert_eq!(
+                announcer.poll_at(),
+                Some(now + Duration::from_millis(delay))
+            );
+            now = announcer.poll_at().unwrap();
+            assert!(announcer.poll(now, &mut buffer).unwrap().is_some());
+        }
+
+        // Main: cyclic offers every second
+        for _ in 0..2 {
+            assert_eq!(announcer.phase(), Phase::Main);
+            assert_eq!(announcer.poll_at(), Some(now + Duration::from_millis(1000)));
+            assert_eq!(announcer.poll(now, &mut buffer), Ok(None));
+            now = announcer.poll_at().unwrap();
+            assert!(announcer.poll(now, &mut buffer).unwrap().is_some());
+        }
+
+        let find = ServiceEntryRepr::find_service(
+            0x1234,
+            ANY_INSTANCE,
+            ANY_MAJOR_VERSION,
+            ANY_MINOR_VERSION,
+            3,
+        );
+        assert!(announcer.answers_find(&find));
+        assert!(!announcer.answers_find(&ServiceEntryRepr {
+            instance_id: 0x0002,
+            ..find
+        }));
+
+        let message = announcer.stop(&mut buffer).unwrap().unwrap();
+        let packet = EntryPacket::new_checked(message.entries).unwrap();
+        assert!(
+            ServiceEntryRepr::parse(&packet)
+                .unwrap()
+                .is_stop_offer_service()
+        );
+        assert_eq!(announcer.phase(), Phase::Down);
+        assert_eq!(announcer.poll_at(), None);
+        assert!(!announcer.answers_find(&find));
+    }
+
+    #[test]
+    fn test_service_announcer_without_repetitions() {
+        let timing = Timing {
+            repetitions_max: 0,
+            cyclic_offer_delay: Duration::ZERO,
+            ..test_timing()
+        };
+        let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 0x01, 0x0A, 3);
+        let mut announcer = ServiceAnnouncer::new(offer, &[], timing).unwrap();
+        let mut buffer = [0u8; 16];
+
+        // Stopping before the first offer does not send a StopOffer
+        announcer.start(Duration::ZERO, u32::MAX);
+        assert_eq!(announcer.stop(&mut buffer), Ok(None));
+
+        announcer.start(Duration::ZERO, u32::MAX);
+        let now = announcer.poll_at().unwrap();
+        assert!(now >= Duration::from_millis(10) && now <= Duration::from_millis(50));
+        let message = announcer.poll(now, &mut buffer).unwrap().unwrap();
+        assert_eq!(message.options, &[]);
+        assert_eq!(announcer.phase(), Phase::Main);
+        assert_eq!(announcer.poll_at(), None);
+
+        assert_eq!(
+            announcer.offer(&mut buffer[..8]),
+            Err(Error::BufferTooShort)
+        );
+    }
+
+    #[test]
+    fn test_service_announcer_too_many_options() {
+        let endpoint = OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+            address: Ipv4Addr::new(192, 168, 0, 1),
+            protocol: L4Protocol::Udp,
+            port: 30000,
+        });
+        let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 0x01, 0x0A, 3);
+        let options = [endpoint; 16];
+        let announcer = ServiceAnnouncer::new(offer, &options[..15], test_timing()).unwrap();
+        assert_eq!(announcer.entry().first_options, OptionRun::new(0, 15));
+        assert_eq!(
+            ServiceAnnouncer::new(offer, &options, test_timing()).map(|_| ()),
+            Err(Error::EntryTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_service_finder() {
+        let find = ServiceEntryRepr::find_service(0x1234, ANY_INSTANCE, 0x01, ANY_MINOR_VERSION, 3);
+        let mut storage = [None; 2];
+        let mut finder = ServiceFinder::new(find, test_timing(), &mut storage);
+        let mut buffer = [0u8; 16];
+
+        finder.start(Duration::ZERO, 0);
+        assert_eq!(finder.phase(), Phase::InitialWait);
+        let mut now = finder.poll_at().unwrap();
+        assert_eq!(now, Duration::from_millis(10));
+        let message = finder.poll(now, &mut buffer).unwrap().unwrap();
+        let packet = EntryPacket::new_checked(message.entries).unwrap();
+        assert_eq!(ServiceEntryRepr::parse(&packet), Ok(find));
+        assert_eq!(message.options, &[]);
+
+        now = finder.poll_at().unwrap();
+        assert!(finder.poll(now, &mut buffer).unwrap().is_some());
+        assert_eq!(finder.phase(), Phase::Repetition);
+
+        // Offers of other services or versions are ignored
+        let other = ServiceEntryRepr::offer_service(0x1235, 0x0001, 0x01, 0x0A, 3);
+        assert_eq!(finder.process_entry(now, &other), Ok(None));
+        let other = ServiceEntryRepr::offer_service(0x1234, 0x0001, 0x02, 0x0A, 3);
+        assert_eq!(finder.process_entry(now, &other), Ok(None));
+        assert_eq!(finder.phase(), Phase::Repetition);
+
+        // A matching offer stops the search
+        let first = ServiceEntryRepr::offer_service(0x1234, 0x0001, 0x01, 0x0A, 3);
+        assert_eq!(
+            finder.process_entry(now, &first),
+            Ok(Some(ServiceEvent::Up(first)))
+        );
+        assert_eq!(finder.phase(), Phase::Main);
+        assert_eq!(finder.poll_at(), Some(now + Duration::from_secs(3)));
+        assert_eq!(
+            finder.poll(now + Duration::from_secs(2), &mut buffer),
+            Ok(None)
+        );
+
+        // Renewing the offer extends its lifetime without another event
+        now += Duration::from_secs(2);
+        assert_eq!(finder.process_entry(now, &first), Ok(None));
+        assert_eq!(finder.expire(now + Duration::from_secs(2)), None);
+        assert_eq!(finder.poll_at(), Some(now + Duration::from_secs(3)));
+
+        let second = ServiceEntryRepr::offer_service(0x1234, 0x0002, 0x01, 0x0A, TTL_INFINITE);
+        assert_eq!(
+            finder.process_entry(now, &second),
+            Ok(Some(ServiceEvent::Up(second)))
+        );
+        let third = ServiceEntryRepr::offer_service(0x1234, 0x0003, 0x01, 0x0A, 3);
+        assert_eq!(finder.process_entry(now, &third), Err(Error::StorageFull));
+        assert_eq!(finder.instances().count(), 2);
+
+        now += Duration::from_secs(3);
+        assert_eq!(finder.expire(now), Some(ServiceEvent::Down(first)));
+        assert_eq!(finder.expire(now), None);
+        assert_eq!(finder.poll_at(), None);
+
+        // StopOffer removes the instance; unknown instances are ignored
+        assert_eq!(
+            finder.process_entry(now, &second.to_stop_offer()),
+            Ok(Some(ServiceEvent::Down(second)))
+        );
+        assert_eq!(finder.process_entry(now, &third.to_stop_offer()), Ok(None));
+        assert_eq!(finder.instances().count(), 0);
+
+        // Restarting with an available instance skips the search
+        finder.stop();
+        assert_eq!(finder.phase(), Phase::Down);
+        assert_eq!(
+            finder.process_entry(now, &first),
+            Ok(Some(ServiceEvent::Up(first)))
+        );
+        finder.start(now, 0);
+        assert_eq!(finder.phase(), Phase::Main);
+    }
+
+    #[test]
+    fn test_subscription_manager() {
+        let endpoint = [OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+            address: Ipv4Addr::new(192, 168, 0, 2),
+            protocol: L4Protocol::Udp,
+            port: 40000,
+        })];
+        let mut storage = [None; 2];
+        let mut manager =
+            SubscriptionManager::new(&endpoint, Duration::from_secs(1), &mut storage).unwrap();
+        let mut buffer = [0u8; 64];
+
+        let subscribe = EventgroupEntryRepr::subscribe(0x1234, 0x0001, 0x01, 0x0010, 0, 4);
+        let other = EventgroupEntryRepr::subscribe(0x1234, 0x0002, 0x01, 0x0010, 0, 4);
+        manager.add(subscribe).unwrap();
+        manager.add(other).unwrap();
+        assert_eq!(
+            manager.add(EventgroupEntryRepr::subscribe(
+                0x1234, 0x0001, 0x01, 0x0020, 0, 4
+            )),
+            Err(Error::StorageFull)
+        );
+        let state = |manager: &SubscriptionManager, instance_id| {
+            manager.get(0x1234, instance_id, 0x0010).unwrap().state()
+        };
+        assert_eq!(state(&manager, 0x0001), SubscriptionState::Down);
+        assert_eq!(manager.poll_at(), None);
+
+        // The offer of instance 1 requests its subscription only
+        let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 0x01, 0x0A, 3);
+        let mut now = Duration::from_secs(10);
+        assert_eq!(manager.process_entry(now, &offer.into()), None);
+        assert_eq!(state(&manager, 0x0001), SubscriptionState::Requested);
+        assert_eq!(state(&manager, 0x0002), SubscriptionState::Down);
+        assert_eq!(manager.poll_at(), Some(now));
+
+        let message = manager.poll(now, &mut buffer).unwrap().unwrap();
+        let resolved = message.resolved_entries().next().unwrap().unwrap();
+        let EntryRepr::Eventgroup(sent) = resolved.0 else {
+            panic!("expected an eventgroup entry");
+        };
+        assert!(sent.is_subscribe());
+        assert_eq!(sent.first_options, OptionRun::new(0, 1));
+        let option = resolved.1.iter().next().unwrap();
+        assert_eq!(OptionRepr::parse(&option), Ok(endpoint[0]));
+        assert_eq!(manager.poll(now, &mut buffer), Ok(None));
+
+        // Renewal 1 s before the TTL of 4 s runs out
+        assert_eq!(manager.poll_at(), Some(now + Duration::from_secs(3)));
+
+        // A repeated offer does not request an active subscription again
+        assert_eq!(manager.process_entry(now, &offer.into()), None);
+        assert_eq!(manager.poll_at(), Some(now + Duration::from_secs(3)));
+
+        // Answers of other subscriptions are ignored
+        let ack = subscribe.to_ack();
+        let stale = EventgroupEntryRepr { counter: 1, ..ack };
+        assert_eq!(manager.process_entry(now, &stale.into()), None);
+        assert_eq!(
+            manager.process_entry(now, &ack.into()),
+            Some(SubscriptionEvent::Subscribed(sent))
+        );
+        assert_eq!(state(&manager, 0x0001), SubscriptionState::Subscribed);
+
+        now += Duration::from_secs(3);
+        assert!(manager.poll(now, &mut buffer).unwrap().is_some());
+        assert_eq!(manager.process_entry(now, &ack.into()), None);
+        assert_eq!(manager.expire(now + Duration::from_secs(3)), None);
+
+        // Without acknowledged renewal the subscription expires
+        now += Duration::from_secs(4);
+        assert_eq!(manager.expire(now), Some(SubscriptionEvent::Expired(sent)));
+        assert_eq!(manager.expire(now), None);
+        assert_eq!(state(&manager, 0x0001), SubscriptionState::Down);
+
+        // A Nack rejects the subscription until the next offer
+        manager.process_entry(now, &offer.into());
+        assert!(manager.poll(now, &mut buffer).unwrap().is_some());
+        assert_eq!(
+            manager.process_entry(now, &subscribe.to_nack().into()),
+            Some(SubscriptionEvent::Rejected(sent))
+        );
+        assert_eq!(state(&manager, 0x0001), SubscriptionState::Rejected);
+        assert_eq!(manager.poll_at(), None);
+
+        // StopOffer tears the subscription down
+        manager.process_entry(now, &offer.into());
+        manager.process_entry(now, &ack.into());
+        let stop = offer.to_stop_offer().into();
+        assert_eq!(
+            manager.process_entry(now, &stop),
+            Some(SubscriptionEvent::Stopped(sent))
+        );
+        assert_eq!(manager.process_entry(now, &stop), None);
+        assert_eq!(state(&manager, 0x0001), SubscriptionState::Down);
+        assert_eq!(manager.poll_at(), None);
+
+        // Removing an active subscription sends StopSubscribeEventgroup
+        manager.process_entry(now, &offer.into());
+        let message = manager
+            .remove(0x1234, 0x0001, 0x0010, &mut buffer)
+            .unwrap()
+            .unwrap();
+        let packet = EntryPacket::new_checked(message.entries).unwrap();
+        assert!(
+            EventgroupEntryRepr::parse(&packet)
+                .unwrap()
+                .is_stop_subscribe()
+        );
+        assert_eq!(manager.get(0x1234, 0x0001, 0x0010), None);
+        assert_eq!(
+            manager.remove(0x1234, 0x0002, 0x0010, &mut buffer),
+            Ok(None)
+        );
+        assert_eq!(manager.subscriptions().count(), 0);
+    }
+
+    #[test]
+    fn test_subscription_manager_stop_offer() {
+        let mut storage = [None; 3];
+        let mut manager =
+            SubscriptionManager::new(&[], Duration::from_secs(1), &mut storage).unwrap();
+        let first = EventgroupEntryRepr::subscribe(0x1234, 0x0001, 0x01, 0x0010, 0, 4);
+        let second = EventgroupEntryRepr::subscribe(0x1234, 0x0001, 0x01, 0x0020, 0, 4);
+        let other = EventgroupEntryRepr::subscribe(0x1234, 0x0002, 0x01, 0x0010, 0, 4);
+        for subscribe in [first, second, other] {
+            manager.add(subscribe).unwrap();
+        }
+
+        let now = Duration::from_secs(10);
+        let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 0x01, 0x0A, 3);
+        manager.process_entry(now, &offer.into());
+        manager.process_entry(now, &first.to_ack().into());
+        let state = |manager: &SubscriptionManager, eventgroup_id| {
+            manager.get(0x1234, 0x0001, eventgroup_id).unwrap().state()
+        };
+        assert_eq!(state(&manager, 0x0010), SubscriptionState::Subscribed);
+        assert_eq!(state(&manager, 0x0020), SubscriptionState::Requested);
+
+        // Both active subscriptions of the instance are reported, one per call
+        let stop = offer.to_stop_offer().into();
+        assert_eq!(
+            manager.process_entry(now, &stop),
+            Some(SubscriptionEvent::Stopped(first))
+        );
+        assert_eq!(
+            manager.process_entry(now, &stop),
+            Some(SubscriptionEvent::Stopped(second))
+        );
+        assert_eq!(manager.process_entry(now, &stop), None);
+        assert_eq!(state(&manager, 0x0010), SubscriptionState::Down);
+        assert_eq!(state(&manager, 0x0020), SubscriptionState::Down);
+        assert_eq!(manager.poll_at(), None);
+        assert_eq!(
+            SubscriptionEvent::Stopped(first).to_string(),
+            "Eventgroup 0x1234.0x0001.0x0010 stopped"
+        );
+    }
+
+    #[test]
+    fn test_subscription_manager_too_many_options() {
+        let endpoint = OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+            address: Ipv4Addr::new(192, 168, 0, 2),
+            protocol: L4Protocol::Udp,
+            port: 40000,
+        });
+        let options = [endpoint; 16];
+        let mut storage = [None; 1];
+        let mut manager =
+            SubscriptionManager::new(&options[..15], Duration::from_secs(1), &mut storage).unwrap();
+        let subscribe = EventgroupEntryRepr::subscribe(0x1234, 0x0001, 0x01, 0x0010, 0, 4);
+        manager.add(subscribe).unwrap();
+        let added = manager.get(0x1234, 0x0001, 0x0010).unwrap();
+        assert_eq!(added.entry().first_options, OptionRun::new(0, 15));
+
+        let mut storage = [None; 1];
+        assert_eq!(
+            SubscriptionManager::new(&options, Duration::from_secs(1), &mut storage).map(|_| ()),
+            Err(Error::EntryTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_reboot_detection_rules() {
+        let state = |reboot, session_id| SessionState { reboot, session_id };
+        // Reboot flag changes from 0 to 1
+        assert!(state(false, 0x1234).is_rebooted_by(&state(true, 0x0001)));
+        // Reboot flag stays 1 and the session ID does not increase
+        assert!(state(true, 0x0005).is_rebooted_by(&state(true, 0x0005)));
+        assert!(state(true, 0x0005).is_rebooted_by(&state(true, 0x0002)));
+        assert!(!state(true, 0x0005).is_rebooted_by(&state(true, 0x0006)));
+        // Session ID wraps around after the reboot flag has been cleared
+        assert!(!state(true, 0xFFFF).is_rebooted_by(&state(false, 0x0001)));
+        assert!(!state(false, 0xFFFF).is_rebooted_by(&state(false, 0x0001)));
+    }
+
+    #[test]
+    fn test_reboot_detector() {
+        let request_id = |session_id| RequestId {
+            client_id: ClientId::from_u16(0x0000),
+            session_id,
+        };
+        let peer: SocketAddr = "192.168.0.1:30490".parse().unwrap();
+        let other: SocketAddr = "192.168.0.2:30490".parse().unwrap();
+        let mut storage = [None; 1];
+        let mut detector = RebootDetector::new(&mut storage);
+
+        let unicast = Channel::Unicast;
+        let multicast = Channel::Multicast;
+        assert_eq!(
+            detector.process(peer, multicast, request_id(1), true),
+            Ok(None)
+        );
+        assert_eq!(
+            detector.process(peer, multicast, request_id(2), true),
+            Ok(None)
+        );
+
+        // Channels are tracked separately
+        assert_eq!(
+            detector.process(peer, unicast, request_id(1), true),
+            Ok(None)
+        );
+        assert_eq!(
+            detector.process(peer, unicast, request_id(2), true),
+            Ok(None)
+        );
+        assert_eq!(
+            detector.process(peer, multicast, request_id(1), true),
+            Ok(Some(RebootEvent {
+                peer,
+                channel: multicast,
+            }))
+        );
+        assert_eq!(
+            detector.process(peer, unicast, request_id(3), true),
+            Ok(None)
+        );
+        assert_eq!(
+            detector.get(peer).unwrap().unicast,
+            Some(SessionState {
+                reboot: true,
+                session_id: 3,
+            })
+        );
+
+        assert_eq!(
+            detector.process(other, unicast, request_id(1), true),
+            Err(Error::StorageFull)
+        );
+        detector.remove(peer);
+        assert_eq!(detector.peers().count(), 0);
+
+        let sd_packet = SdPacket::new_checked(&SD_PAYLOAD[..]).unwrap();
+        let sd_repr = SdRepr::parse(&sd_packet).unwrap();
+        assert_eq!(
+            detector.process_repr(other, unicast, request_id(9), &sd_repr),
+            Ok(None)
+        );
+        assert_eq!(
+            detector.process_repr(other, unicast, request_id(1), &sd_repr),
+            Ok(Some(RebootEvent {
+                peer: other,
+                channel: unicast,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_message_builder_split() {
+        let endpoints = [
+            OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+                address: Ipv4Addr::new(192, 168, 0, 1),
+                protocol: L4Protocol::Udp,
+                port: 30000,
+            }),
+            OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+                address: Ipv4Addr::new(192, 168, 0, 1),
+                protocol: L4Protocol::Tcp,
+                port: 30000,
+            }),
+        ];
+        let mut entries = [None; 8];
+        let mut options = [None; 4];
+        // SOME/IP and SD headers, two entries and one endpoint option
+        let mut builder = MessageBuilder::new(28 + 2 * 16 + 12, &mut entries, &mut options);
+        assert_eq!(builder.capacity(), 44);
+
+        for instance_id in 1..=5 {
+            let offer = ServiceEntryRepr::offer_service(0x1234, instance_id, 0x01, 0x0A, 3);
+            builder.push(offer.into(), &endpoints[..1], &[]).unwrap();
+        }
+        assert_eq!(builder.len(), 5);
+
+        let mut buffer = [0u8; 64];
+        let mut instances = Vec::new();
+        for expected in [2, 2, 1] {
+            let message = builder.next_message(&mut buffer).unwrap().unwrap();
+            assert!(message.buffer_len() <= 44 + field::sd::HEADER_LENGTH);
+            assert_eq!(message.entries().count(), expected);
+            assert_eq!(message.option_count(), 1);
+            for resolved in message.resolved_entries() {
+                let (entry, options) = resolved.unwrap();
+                assert_eq!(entry.first_options(), OptionRun::new(0, 1));
+                let option = options.iter().next().unwrap();
+                assert_eq!(OptionRepr::parse(&option), Ok(endpoints[0]));
+                instances.push(entry.instance_id());
+            }
+        }
+        assert_eq!(instances, [1, 2, 3, 4, 5]);
+        assert!(builder.is_empty());
+        assert_eq!(builder.next_message(&mut buffer), Ok(None));
+
+        // Entries with different options are packed into separate messages
+        let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 0x01, 0x0A, 3);
+        builder.push(offer.into(), &endpoints[..1], &[]).unwrap();
+        builder.push(offer.into(), &endpoints[1..], &[]).unwrap();
+        let message = builder.next_message(&mut buffer).unwrap().unwrap();
+        assert_eq!(message.entries().count(), 1);
+        let message = builder.next_message(&mut buffer).unwrap().unwrap();
+        assert_eq!(message.entries().count(), 1);
+        let option = OptionPacket::new_checked(message.options).unwrap();
+        assert_eq!(OptionRepr::parse(&option), Ok(endpoints[1]));
+    }
+
+    #[test]
+    fn test_message_builder_order() {
+        let endpoints = [
+            OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+                address: Ipv4Addr::new(192, 168, 0, 1),
+                protocol: L4Protocol::Udp,
+                port: 30000,
+            }),
+            OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+                address: Ipv4Addr::new(192, 168, 0, 1),
+                protocol: L4Protocol::Tcp,
+                port: 30000,
+            }),
+        ];
+        let mut entries = [None; 4];
+        let mut options = [None; 4];
+        // SOME/IP and SD headers, two entries and one endpoint option
+        let mut builder = MessageBuilder::new(28 + 2 * 16 + 12, &mut entries, &mut options);
+        let offer = |instance_id| ServiceEntryRepr::offer_service(0x1234, instance_id, 1, 0, 3);
+        builder.push(offer(1).into(), &endpoints[..1], &[]).unwrap();
+        builder.push(offer(2).into(), &endpoints[1..], &[]).unwrap();
+        builder.push(offer(3).into(), &endpoints[..1], &[]).unwrap();
+
+        let mut buffer = [0u8; 64];
+        let mut instances = |builder: &mut MessageBuilder| {
+            let message = builder.next_message(&mut buffer).unwrap().unwrap();
+            message
+                .entries()
+                .map(|entry| EntryRepr::parse(&entry).unwrap().instance_id())
+                .collect::<Vec<_>>()
+        };
+        // The third entry would fit next to the first one, but does not overtake the second
+        assert_eq!(instances(&mut builder), [1]);
+        builder.push(offer(4).into(), &endpoints[..1], &[]).unwrap();
+        assert_eq!(instances(&mut builder), [2]);
+        assert_eq!(instances(&mut builder), [3, 4]);
+        assert!(builder.is_empty());
+    }
+
+    #[test]
+    fn test_message_builder_overlapping_runs() {
+        let endpoint = Ipv4EndpointRepr {
+            address: Ipv4Addr::new(192, 168, 0, 1),
+            protocol: L4Protocol::Udp,
+            port: 30000,
+        };
+        let udp = OptionRepr::Ipv4Endpoint(endpoint);
+        let tcp = OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+            protocol: L4Protocol::Tcp,
+            ..endpoint
+        });
+        let load_balancing = OptionRepr::LoadBalancing(LoadBalancingRepr {
+            priority: 1,
+            weight: 1,
+        });
+        let runs = [[udp, tcp], [tcp, load_balancing], [udp, load_balancing]];
+        let mut entries = [None; 3];
+        let mut options = [None; 8];
+        let mut builder = MessageBuilder::new(1400, &mut entries, &mut options);
+        for (instance_id, run) in (1..).zip(&runs) {
+            let offer = ServiceEntryRepr::offer_service(0x1234, instance_id, 0x01, 0x0A, 3);
+            builder.push(offer.into(), run, &[]).unwrap();
+        }
+
+        let mut buffer = [0u8; 256];
+        let message = builder.next_message(&mut buffer).unwrap().unwrap();
+        // The second run continues the first one; the third run cannot share its
+        // options, as they are not adjacent
+        assert_eq!(message.option_count(), 5);
+        let mut resolved = message.resolved_entries();
+        for (expected, run) in [
+            OptionRun::new(0, 2),
+            OptionRun::new(1, 2),
+            OptionRun::new(3, 2),
+        ]
+        .into_iter()
+        .zip(&runs)
+        {
+            let (entry, options) = resolved.next().unwrap().unwrap();
+            assert_eq!(entry.first_options(), expected);
+            let options: Vec<_> = options
+                .iter()
+                .map(|option| OptionRepr::parse(&option).unwrap())
+                .collect();
+            assert_eq!(options, run);
+        }
+    }
+
+    #[test]
+    fn test_message_builder_shared_options() {
+        let endpoints = [
+            OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+                address: Ipv4Addr::new(192, 168, 0, 1),
+                protocol: L4Protocol::Udp,
+                port: 30000,
+            }),
+            OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr {
+                address: Ipv4Addr::new(192, 168, 0, 1),
+                protocol: L4Protocol::Tcp,
+                port: 30000,
+            }),
+            OptionRepr::LoadBalancing(LoadBalancingRepr {
+                priority: 1,
+                weight: 1,
+            }),
+        ];
+        let mut entries = [None; 2];
+        let mut options = [None; 4];
+        let mut builder = MessageBuilder::new(1400, &mut entries, &mut options);
+
+        let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 0x01, 0x0A, 3);
+        builder
+            .push(offer.into(), &endpoints[..2], &endpoints[2..])
+            .unwrap();
+        let offer = ServiceEntryRepr {
+            instance_id: 0x0002,
+            ..offer
+        };
+        builder.push(offer.into(), &endpoints[1..2], &[]).unwrap();
+        assert_eq!(
+            builder.push(offer.into(), &[], &[]),
+            Err(Error::StorageFull)
+        );
+
+        let mut buffer = [0u8; 128];
+        assert_eq!(
+            builder.next_message(&mut buffer[..20]),
+            Err(Error::BufferTooShort)
+        );
+        let message = builder.next_message(&mut buffer).unwrap().unwrap();
+        assert_eq!(message.option_count(), 3);
+        let runs: Vec<_> = message
+            .entries()
+            .map(|entry| {
+                let entry = EntryRepr::parse(&entry).unwrap();
+                (entry.first_options(), entry.second_options())
+            })
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                (OptionRun::new(0, 2), OptionRun::new(2, 1)),
+                (OptionRun::new(1, 1), OptionRun::new(0, 0)),
+            ]
+        );
+
+        let many = [endpoints[0]; 16];
+        assert_eq!(
+            builder.push(offer.into(), &many, &[]),
+            Err(Error::EntryTooLarge)
+        );
+        let mut builder = MessageBuilder::new(28 + 16, &mut entries, &mut options);
+        assert_eq!(
+            builder.push(offer.into(), &endpoints[..1], &[]),
+            Err(Error::EntryTooLarge)
+        );
+        assert_eq!(builder.push(offer.into(), &[], &[]), Ok(()));
+    }
+}
@@ -0,0 +1,890 @@
+//! SD option module
+//!
+//! This module contains the `OptionPacket` type, which is a read/write wrapper around a single
+//! SOME/IP-SD option, and the typed representations of the options it can hold.
+
+use crate::error::Error;
+use crate::field;
+use crate::packet::Result;
+use crate::sd::entry::ServiceEntryRepr;
+use byteorder::{ByteOrder, NetworkEndian};
+use core::fmt::{self, Display};
+use core::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
+/// A read/write wrapper around a single SOME/IP-SD option buffer.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OptionPacket<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+impl<T: AsRef<[u8]>> OptionPacket<T> {
+    /// Creates a new unchecked `OptionPacket`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - A buffer containing the option data.
+    ///
+    /// # Returns
+    ///
+    /// * `OptionPacket` - A new `OptionPacket` instance.
+    pub const fn new_unchecked(buffer: T) -> OptionPacket<T> {
+        OptionPacket { buffer }
+    }
+
+    /// Creates a new checked `OptionPacket`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - A buffer containing the option data.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<OptionPacket>` - A new `OptionPacket` instance if the buffer is valid.
+    pub fn new_checked(buffer: T) -> Result<OptionPacket<T>> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    /// Checks the length of the option.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Ok if the buffer holds the whole option, otherwise an error.
+    pub fn check_len(&self) -> Result<()> {
+        let len = self.buffer.as_ref().len();
+        if len < field::sd_option::TYPE.end {
+            return Err(Error::BufferTooShort);
+        }
+        let length = self.length();
+        if length == 0 {
+            return Err(Error::InvalidOptionLength(length));
+        }
+        if len < self.total_length() {
+            return Err(Error::Truncated);
+        }
+        Ok(())
+    }
+
+    /// Returns the inner buffer.
+    ///
+    /// # Returns
+    ///
+    /// * `T` - The inner buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// Returns the Length
+    ///
+    /// # Returns
+    ///
+    /// * `u16` - The raw length field, counting the bytes following the type field
+    pub fn length(&self) -> u16 {
+        NetworkEndian::read_u16(&self.buffer.as_ref()[field::sd_option::LENGTH])
+    }
+
+    /// Returns the Type (raw u8)
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The raw option type byte
+    pub fn option_type(&self) -> u8 {
+        self.buffer.as_ref()[field::sd_option::TYPE.start]
+    }
+
+    /// Returns the Discardable flag
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the option may be discarded by receivers that do not support it
+    pub fn discardable(&self) -> bool {
+        self.buffer.as_ref()[field::sd_option::RESERVED.start] & field::sd_option::DISCARDABLE_FLAG
+            != 0
+    }
+
+    /// Returns the total length of the option
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The length of the option including the length and type fields
+    pub fn total_length(&self) -> usize {
+        field::sd_option::TYPE.end + self.length() as usize
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> OptionPacket<T> {
+    /// Sets the Length
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The new length field, counting the bytes following the type field
+    pub fn set_length(&mut self, length: u16) {
+        NetworkEndian::write_u16(&mut self.buffer.as_mut()[field::sd_option::LENGTH], length);
+    }
+
+    /// Sets the Type (raw u8)
+    ///
+    /// # Arguments
+    ///
+    /// * `option_type` - The new option type byte to set
+    pub fn set_option_type(&mut self, option_type: u8) {
+        self.buffer.as_mut()[field::sd_option::TYPE.start] = option_type;
+    }
+
+    /// Sets the Discardable flag, clearing the remaining reserved bits
+    ///
+    /// # Arguments
+    ///
+    /// * `discardable` - Whether the option may be discarded by receivers
+    pub fn set_discardable(&mut self, discardable: bool) {
+        self.buffer.as_mut()[field::sd_option::RESERVED.start] = if discardable {
+            field::sd_option::DISCARDABLE_FLAG
+        } else {
+            0
+        };
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> OptionPacket<&'a T> {
+    /// Returns a reference to the option data following the reserved field.
+    ///
+    /// # Returns
+    ///
+    /// * `&'a [u8]` - A reference to the option data.
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        let range = field::sd_option::data(self.length() as usize);
+        &self.buffer.as_ref()[range]
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]> + ?Sized> OptionPacket<&mut T> {
+    /// Returns a mutable reference to the option data following the reserved field.
+    ///
+    /// # Returns
+    ///
+    /// * `&mut [u8]` - A mutable reference to the option data.
+    #[inline]
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        let range = field::sd_option::data(self.length() as usize);
+        &mut self.buffer.as_mut()[range]
+    }
+}
+
+/// Option Type for SOME/IP-SD options
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OptionType {
+    /// Configuration option (0x01)
+    Configuration,
+    /// Load Balancing option (0x02)
+    LoadBalancing,
+    /// IPv4 Endpoint option (0x04)
+    Ipv4Endpoint,
+    /// IPv6 Endpoint option (0x06)
+    Ipv6Endpoint,
+    /// IPv4 Multicast option (0x14)
+    Ipv4Multicast,
+    /// IPv6 Multicast option (0x16)
+    Ipv6Multicast,
+    /// IPv4 SD Endpoint option (0x24)
+    Ipv4SdEndpoint,
+    /// IPv6 SD Endpoint option (0x26)
+    Ipv6SdEndpoint,
+}
+
+impl OptionType {
+    /// Convert from wire format (u8) to OptionType
+    ///
+    /// Returns None if the value doesn't correspond to a known option type
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0x01 => Some(OptionType::Configuration),
+            0x02 => Some(OptionType::LoadBalancing),
+            0x04 => Some(OptionType::Ipv4Endpoint),
+            0x06 => Some(OptionType::Ipv6Endpoint),
+            0x14 => Some(OptionType::Ipv4Multicast),
+            0x16 => Some(OptionType::Ipv6Multicast),
+            0x24 => Some(OptionType::Ipv4SdEndpoint),
+            0x26 => Some(OptionType::Ipv6SdEndpoint),
+            _ => None,
+        }
+    }
+
+    /// Convert to wire format (u8)
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            OptionType::Configuration => 0x01,
+            OptionType::LoadBalancing => 0x02,
+            OptionType::Ipv4Endpoint => 0x04,
+            OptionType::Ipv6Endpoint => 0x06,
+            OptionType::Ipv4Multicast => 0x14,
+            OptionType::Ipv6Multicast => 0x16,
+            OptionType::Ipv4SdEndpoint => 0x24,
+            OptionType::Ipv6SdEndpoint => 0x26,
+        }
+    }
+}
+
+// Convenience: convert to u8
+impl From<OptionType> for u8 {
+    fn from(option_type: OptionType) -> Self {
+        option_type.as_u8()
+    }
+}
+
+impl Display for OptionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionType::Configuration => write!(f, "Configuration"),
+            OptionType::LoadBalancing => write!(f, "Load Balancing"),
+            OptionType::Ipv4Endpoint => write!(f, "IPv4 Endpoint"),
+            OptionType::Ipv6Endpoint => write!(f, "IPv6 Endpoint"),
+            OptionType::Ipv4Multicast => write!(f, "IPv4 Multicast"),
+            OptionType::Ipv6Multicast => write!(f, "IPv6 Multicast"),
+            OptionType::Ipv4SdEndpoint => write!(f, "IPv4 SD Endpoint"),
+            OptionType::Ipv6SdEndpoint => write!(f, "IPv6 SD Endpoint"),
+        }
+    }
+}
+
+/// Transport layer protocol of an endpoint option
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum L4Protocol {
+    /// TCP (0x06)
+    Tcp,
+    /// UDP (0x11)
+    Udp,
+}
+
+impl L4Protocol {
+    /// Convert from wire format (u8) to L4Protocol
+    ///
+    /// Returns None if the value is neither TCP nor UDP
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0x06 => Some(L4Protocol::Tcp),
+            0x11 => Some(L4Protocol::Udp),
+            _ => None,
+        }
+    }
+
+    /// Convert to wire format (u8)
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            L4Protocol::Tcp => 0x06,
+            L4Protocol::Udp => 0x11,
+        }
+    }
+}
+
+// Convenience: convert to u8
+impl From<L4Protocol> for u8 {
+    fn from(protocol: L4Protocol) -> Self {
+        protocol.as_u8()
+    }
+}
+
+impl Display for L4Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            L4Protocol::Tcp => write!(f, "TCP"),
+            L4Protocol::Udp => write!(f, "UDP"),
+        }
+    }
+}
+
+/// A high-level representation of a load balancing option.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct LoadBalancingRepr {
+    /// Priority of the instance, lower values are preferred
+    pub priority: u16,
+    /// Weight of the instance among instances of the same priority
+    pub weight: u16,
+}
+
+impl LoadBalancingRepr {
+    /// Load balancing assumed for offers without a load balancing option:
+    /// the lowest priority with a weight of one.
+    pub const DEFAULT: LoadBalancingRepr = LoadBalancingRepr {
+        priority: u16::MAX,
+        weight: 1,
+    };
+
+    fn parse(data: &[u8], length: u16) -> core::result::Result<Self, Error> {
+        if length != field::sd_load_balancing_option::LENGTH {
+            return Err(Error::InvalidOptionLength(length));
+        }
+        Ok(LoadBalancingRepr {
+            priority: NetworkEndian::read_u16(&data[field::sd_load_balancing_option::PRIORITY]),
+            weight: NetworkEndian::read_u16(&data[field::sd_load_balancing_option::WEIGHT]),
+        })
+    }
+
+    fn emit(&self, data: &mut [u8]) {
+        NetworkEndian::write_u16(
+            &mut data[field::sd_load_balancing_option::PRIORITY],
+            self.priority,
+        );
+        NetworkEndian::write_u16(
+            &mut data[field::sd_load_balancing_option::WEIGHT],
+            self.weight,
+        );
+    }
+}
+
+impl Display for LoadBalancingRepr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "priority={}, weight={}", self.priority, self.weight)
+    }
+}
+
+/// Selects one of several offered instances of a service following the load balancing rules.
+///
+/// Only OfferService entries (with a non-zero TTL) for `service_id` are considered.
+/// Among them, the instances with the lowest priority value are kept, and one of those
+/// is picked with a probability proportional to its weight. Offers without a load
+/// balancing option use [`LoadBalancingRepr::DEFAULT`].
+///
+/// The selection is deterministic: the caller supplies the random value, which makes
+/// the outcome reproducible in tests.
+///
+/// # Arguments
+///
+/// * `candidates` - Offer entries paired with their load balancing option, if any
+/// * `service_id` - The service to select an instance for
+/// * `random` - A random value used for the weighted choice
+///
+/// # Returns
+///
+/// Returns the selected entry, or None if no matching offer exists.
+pub fn select_instance(
+    candidates: &[(ServiceEntryRepr, Option<LoadBalancingRepr>)],
+    service_id: u16,
+    random: u32,
+) -> Option<ServiceEntryRepr> {
+    let offers = || {
+        candidates
+            .iter()
+            .filter(move |(entry, _)| entry.service_id == service_id && entry.is_offer_service())
+            .map(|(entry, load_balancing)| {
+                (entry, load_balancing.unwrap_or(LoadBalancingRepr::DEFAULT))
+            })
+    };
+
+    let priority = offers()
+        .map(|(_, load_balancing)| load_balancing.priority)
+        .min()?;
+    let best =
+        move || offers().filter(move |(_, load_balancing)| load_balancing.priority == priority);
+
+    let total_weight: u32 = best()
+        .map(|(_, load_balancing)| load_balancing.weight as u32)
+        .sum();
+    if total_weight == 0 {
+        return best().map(|(entry, _)| *entry).next();
+    }
+
+    let mut target = random % total_weight;
+    for (entry, load_balancing) in best() {
+        let weight = load_balancing.weight as u32;
+        if target < weight {
+            return Some(*entry);
+        }
+        target -= weight;
+    }
+    None
+}
+
+/// A high-level representation of an IPv4 endpoint, multicast or SD endpoint option.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Ipv4EndpointRepr {
+    /// IPv4 address
+    pub address: Ipv4Addr,
+    /// Transport layer protocol
+    pub protocol: L4Protocol,
+    /// Port number
+    pub port: u16,
+}
+
+impl Ipv4EndpointRepr {
+    /// Returns the socket address of the endpoint
+    pub const fn socket_addr(&self) -> SocketAddrV4 {
+        SocketAddrV4::new(self.address, self.port)
+    }
+
+    fn parse(data: &[u8], length: u16) -> core::result::Result<Self, Error> {
+        if length != field::sd_ipv4_option::LENGTH {
+            return Err(Error::InvalidOptionLength(length));
+        }
+        let protocol_byte = data[field::sd_ipv4_option::L4_PROTOCOL.start];
+        let address: [u8; 4] = data[field::sd_ipv4_option::ADDRESS].try_into().unwrap();
+        Ok(Ipv4EndpointRepr {
+            address: Ipv4Addr::from(address),
+            protocol: L4Protocol::from_u8(protocol_byte)
+                .ok_or(Error::InvalidL4Protocol(protocol_byte))?,
+            port: NetworkEndian::read_u16(&data[field::sd_ipv4_option::PORT]),
+        })
+    }
+
+    fn emit(&self, data: &mut [u8]) {
+        data[field::sd_ipv4_option::ADDRESS].copy_from_slice(&self.address.octets());
+        data[field::sd_ipv4_option::RESERVED.start] = 0;
+        data[field::sd_ipv4_option::L4_PROTOCOL.start] = self.protocol.as_u8();
+        NetworkEndian::write_u16(&mut data[field::sd_ipv4_option::PORT], self.port);
+    }
+}
+
+impl Display for Ipv4EndpointRepr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.protocol, self.socket_addr())
+    }
+}
+
+/// A high-level representation of an IPv6 endpoint, multicast or SD endpoint option.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Ipv6EndpointRepr {
+    /// IPv6 address
+    pub address: Ipv6Addr,
+    /// Transport layer protocol
+    pub protocol: L4Protocol,
+    /// Port number
+    pub port: u16,
+}
+
+impl Ipv6EndpointRepr {
+    /// Returns the socket address of the endpoint
+    pub const fn socket_addr(&self) -> SocketAddrV6 {
+        SocketAddrV6::new(self.address, self.port, 0, 0)
+    }
+
+    fn parse(data: &[u8], length: u16) -> core::result::Result<Self, Error> {
+        if length != field::sd_ipv6_option::LENGTH {
+            return Err(Error::InvalidOptionLength(length));
+        }
+        let protocol_byte = data[field::sd_ipv6_option::L4_PROTOCOL.start];
+        let address: [u8; 16] = data[field::sd_ipv6_option::ADDRESS].try_into().unwrap();
+        Ok(Ipv6EndpointRepr {
+            address: Ipv6Addr::from(address),
+            protocol: L4Protocol::from_u8(protocol_byte)
+                .ok_or(Error::InvalidL4Protocol(protocol_byte))?,
+            port: NetworkEndian::read_u16(&data[field::sd_ipv6_option::PORT]),
+        })
+    }
+
+    fn emit(&self, data: &mut [u8]) {
+        data[field::sd_ipv6_option::ADDRESS].copy_from_slice(&self.address.octets());
+        data[field::sd_ipv6_option::RESERVED.start] = 0;
+        data[field::sd_ipv6_option::L4_PROTOCOL.start] = self.protocol.as_u8();
+        NetworkEndian::write_u16(&mut data[field::sd_ipv6_option::PORT], self.port);
+    }
+}
+
+impl Display for Ipv6EndpointRepr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.protocol, self.socket_addr())
+    }
+}
+
+/// A single `key=value` or `key` string of a configuration option.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ConfigurationItem<'a> {
+    /// Key (non-empty printable US-ASCII without `=`)
+    pub key: &'a str,
+    /// Value, or None if the key is present without `=`
+    pub value: Option<&'a str>,
+}
+
+impl<'a> ConfigurationItem<'a> {
+    /// Create a `key=value` item.
+    pub const fn new(key: &'a str, value: &'a str) -> Self {
+        ConfigurationItem {
+            key,
+            value: Some(value),
+        }
+    }
+
+    /// Create a `key` item without a value.
+    pub const fn flag(key: &'a str) -> Self {
+        ConfigurationItem { key, value: None }
+    }
+
+    /// Returns the length of the item string without its length prefix
+    pub fn string_len(&self) -> usize {
+        self.key.len() + self.value.map_or(0, |value| 1 + value.len())
+    }
+
+    fn parse(string: &'a [u8]) -> core::result::Result<Self, Error> {
+        let (key, value) = match string.iter().position(|&byte| byte == b'=') {
+            Some(position) => (&string[..position], Some(&string[position + 1..])),
+            None => (string, None),
+        };
+        if !is_valid_key(key) {
+            return Err(Error::InvalidConfigurationString);
+        }
+        Ok(ConfigurationItem {
+            key: core::str::from_utf8(key).map_err(|_| Error::InvalidConfigurationString)?,
+            value: value
+                .map(core::str::from_utf8)
+                .transpose()
+                .map_err(|_| Error::InvalidConfigurationString)?,
+        })
+    }
+}
+
+impl<'a> Display for ConfigurationItem<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value {
+            Some(value) => write!(f, "{}={}", self.key, value),
+            None => write!(f, "{}", self.key),
+        }
+    }
+}
+
+fn is_valid_key(key: &[u8]) -> bool {
+    !key.is_empty()
+        && key
+            .iter()
+            .all(|&byte| (0x20..=0x7E).contains(&byte) && byte != b'=')
+}
+
+/// A high-level representation of a configuration option.
+///
+/// The option data is a sequence of strings, each prefixed with its length in a
+/// single byte, terminated by a zero length byte. The strings are borrowed from the
+/// packet without copying.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ConfigurationRepr<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ConfigurationRepr<'a> {
+    /// Creates a configuration representation over raw option data, validating every string.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The option data following the reserved field
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ConfigurationRepr` on success, `Error::OptionTooLarge` if the data does
+    /// not fit the option length field, or an `Error` if a string is malformed or runs
+    /// past the end of the data.
+    pub fn new_checked(data: &'a [u8]) -> core::result::Result<Self, Error> {
+        if data.len() > OptionRepr::MAX_DATA_LENGTH {
+            return Err(Error::OptionTooLarge(data.len() as u32));
+        }
+        let mut remaining = data;
+        while let Some((&length, rest)) = remaining.split_first() {
+            if length == 0 {
+                break;
+            }
+            let length = length as usize;
+            if rest.len() < length {
+                return Err(Error::Truncated);
+            }
+            ConfigurationItem::parse(&rest[..length])?;
+            remaining = &rest[length..];
+        }
+        Ok(ConfigurationRepr { data })
+    }
+
+    /// Returns the raw option data
+    pub const fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns an iterator over the configuration items.
+    pub fn items(&self) -> ConfigurationItems<'a> {
+        ConfigurationItems {
+            remaining: self.data,
+        }
+    }
+
+    /// Looks up an item by key.
+    ///
+    /// # Returns
+    ///
+    /// Returns the first item with the given key, or None if the key is not present.
+    pub fn get(&self, key: &str) -> Option<ConfigurationItem<'a>> {
+        self.items().find(|item| item.key == key)
+    }
+
+    /// Writes the given items, followed by the terminating zero byte, into `buffer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The items to write
+    /// * `buffer` - The buffer to write the option data into
+    ///
+    /// # Returns
+    ///
+    /// Returns a `ConfigurationRepr` borrowing the written part of `buffer`, or an
+    /// `Error` if an item is malformed, the buffer is too small or the items do not fit
+    /// the option length field.
+    pub fn emit_items<'b>(
+        items: &[ConfigurationItem<'_>],
+        buffer: &'b mut [u8],
+    ) -> core::result::Result<ConfigurationRepr<'b>, Error> {
+        let mut offset = 0;
+        for item in items {
+            let length = item.string_len();
+            if !is_valid_key(item.key.as_bytes()) || length > u8::MAX as usize {
+                return Err(Error::InvalidConfigurationString);
+            }
+            if buffer.len() < offset + 1 + length {
+                return Err(Error::BufferTooShort);
+            }
+
+            buffer[offset] = length as u8;
+            offset += 1;
+            buffer[offset..offset + item.key.len()].copy_from_slice(item.key.as_bytes());
+            offset += item.key.len();
+            if let Some(value) = item.value {
+                buffer[offset] = b'=';
+                offset += 1;
+                buffer[offset..offset + value.len()].copy_from_slice(value.as_bytes());
+                offset += value.len();
+            }
+        }
+
+        if buffer.len() < offset + 1 {
+            return Err(Error::BufferTooShort);
+        }
+        buffer[offset] = 0;
+        offset += 1;
+        if offset > OptionRepr::MAX_DATA_LENGTH {
+            return Err(Error::OptionTooLarge(offset as u32));
+        }
+        Ok(ConfigurationRepr {
+            data: &buffer[..offset],
+        })
+    }
+}
+
+/// Iterator over the items of a configuration option.
+#[derive(Debug, Clone)]
+pub struct ConfigurationItems<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> Iterator for ConfigurationItems<'a> {
+    type Item = ConfigurationItem<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&length, rest) = self.remaining.split_first()?;
+        let length = length as usize;
+        if length == 0 || rest.len() < length {
+            self.remaining = &[];
+            return None;
+        }
+        let (string, rest) = rest.split_at(length);
+        self.remaining = rest;
+        ConfigurationItem::parse(string).ok()
+    }
+}
+
+/// A high-level representation of a SOME/IP-SD option.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OptionRepr<'a> {
+    /// DNS-SD style configuration strings (0x01)
+    Configuration(ConfigurationRepr<'a>),
+    /// Priority and weight of a service instance (0x02)
+    LoadBalancing(LoadBalancingRepr),
+    /// Unicast endpoint of an IPv4 service instance (0x04)
+    Ipv4Endpoint(Ipv4EndpointRepr),
+    /// Unicast endpoint of an IPv6 service instance (0x06)
+    Ipv6Endpoint(Ipv6EndpointRepr),
+    /// IPv4 multicast address events of an eventgroup are sent to (0x14)
+    Ipv4Multicast(Ipv4EndpointRepr),
+    /// IPv6 multicast address events of an eventgroup are sent to (0x16)
+    Ipv6Multicast(Ipv6EndpointRepr),
+    /// IPv4 endpoint of the SD instance sending the message (0x24)
+    Ipv4SdEndpoint(Ipv4EndpointRepr),
+    /// IPv6 endpoint of the SD instance sending the message (0x26)
+    Ipv6SdEndpoint(Ipv6EndpointRepr),
+    /// Option of a type not known to this crate, kept as raw data
+    Unknown {
+        /// Raw option type byte
+        option_type: u8,
+        /// Whether receivers that do not support the option may discard it
+        discardable: bool,
+        /// Option data following the reserved field
+        data: &'a [u8],
+    },
+}
+
+impl<'a> OptionRepr<'a> {
+    /// Maximum length of the option data, as the length field also covers the reserved
+    /// byte
+    pub const MAX_DATA_LENGTH: usize = u16::MAX as usize - 1;
+
+    /// Returns the raw option type byte
+    pub fn option_type(&self) -> u8 {
+        match self {
+            OptionRepr::Configuration(_) => OptionType::Configuration.as_u8(),
+            OptionRepr::LoadBalancing(_) => OptionType::LoadBalancing.as_u8(),
+            OptionRepr::Ipv4Endpoint(_) => OptionType::Ipv4Endpoint.as_u8(),
+            OptionRepr::Ipv6Endpoint(_) => OptionType::Ipv6Endpoint.as_u8(),
+            OptionRepr::Ipv4Multicast(_) => OptionType::Ipv4Multicast.as_u8(),
+            OptionRepr::Ipv6Multicast(_) => OptionType::Ipv6Multicast.as_u8(),
+            OptionRepr::Ipv4SdEndpoint(_) => OptionType::Ipv4SdEndpoint.as_u8(),
+            OptionRepr::Ipv6SdEndpoint(_) => OptionType::Ipv6SdEndpoint.as_u8(),
+            OptionRepr::Unknown { option_type, .. } => *option_type,
+        }
+    }
+
+    /// Returns the value of the option length field (reserved byte + option data)
+    ///
+    /// The value is only meaningful for options passing [`OptionRepr::check_length`].
+    pub fn length(&self) -> u16 {
+        match self {
+            OptionRepr::Configuration(config) => config.data.len() as u16 + 1,
+            OptionRepr::LoadBalancing(_) => field::sd_load_balancing_option::LENGTH,
+            OptionRepr::Ipv4Endpoint(_)
+            | OptionRepr::Ipv4Multicast(_)
+            | OptionRepr::Ipv4SdEndpoint(_) => field::sd_ipv4_option::LENGTH,
+            OptionRepr::Ipv6Endpoint(_)
+            | OptionRepr::Ipv6Multicast(_)
+            | OptionRepr::Ipv6SdEndpoint(_) => field::sd_ipv6_option::LENGTH,
+            OptionRepr::Unknown { data, .. } => data.len() as u16 + 1,
+        }
+    }
+
+    /// Checks that the option data fits the option length field.
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::OptionTooLarge` if the configuration or raw data is longer than
+    /// [`OptionRepr::MAX_DATA_LENGTH`].
+    pub fn check_length(&self) -> core::result::Result<(), Error> {
+        let length = match self {
+            OptionRepr::Configuration(config) => config.data.len(),
+            OptionRepr::Unknown { data, .. } => data.len(),
+            _ => 0,
+        };
+        if length > Self::MAX_DATA_LENGTH {
+            return Err(Error::OptionTooLarge(length as u32));
+        }
+        Ok(())
+    }
+
+    /// Returns the length of the buffer required to emit this option.
+    pub fn buffer_len(&self) -> usize {
+        field::sd_option::TYPE.end + self.length() as usize
+    }
+
+    /// Parse an SD option into the matching option representation.
+    ///
+    /// Options of unknown types are returned as [`OptionRepr::Unknown`], so that the
+    /// caller can decide whether to discard them.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - The option buffer to parse
+    ///
+    /// # Returns
+    ///
+    /// Returns an `OptionRepr` on success, or an `Error` if the option is malformed.
+    pub fn parse<T>(packet: &OptionPacket<&'a T>) -> core::result::Result<OptionRepr<'a>, Error>
+    where
+        T: AsRef<[u8]> + ?Sized,
+    {
+        packet.check_len()?;
+
+        let length = packet.length();
+        let data = packet.data();
+        let option_type_byte = packet.option_type();
+        Ok(match OptionType::from_u8(option_type_byte) {
+            Some(OptionType::Configuration) => {
+                OptionRepr::Configuration(ConfigurationRepr::new_checked(data)?)
+            }
+            Some(OptionType::LoadBalancing) => {
+                OptionRepr::LoadBalancing(LoadBalancingRepr::parse(data, length)?)
+            }
+            Some(OptionType::Ipv4Endpoint) => {
+                OptionRepr::Ipv4Endpoint(Ipv4EndpointRepr::parse(data, length)?)
+            }
+            Some(OptionType::Ipv6Endpoint) => {
+                OptionRepr::Ipv6Endpoint(Ipv6EndpointRepr::parse(data, length)?)
+            }
+            Some(OptionType::Ipv4Multicast) => {
+                OptionRepr::Ipv4Multicast(Ipv4EndpointRepr::parse(data, length)?)
+            }
+            Some(OptionType::Ipv6Multicast) => {
+                OptionRepr::Ipv6Multicast(Ipv6EndpointRepr::parse(data, length)?)
+            }
+            Some(OptionType::Ipv4SdEndpoint) => {
+                OptionRepr::Ipv4SdEndpoint(Ipv4EndpointRepr::parse(data, length)?)
+            }
+            Some(OptionType::Ipv6SdEndpoint) => {
+                OptionRepr::Ipv6SdEndpoint(Ipv6EndpointRepr::parse(data, length)?)
+            }
+            None => OptionRepr::Unknown {
+                option_type: option_type_byte,
+                discardable: packet.discardable(),
+                data,
+            },
+        })
+    }
+
+    /// Emits the option into the provided option buffer.
+    ///
+    /// The option must pass [`OptionRepr::check_length`], and the buffer must be at
+    /// least [`OptionRepr::buffer_len`] bytes long.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A mutable reference to the option where the representation will be written.
+    pub fn emit<T>(&self, packet: &mut OptionPacket<&mut T>)
+    where
+        T: AsRef<[u8]> + AsMut<[u8]> + ?Sized,
+    {
+        packet.set_length(self.length());
+        packet.set_option_type(self.option_type());
+        match self {
+            OptionRepr::Unknown { discardable, .. } => packet.set_discardable(*discardable),
+            _ => packet.set_discardable(false),
+        }
+
+        let data = packet.data_mut();
+        match self {
+            OptionRepr::Configuration(config) => data.copy_from_slice(config.data),
+            OptionRepr::LoadBalancing(load_balancing) => load_balancing.emit(data),
+            OptionRepr::Ipv4Endpoint(endpoint)
+            | OptionRepr::Ipv4Multicast(endpoint)
+            | OptionRepr::Ipv4SdEndpoint(endpoint) => endpoint.emit(data),
+            OptionRepr::Ipv6Endpoint(endpoint)
+            | OptionRepr::Ipv6Multicast(endpoint)
+            | OptionRepr::Ipv6SdEndpoint(endpoint) => endpoint.emit(data),
+            OptionRepr::Unknown { data: raw, .. } => data.copy_from_slice(raw),
+        }
+    }
+}
+
+impl<'a> Display for OptionRepr<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionRepr::Configuration(config) => {
+                write!(f, "Configuration: {} items", config.items().count())
+            }
+            OptionRepr::LoadBalancing(load_balancing) => {
+                write!(f, "Load Balancing: {}", load_balancing)
+            }
+            OptionRepr::Ipv4Endpoint(endpoint) => write!(f, "IPv4 Endpoint: {}", endpoint),
+            OptionRepr::Ipv6Endpoint(endpoint) => write!(f, "IPv6 Endpoint: {}", endpoint),
+            OptionRepr::Ipv4Multicast(endpoint) => write!(f, "IPv4 Multicast: {}", endpoint),
+            OptionRepr::Ipv6Multicast(endpoint) => write!(f, "IPv6 Multicast: {}", endpoint),
+            OptionRepr::Ipv4SdEndpoint(endpoint) => write!(f, "IPv4 SD Endpoint: {}", endpoint),
+            OptionRepr::Ipv6SdEndpoint(endpoint) => write!(f, "IPv6 SD Endpoint: {}", endpoint),
+            OptionRepr::Unknown {
+                option_type, data, ..
+            } => write!(
+                f,
+                "Unknown option 0x{:02X}: data_len={}",
+                option_type,
+                data.len()
+            ),
+        }
+    }
+}
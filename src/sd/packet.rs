@@ -0,0 +1,267 @@
+//! SD packet module
+//!
+//! This module contains the `SdPacket` type, which is a read/write wrapper around a SOME/IP-SD payload buffer.
+
+use crate::error::Error;
+use crate::field;
+use crate::packet::Result;
+use byteorder::{ByteOrder, NetworkEndian};
+use core::fmt;
+
+/// A read/write wrapper around a SOME/IP-SD payload buffer.
+///
+/// The buffer is the payload of a SOME/IP message, i.e. `Repr::data` of a message
+/// whose Message ID is [`MESSAGE_ID`](crate::sd::MESSAGE_ID).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SdPacket<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+impl<T: AsRef<[u8]>> SdPacket<T> {
+    /// Creates a new unchecked `SdPacket`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - A buffer containing the SD payload.
+    ///
+    /// # Returns
+    ///
+    /// * `SdPacket` - A new `SdPacket` instance.
+    pub const fn new_unchecked(buffer: T) -> SdPacket<T> {
+        SdPacket { buffer }
+    }
+
+    /// Creates a new checked `SdPacket`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - A buffer containing the SD payload.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<SdPacket>` - A new `SdPacket` instance if the buffer is valid.
+    pub fn new_checked(buffer: T) -> Result<SdPacket<T>> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    /// Checks the length of the SD payload.
+    ///
+    /// Verifies that the buffer holds the SD header and that both the entries
+    /// and the options arrays fit inside it.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Ok if the length is valid, otherwise an error.
+    pub fn check_len(&self) -> Result<()> {
+        let len = self.buffer.as_ref().len();
+        if len < field::sd::HEADER_LENGTH {
+            return Err(Error::BufferTooShort);
+        }
+
+        let entries_length = self.entries_length();
+        if entries_length > len || len < field::sd::options_length(entries_length).end {
+            return Err(Error::Truncated);
+        }
+        if !entries_length.is_multiple_of(field::sd_entry::ENTRY_LENGTH) {
+            return Err(Error::InvalidEntriesLength(entries_length as u32));
+        }
+
+        let options_length = self.options_length();
+        if options_length > len || len < field::sd::options(entries_length, options_length).end {
+            return Err(Error::Truncated);
+        }
+        Ok(())
+    }
+
+    /// Returns the inner buffer.
+    ///
+    /// # Returns
+    ///
+    /// * `T` - The inner buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// Returns a reference to the inner buffer.
+    ///
+    /// # Returns
+    ///
+    /// * `&[u8]` - A reference to the buffer.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+
+    /// Returns the Flags (raw u8)
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The raw flags byte of the SD header
+    pub fn flags(&self) -> u8 {
+        self.buffer.as_ref()[field::sd::FLAGS.start]
+    }
+
+    /// Returns the Reboot flag
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the sender has rebooted since its session ID last wrapped around
+    pub fn reboot(&self) -> bool {
+        self.flags() & field::sd::REBOOT_FLAG != 0
+    }
+
+    /// Returns the Unicast flag
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the sender supports receiving unicast SD messages
+    pub fn unicast(&self) -> bool {
+        self.flags() & field::sd::UNICAST_FLAG != 0
+    }
+
+    /// Returns the Explicit Initial Data Control flag
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if the sender supports explicit initial data control
+    pub fn explicit_initial_data_control(&self) -> bool {
+        self.flags() & field::sd::EXPLICIT_INITIAL_DATA_CONTROL_FLAG != 0
+    }
+
+    /// Returns the Length of Entries Array
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The length of the entries array in bytes
+    pub fn entries_length(&self) -> usize {
+        NetworkEndian::read_u32(&self.buffer.as_ref()[field::sd::ENTRIES_LENGTH]) as usize
+    }
+
+    /// Returns the Length of Options Array
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The length of the options array in bytes
+    pub fn options_length(&self) -> usize {
+        let range = field::sd::options_length(self.entries_length());
+        NetworkEndian::read_u32(&self.buffer.as_ref()[range]) as usize
+    }
+
+    /// Returns the number of entries in the entries array
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of 16-byte entries
+    pub fn entry_count(&self) -> usize {
+        self.entries_length() / field::sd_entry::ENTRY_LENGTH
+    }
+
+    /// Returns the total length of the SD payload
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The length of the header, entries array and options array combined
+    pub fn total_length(&self) -> usize {
+        field::sd::options(self.entries_length(), self.options_length()).end
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> SdPacket<T> {
+    /// Sets the Flags (raw u8)
+    ///
+    /// # Arguments
+    ///
+    /// * `flags` - The new flags byte to set
+    pub fn set_flags(&mut self, flags: u8) {
+        self.buffer.as_mut()[field::sd::FLAGS.start] = flags;
+    }
+
+    /// Clears the Reserved field
+    pub fn clear_reserved(&mut self) {
+        self.buffer.as_mut()[field::sd::RESERVED].fill(0);
+    }
+
+    /// Sets the Length of Entries Array
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The new length of the entries array in bytes
+    pub fn set_entries_length(&mut self, length: u32) {
+        NetworkEndian::write_u32(&mut self.buffer.as_mut()[field::sd::ENTRIES_LENGTH], length);
+    }
+
+    /// Sets the Length of Options Array
+    ///
+    /// The entries length must be set beforehand, as it determines the position of this field.
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The new length of the options array in bytes
+    pub fn set_options_length(&mut self, length: u32) {
+        let range = field::sd::options_length(self.entries_length());
+        NetworkEndian::write_u32(&mut self.buffer.as_mut()[range], length);
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> SdPacket<&'a T> {
+    /// Returns a reference to the entries array.
+    ///
+    /// # Returns
+    ///
+    /// * `&'a [u8]` - A reference to the entries array.
+    #[inline]
+    pub fn entries(&self) -> &'a [u8] {
+        let range = field::sd::entries(self.entries_length());
+        &self.buffer.as_ref()[range]
+    }
+
+    /// Returns a reference to the options array.
+    ///
+    /// # Returns
+    ///
+    /// * `&'a [u8]` - A reference to the options array.
+    #[inline]
+    pub fn options(&self) -> &'a [u8] {
+        let range = field::sd::options(self.entries_length(), self.options_length());
+        &self.buffer.as_ref()[range]
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]> + ?Sized> SdPacket<&mut T> {
+    /// Returns a mutable reference to the entries array.
+    ///
+    /// # Returns
+    ///
+    /// * `&mut [u8]` - A mutable reference to the entries array.
+    #[inline]
+    pub fn entries_mut(&mut self) -> &mut [u8] {
+        let range = field::sd::entries(self.entries_length());
+        &mut self.buffer.as_mut()[range]
+    }
+
+    /// Returns a mutable reference to the options array.
+    ///
+    /// # Returns
+    ///
+    /// * `&mut [u8]` - A mutable reference to the options array.
+    #[inline]
+    pub fn options_mut(&mut self) -> &mut [u8] {
+        let range = field::sd::options(self.entries_length(), self.options_length());
+        &mut self.buffer.as_mut()[range]
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> fmt::Display for SdPacket<&T> {
+    /// Formats the SD packet as a string
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SOME/IP-SD Packet: flags=0x{:02X}, entries_length={}, options_length={}",
+            self.flags(),
+            self.entries_length(),
+            self.options_length()
+        )
+    }
+}
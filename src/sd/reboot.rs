@@ -0,0 +1,209 @@
+//! SD reboot detection module
+//!
+//! This module contains the `RebootDetector`, which tracks the reboot flag and session ID
+//! of the SD messages received from each peer and detects when a peer has restarted.
+
+use crate::error::Error;
+use crate::sd::repr::SdRepr;
+use crate::types::RequestId;
+use core::fmt::{self, Display};
+use core::net::SocketAddr;
+
+/// Channel an SD message has been received on.
+///
+/// Session IDs are counted separately for unicast and multicast messages, so reboot
+/// detection tracks both channels of a peer independently.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Channel {
+    /// Message sent to the unicast address of the receiver
+    Unicast,
+    /// Message sent to the SD multicast address
+    Multicast,
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Channel::Unicast => write!(f, "unicast"),
+            Channel::Multicast => write!(f, "multicast"),
+        }
+    }
+}
+
+/// Reboot flag and session ID of the last message received on a channel
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SessionState {
+    /// Reboot flag of the last message
+    pub reboot: bool,
+    /// Session ID of the last message
+    pub session_id: u16,
+}
+
+impl SessionState {
+    /// Checks if a message with the given state indicates a reboot of the sender.
+    ///
+    /// A reboot is detected if the reboot flag changes from 0 to 1, or if it stays 1
+    /// while the session ID does not increase.
+    pub const fn is_rebooted_by(&self, next: &SessionState) -> bool {
+        next.reboot && (!self.reboot || self.session_id >= next.session_id)
+    }
+}
+
+/// Tracked state of a single peer
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PeerState {
+    /// Address of the peer
+    pub peer: SocketAddr,
+    /// State of the last unicast message, if any
+    pub unicast: Option<SessionState>,
+    /// State of the last multicast message, if any
+    pub multicast: Option<SessionState>,
+}
+
+impl PeerState {
+    fn channel_mut(&mut self, channel: Channel) -> &mut Option<SessionState> {
+        match channel {
+            Channel::Unicast => &mut self.unicast,
+            Channel::Multicast => &mut self.multicast,
+        }
+    }
+}
+
+/// A peer has rebooted.
+///
+/// Subscriptions and offers learned from the peer before the reboot are stale and
+/// should be dropped by the caller.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RebootEvent {
+    /// Address of the peer
+    pub peer: SocketAddr,
+    /// Channel on which the reboot was detected
+    pub channel: Channel,
+}
+
+impl Display for RebootEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Peer {} rebooted ({})", self.peer, self.channel)
+    }
+}
+
+/// Detects reboots of SD peers.
+///
+/// Peers are stored in a caller-provided slice, one slot per remote endpoint.
+///
+/// # Example
+///
+/// ```rust
+/// use core::net::SocketAddr;
+/// use someip_wire::sd::reboot::{Channel, RebootDetector};
+/// use someip_wire::types::{ClientId, RequestId};
+///
+/// let peer: SocketAddr = "192.168.0.1:30490".parse().unwrap();
+/// let mut storage = [None; 4];
+/// let mut detector = RebootDetector::new(&mut storage);
+///
+/// let request_id = |session_id| RequestId {
+///     client_id: ClientId::from_u16(0),
+///     session_id,
+/// };
+/// let rebooted = detector.process(peer, Channel::Multicast, request_id(5), true);
+/// assert_eq!(rebooted, Ok(None));
+/// let rebooted = detector.process(peer, Channel::Multicast, request_id(1), true);
+/// assert!(rebooted.unwrap().is_some());
+/// ```
+#[derive(Debug)]
+pub struct RebootDetector<'a> {
+    peers: &'a mut [Option<PeerState>],
+}
+
+impl<'a> RebootDetector<'a> {
+    /// Create a new detector without any known peers.
+    ///
+    /// # Arguments
+    ///
+    /// * `peers` - Storage for the peer states
+    pub fn new(peers: &'a mut [Option<PeerState>]) -> Self {
+        RebootDetector { peers }
+    }
+
+    /// Returns an iterator over the known peers
+    pub fn peers(&self) -> impl Iterator<Item = &PeerState> + '_ {
+        self.peers.iter().flatten()
+    }
+
+    /// Returns the state of a peer, if it is known.
+    pub fn get(&self, peer: SocketAddr) -> Option<&PeerState> {
+        self.peers().find(|state| state.peer == peer)
+    }
+
+    /// Processes the header fields of a received SD message.
+    ///
+    /// # Arguments
+    ///
+    /// * `peer` - The source address of the message
+    /// * `channel` - Whether the message was received by unicast or multicast
+    /// * `request_id` - The Request ID of the SOME/IP header
+    /// * `reboot` - The reboot flag of the SD header
+    ///
+    /// # Returns
+    ///
+    /// Returns a `RebootEvent` if the peer has rebooted, or `Error::StorageFull` if the
+    /// peer is unknown and no slot is free.
+    pub fn process(
+        &mut self,
+        peer: SocketAddr,
+        channel: Channel,
+        request_id: RequestId,
+        reboot: bool,
+    ) -> Result<Option<RebootEvent>, Error> {
+        let next = SessionState {
+            reboot,
+            session_id: request_id.session_id,
+        };
+
+        let index = match self
+            .peers
+            .iter()
+            .position(|slot| slot.is_some_and(|state| state.peer == peer))
+        {
+            Some(index) => index,
+            None => self
+                .peers
+                .iter()
+                .position(Option::is_none)
+                .ok_or(Error::StorageFull)?,
+        };
+        let state = self.peers[index].get_or_insert(PeerState {
+            peer,
+            unicast: None,
+            multicast: None,
+        });
+        let last = state.channel_mut(channel).replace(next);
+        Ok(last
+            .filter(|last| last.is_rebooted_by(&next))
+            .map(|_| RebootEvent { peer, channel }))
+    }
+
+    /// Processes a received SD message.
+    ///
+    /// Convenience wrapper around [`RebootDetector::process`] taking the reboot flag
+    /// from a parsed SD payload.
+    pub fn process_repr(
+        &mut self,
+        peer: SocketAddr,
+        channel: Channel,
+        request_id: RequestId,
+        repr: &SdRepr<'_>,
+    ) -> Result<Option<RebootEvent>, Error> {
+        self.process(peer, channel, request_id, repr.reboot)
+    }
+
+    /// Forgets a peer, e.g. after all state learned from it has been dropped.
+    pub fn remove(&mut self, peer: SocketAddr) {
+        for slot in self.peers.iter_mut() {
+            if slot.is_some_and(|state| state.peer == peer) {
+                *slot = None;
+            }
+        }
+    }
+}
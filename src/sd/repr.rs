@@ -0,0 +1,359 @@
+use crate::sd::entry::{EntryPacket, EntryRepr, OptionRun};
+use crate::sd::option::{OptionPacket, OptionRepr};
+use crate::sd::packet::SdPacket;
+use crate::{error::*, field};
+use core::fmt;
+
+/// A high-level representation of a SOME/IP-SD message.
+///
+/// The entries and options arrays are kept as raw byte slices borrowed from the
+/// packet. Use [`SdRepr::entries`] and [`SdRepr::options`] to iterate over them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct SdRepr<'a> {
+    /// Reboot flag: set until the sender's session ID wraps around for the first time
+    pub reboot: bool,
+    /// Unicast flag: the sender supports receiving unicast SD messages
+    pub unicast: bool,
+    /// Explicit Initial Data Control flag
+    pub explicit_initial_data_control: bool,
+    /// Entries array (a multiple of 16 bytes)
+    pub entries: &'a [u8],
+    /// Options array (variable length)
+    pub options: &'a [u8],
+}
+
+impl<'a> SdRepr<'a> {
+    /// Parse a SOME/IP-SD payload into a high-level representation.
+    ///
+    /// Besides the SD header, the layout of the options array is validated so that
+    /// iterating over it later cannot fail.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - The SD packet buffer to parse
+    ///
+    /// # Returns
+    ///
+    /// Returns an `SdRepr` on success, or an `Error` if the payload is malformed.
+    pub fn parse<T>(packet: &'a SdPacket<T>) -> core::result::Result<SdRepr<'a>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        packet.check_len()?;
+
+        let buffer = packet.as_slice();
+        let entries_length = packet.entries_length();
+        let options_length = packet.options_length();
+        let flags = packet.flags();
+
+        let options = &buffer[field::sd::options(entries_length, options_length)];
+        let mut remaining = options;
+        while !remaining.is_empty() {
+            let option = OptionPacket::new_checked(remaining)?;
+            remaining = &remaining[option.total_length()..];
+        }
+
+        Ok(SdRepr {
+            reboot: flags & field::sd::REBOOT_FLAG != 0,
+            unicast: flags & field::sd::UNICAST_FLAG != 0,
+            explicit_initial_data_control: flags & field::sd::EXPLICIT_INITIAL_DATA_CONTROL_FLAG
+                != 0,
+            entries: &buffer[field::sd::entries(entries_length)],
+            options,
+        })
+    }
+
+    /// Serializes typed entries and options into `buffer` and returns a representation
+    /// borrowing the written arrays.
+    ///
+    /// The option runs of the entries are written as given, so they must refer to
+    /// positions in `options`. The unicast flag is set, as required for all senders;
+    /// the other flags are cleared.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The entries to write into the entries array
+    /// * `options` - The options to write into the options array
+    /// * `buffer` - Scratch buffer holding the arrays
+    ///
+    /// # Returns
+    ///
+    /// Returns an `SdRepr` on success, `Error::OptionTooLarge` if an option does not fit
+    /// its length field, or `Error::BufferTooShort` if the arrays do not fit into
+    /// `buffer`.
+    pub fn from_entries(
+        entries: &[EntryRepr],
+        options: &[OptionRepr<'_>],
+        buffer: &'a mut [u8],
+    ) -> core::result::Result<SdRepr<'a>, Error> {
+        for option in options {
+            option.check_length()?;
+        }
+        let entries_length = entries.len() * field::sd_entry::ENTRY_LENGTH;
+        let options_length: usize = options.iter().map(OptionRepr::buffer_len).sum();
+        if buffer.len() < entries_length + options_length {
+            return Err(Error::BufferTooShort);
+        }
+
+        let (entries_buffer, options_buffer) = buffer.split_at_mut(entries_length);
+        for (entry, chunk) in entries
+            .iter()
+            .zip(entries_buffer.chunks_exact_mut(field::sd_entry::ENTRY_LENGTH))
+        {
+            entry.emit(&mut EntryPacket::new_unchecked(chunk));
+        }
+        let mut offset = 0;
+        for option in options {
+            let length = option.buffer_len();
+            option.emit(&mut OptionPacket::new_unchecked(
+                &mut options_buffer[offset..offset + length],
+            ));
+            offset += length;
+        }
+
+        let buffer = &*buffer;
+        Ok(SdRepr {
+            reboot: false,
+            unicast: true,
+            explicit_initial_data_control: false,
+            entries: &buffer[..entries_length],
+            options: &buffer[entries_length..entries_length + options_length],
+        })
+    }
+
+    /// Returns the length of the buffer required to emit this representation.
+    pub fn buffer_len(&self) -> usize {
+        field::sd::options(self.entries.len(), self.options.len()).end
+    }
+
+    /// Returns the flags byte of this representation.
+    pub fn flags(&self) -> u8 {
+        let mut flags = 0;
+        if self.reboot {
+            flags |= field::sd::REBOOT_FLAG;
+        }
+        if self.unicast {
+            flags |= field::sd::UNICAST_FLAG;
+        }
+        if self.explicit_initial_data_control {
+            flags |= field::sd::EXPLICIT_INITIAL_DATA_CONTROL_FLAG;
+        }
+        flags
+    }
+
+    /// Returns an iterator over the entries of the entries array.
+    pub fn entries(&self) -> Entries<'a> {
+        Entries {
+            chunks: self.entries.chunks_exact(field::sd_entry::ENTRY_LENGTH),
+        }
+    }
+
+    /// Returns an iterator over the options of the options array.
+    pub fn options(&self) -> Options<'a> {
+        Options {
+            remaining: self.options,
+        }
+    }
+
+    /// Returns the number of options in the options array.
+    pub fn option_count(&self) -> usize {
+        self.options().count()
+    }
+
+    /// Resolves an option run into the options it references.
+    ///
+    /// # Arguments
+    ///
+    /// * `run` - The option run of an entry
+    ///
+    /// # Returns
+    ///
+    /// Returns an iterator over the referenced options, or
+    /// `Error::OptionIndexOutOfBounds` if the run extends past the options array.
+    pub fn option_run(&self, run: OptionRun) -> core::result::Result<OptionRunIter<'a>, Error> {
+        let mut options = self.options();
+        if run.count > 0 {
+            let available = options.clone().skip(run.index as usize).count();
+            if available < run.count as usize {
+                return Err(Error::OptionIndexOutOfBounds(run.index));
+            }
+            if run.index > 0 {
+                options.nth(run.index as usize - 1);
+            }
+        }
+        Ok(OptionRunIter {
+            options,
+            remaining: run.count,
+        })
+    }
+
+    /// Resolves both option runs of an entry into the options they reference.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - An entry of this message
+    ///
+    /// # Returns
+    ///
+    /// Returns the resolved options, or `Error::OptionIndexOutOfBounds` if either
+    /// run extends past the options array.
+    pub fn resolve(&self, entry: &EntryRepr) -> core::result::Result<ResolvedOptions<'a>, Error> {
+        Ok(ResolvedOptions {
+            first: self.option_run(entry.first_options())?,
+            second: self.option_run(entry.second_options())?,
+        })
+    }
+
+    /// Returns an iterator over the parsed entries together with their resolved options.
+    ///
+    /// Each item is an `Error` if the entry is malformed or references options
+    /// outside the options array.
+    pub fn resolved_entries(&self) -> ResolvedEntries<'a> {
+        ResolvedEntries {
+            repr: *self,
+            entries: self.entries(),
+        }
+    }
+
+    /// Emits the high-level representation of the SD message into the provided packet/buffer.
+    ///
+    /// The buffer must be at least [`SdRepr::buffer_len`] bytes long.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A mutable reference to the packet where the high-level representation will be written.
+    pub fn emit<T>(&self, packet: &mut SdPacket<&mut T>)
+    where
+        T: AsRef<[u8]> + AsMut<[u8]> + ?Sized,
+    {
+        packet.set_flags(self.flags());
+        packet.clear_reserved();
+        packet.set_entries_length(self.entries.len() as u32);
+        packet.entries_mut().copy_from_slice(self.entries);
+        packet.set_options_length(self.options.len() as u32);
+        packet.options_mut().copy_from_slice(self.options);
+    }
+}
+
+impl<'a> fmt::Display for SdRepr<'a> {
+    /// Formats the high-level representation as a string.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SOME/IP-SD Payload: reboot={}, unicast={}, explicit_initial_data_control={}, entries={}, options_len={}",
+            self.reboot,
+            self.unicast,
+            self.explicit_initial_data_control,
+            self.entries.len() / field::sd_entry::ENTRY_LENGTH,
+            self.options.len()
+        )
+    }
+}
+
+/// Iterator over the entries of an SD entries array.
+#[derive(Debug, Clone)]
+pub struct Entries<'a> {
+    chunks: core::slice::ChunksExact<'a, u8>,
+}
+
+impl<'a> Iterator for Entries<'a> {
+    type Item = EntryPacket<&'a [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.chunks.next().map(EntryPacket::new_unchecked)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Entries<'_> {}
+
+/// Iterator over the options of an SD options array.
+///
+/// Iteration stops at the first option that does not fit in the remaining bytes,
+/// which cannot happen for an options array validated by [`SdRepr::parse`].
+#[derive(Debug, Clone)]
+pub struct Options<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> Iterator for Options<'a> {
+    type Item = OptionPacket<&'a [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        match OptionPacket::new_checked(self.remaining) {
+            Ok(option) => {
+                let (option, rest) = self.remaining.split_at(option.total_length());
+                self.remaining = rest;
+                Some(OptionPacket::new_unchecked(option))
+            }
+            Err(_) => {
+                self.remaining = &[];
+                None
+            }
+        }
+    }
+}
+
+/// Iterator over the options of a single option run.
+#[derive(Debug, Clone)]
+pub struct OptionRunIter<'a> {
+    options: Options<'a>,
+    remaining: u8,
+}
+
+impl<'a> Iterator for OptionRunIter<'a> {
+    type Item = OptionPacket<&'a [u8]>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.options.next()
+    }
+}
+
+/// The options referenced by both option runs of an entry.
+#[derive(Debug, Clone)]
+pub struct ResolvedOptions<'a> {
+    /// Options of the first option run
+    pub first: OptionRunIter<'a>,
+    /// Options of the second option run
+    pub second: OptionRunIter<'a>,
+}
+
+impl<'a> ResolvedOptions<'a> {
+    /// Returns an iterator over the options of both runs, first run first.
+    pub fn iter(&self) -> core::iter::Chain<OptionRunIter<'a>, OptionRunIter<'a>> {
+        self.first.clone().chain(self.second.clone())
+    }
+}
+
+/// Iterator over the entries of an SD message together with their resolved options.
+#[derive(Debug, Clone)]
+pub struct ResolvedEntries<'a> {
+    repr: SdRepr<'a>,
+    entries: Entries<'a>,
+}
+
+impl<'a> Iterator for ResolvedEntries<'a> {
+    type Item = core::result::Result<(EntryRepr, ResolvedOptions<'a>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let packet = self.entries.next()?;
+        Some(EntryRepr::parse(&packet).and_then(|entry| {
+            let options = self.repr.resolve(&entry)?;
+            Ok((entry, options))
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
@@ -0,0 +1,175 @@
+//! SD server module
+//!
+//! This module contains the `ServiceAnnouncer`, a sans-IO state machine that offers a
+//! service instance through the Initial Wait, Repetition and Main phases.
+
+use crate::error::Error;
+use crate::sd::entry::{EntryRepr, OptionRun, ServiceEntryRepr};
+use crate::sd::option::OptionRepr;
+use crate::sd::repr::SdRepr;
+use crate::sd::timing::{Phase, Scheduler, Timing};
+use core::time::Duration;
+
+/// Offers a single service instance.
+///
+/// The announcer does not perform any I/O and does not read a clock. The caller passes
+/// the current time to [`ServiceAnnouncer::poll`], sends the returned `SdRepr` and calls
+/// `poll` again no later than [`ServiceAnnouncer::poll_at`].
+///
+/// Returned messages borrow a caller-provided scratch buffer. They have the unicast flag
+/// set and the reboot flag cleared; the caller owns the session ID and therefore sets the
+/// reboot flag before emitting the message.
+///
+/// # Example
+///
+/// ```rust
+/// use core::time::Duration;
+/// use someip_wire::sd::server::ServiceAnnouncer;
+/// use someip_wire::sd::timing::{Phase, Timing};
+/// use someip_wire::sd::ServiceEntryRepr;
+///
+/// let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 1, 0, 3);
+/// let mut announcer = ServiceAnnouncer::new(offer, &[], Timing::default()).unwrap();
+/// announcer.start(Duration::ZERO, 0);
+///
+/// let mut buffer = [0u8; 64];
+/// let now = announcer.poll_at().unwrap();
+/// let message = announcer.poll(now, &mut buffer).unwrap().unwrap();
+/// assert_eq!(message.entries().count(), 1);
+/// assert_eq!(announcer.phase(), Phase::Repetition);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServiceAnnouncer<'a> {
+    entry: ServiceEntryRepr,
+    options: &'a [OptionRepr<'a>],
+    scheduler: Scheduler,
+}
+
+impl<'a> ServiceAnnouncer<'a> {
+    /// Create a new announcer in the Down phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry` - The OfferService entry to announce; its option runs are replaced
+    /// * `options` - Options attached to every offer, typically endpoint options (at most 15)
+    /// * `timing` - Timing parameters of the phases
+    ///
+    /// # Returns
+    ///
+    /// Returns the announcer, or `Error::EntryTooLarge` if there are more options than
+    /// fit into a single option run.
+    pub fn new(
+        entry: ServiceEntryRepr,
+        options: &'a [OptionRepr<'a>],
+        timing: Timing,
+    ) -> Result<Self, Error> {
+        if options.len() > OptionRun::MAX_COUNT as usize {
+            return Err(Error::EntryTooLarge);
+        }
+        Ok(ServiceAnnouncer {
+            entry: ServiceEntryRepr {
+                first_options: OptionRun::new(0, options.len() as u8),
+                second_options: OptionRun::new(0, 0),
+                ..entry
+            },
+            options,
+            scheduler: Scheduler::new(timing),
+        })
+    }
+
+    /// Returns the current phase
+    pub fn phase(&self) -> Phase {
+        self.scheduler.phase()
+    }
+
+    /// Returns the timing parameters
+    pub fn timing(&self) -> &Timing {
+        self.scheduler.timing()
+    }
+
+    /// Returns the offered entry
+    pub fn entry(&self) -> &ServiceEntryRepr {
+        &self.entry
+    }
+
+    /// Returns the time at which [`ServiceAnnouncer::poll`] must be called next,
+    /// or None if no message is scheduled.
+    pub fn poll_at(&self) -> Option<Duration> {
+        self.scheduler.poll_at()
+    }
+
+    /// Marks the service as available and enters the Initial Wait phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    /// * `random` - A random value selecting the Initial Wait delay
+    pub fn start(&mut self, now: Duration, random: u32) {
+        self.scheduler.start(now, random);
+    }
+
+    /// Marks the service as unavailable and returns to the Down phase.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - Scratch buffer holding the returned message
+    ///
+    /// # Returns
+    ///
+    /// Returns the StopOfferService message to send if an offer was sent before,
+    /// or an `Error` if the message does not fit into `buffer`.
+    pub fn stop<'b>(&mut self, buffer: &'b mut [u8]) -> Result<Option<SdRepr<'b>>, Error> {
+        let offered = matches!(self.phase(), Phase::Repetition | Phase::Main);
+        self.scheduler.stop();
+        if !offered {
+            return Ok(None);
+        }
+        let entry = EntryRepr::Service(self.entry.to_stop_offer());
+        SdRepr::from_entries(&[entry], self.options, buffer).map(Some)
+    }
+
+    /// Advances the state machine to `now`.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    /// * `buffer` - Scratch buffer holding the returned message
+    ///
+    /// # Returns
+    ///
+    /// Returns the OfferService message to send if one is due, or an `Error` if the
+    /// message does not fit into `buffer`.
+    pub fn poll<'b>(
+        &mut self,
+        now: Duration,
+        buffer: &'b mut [u8],
+    ) -> Result<Option<SdRepr<'b>>, Error> {
+        if !self.scheduler.poll(now) {
+            return Ok(None);
+        }
+        self.offer(buffer).map(Some)
+    }
+
+    /// Builds an OfferService message for the service, e.g. to answer a FindService.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - Scratch buffer holding the returned message
+    ///
+    /// # Returns
+    ///
+    /// Returns the OfferService message, or an `Error` if it does not fit into `buffer`.
+    pub fn offer<'b>(&self, buffer: &'b mut [u8]) -> Result<SdRepr<'b>, Error> {
+        SdRepr::from_entries(&[EntryRepr::Service(self.entry)], self.options, buffer)
+    }
+
+    /// Checks if a received FindService entry searches for this service.
+    ///
+    /// FindService entries are only answered in the Repetition and Main phases;
+    /// during the Initial Wait phase the service is not offered yet.
+    pub fn answers_find(&self, find: &ServiceEntryRepr) -> bool {
+        matches!(self.phase(), Phase::Repetition | Phase::Main)
+            && find.is_find_service()
+            && find.matches(&self.entry)
+    }
+}
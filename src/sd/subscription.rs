@@ -0,0 +1,435 @@
+//! SD subscription module
+//!
+//! This module contains the `SubscriptionManager`, a sans-IO state machine that subscribes
+//! to eventgroups of offered services and keeps the subscriptions alive.
+
+use crate::error::Error;
+use crate::sd::entry::{EntryRepr, EventgroupEntryRepr, OptionRun, ServiceEntryRepr, TTL_INFINITE};
+use crate::sd::option::OptionRepr;
+use crate::sd::repr::SdRepr;
+use core::fmt::{self, Display};
+use core::time::Duration;
+
+/// State of an eventgroup subscription
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SubscriptionState {
+    /// The service is not offered, no subscription is active
+    Down,
+    /// SubscribeEventgroup is due or has been sent without an answer yet
+    Requested,
+    /// The subscription has been acknowledged
+    Subscribed,
+    /// The subscription has been rejected; it is retried on the next offer
+    Rejected,
+}
+
+impl Display for SubscriptionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubscriptionState::Down => write!(f, "Down"),
+            SubscriptionState::Requested => write!(f, "Requested"),
+            SubscriptionState::Subscribed => write!(f, "Subscribed"),
+            SubscriptionState::Rejected => write!(f, "Rejected"),
+        }
+    }
+}
+
+/// A subscription to a single eventgroup of a service instance.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Subscription {
+    entry: EventgroupEntryRepr,
+    state: SubscriptionState,
+    renew_at: Option<Duration>,
+    expires_at: Option<Duration>,
+}
+
+impl Subscription {
+    /// Returns the SubscribeEventgroup entry
+    pub const fn entry(&self) -> &EventgroupEntryRepr {
+        &self.entry
+    }
+
+    /// Returns the current state
+    pub const fn state(&self) -> SubscriptionState {
+        self.state
+    }
+
+    /// Returns the time at which the acknowledged subscription expires, or None if it
+    /// is not acknowledged or has an infinite TTL.
+    pub const fn expires_at(&self) -> Option<Duration> {
+        self.expires_at
+    }
+
+    fn is_active(&self) -> bool {
+        matches!(
+            self.state,
+            SubscriptionState::Requested | SubscriptionState::Subscribed
+        )
+    }
+
+    fn is_offered_by(&self, offer: &ServiceEntryRepr) -> bool {
+        self.entry.service_id == offer.service_id
+            && self.entry.instance_id == offer.instance_id
+            && self.entry.major_version == offer.major_version
+    }
+
+    fn is_identified_by(&self, service_id: u16, instance_id: u16, eventgroup_id: u16) -> bool {
+        self.entry.service_id == service_id
+            && self.entry.instance_id == instance_id
+            && self.entry.eventgroup_id == eventgroup_id
+    }
+
+    fn stop(&mut self) {
+        self.state = SubscriptionState::Down;
+        self.renew_at = None;
+        self.expires_at = None;
+    }
+}
+
+/// Change of state of an eventgroup subscription
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SubscriptionEvent {
+    /// The server acknowledged the subscription
+    Subscribed(EventgroupEntryRepr),
+    /// The server rejected the subscription
+    Rejected(EventgroupEntryRepr),
+    /// The subscription expired without being renewed
+    Expired(EventgroupEntryRepr),
+    /// The subscription was active when the service stopped being offered
+    Stopped(EventgroupEntryRepr),
+}
+
+impl Display for SubscriptionEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (entry, state) = match self {
+            SubscriptionEvent::Subscribed(entry) => (entry, "subscribed"),
+            SubscriptionEvent::Rejected(entry) => (entry, "rejected"),
+            SubscriptionEvent::Expired(entry) => (entry, "expired"),
+            SubscriptionEvent::Stopped(entry) => (entry, "stopped"),
+        };
+        write!(
+            f,
+            "Eventgroup 0x{:04X}.0x{:04X}.0x{:04X} {}",
+            entry.service_id, entry.instance_id, entry.eventgroup_id, state
+        )
+    }
+}
+
+/// Subscribes to eventgroups and renews the subscriptions.
+///
+/// Subscriptions are added with [`SubscriptionManager::add`] and stay in the Down state
+/// until the service is offered. Every received entry is passed to
+/// [`SubscriptionManager::process_entry`]: an OfferService requests the subscriptions
+/// of the offered instance, a StopOfferService tears them down and reports the active
+/// ones, and SubscribeEventgroupAck/Nack entries answer them.
+///
+/// Subscriptions are renewed `renewal_margin` before their TTL runs out, but not
+/// before half of the TTL has passed. A subscription whose renewal is not acknowledged
+/// in time expires and is reported by [`SubscriptionManager::expire`].
+///
+/// Subscriptions are stored in a caller-provided slice, one slot per eventgroup.
+///
+/// # Example
+///
+/// ```rust
+/// use core::time::Duration;
+/// use someip_wire::sd::{EventgroupEntryRepr, ServiceEntryRepr};
+/// use someip_wire::sd::subscription::{SubscriptionEvent, SubscriptionManager};
+///
+/// let mut storage = [None; 4];
+/// let mut manager = SubscriptionManager::new(&[], Duration::from_secs(1), &mut storage).unwrap();
+/// let subscribe = EventgroupEntryRepr::subscribe(0x1234, 0x0001, 1, 0x0010, 0, 3);
+/// manager.add(subscribe).unwrap();
+///
+/// let offer = ServiceEntryRepr::offer_service(0x1234, 0x0001, 1, 0, 3);
+/// manager.process_entry(Duration::ZERO, &offer.into());
+///
+/// let mut buffer = [0u8; 64];
+/// let message = manager.poll(Duration::ZERO, &mut buffer).unwrap().unwrap();
+/// assert_eq!(message.entries().count(), 1);
+///
+/// let ack = subscribe.to_ack();
+/// let event = manager.process_entry(Duration::ZERO, &ack.into());
+/// assert_eq!(event, Some(SubscriptionEvent::Subscribed(subscribe)));
+/// ```
+#[derive(Debug)]
+pub struct SubscriptionManager<'a> {
+    options: &'a [OptionRepr<'a>],
+    renewal_margin: Duration,
+    subscriptions: &'a mut [Option<Subscription>],
+}
+
+impl<'a> SubscriptionManager<'a> {
+    /// Create a new subscription manager.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Options attached to every subscription, typically the endpoint
+    ///   options of the client (at most 15)
+    /// * `renewal_margin` - How long before the TTL runs out a subscription is renewed
+    /// * `subscriptions` - Storage for the subscriptions
+    ///
+    /// # Returns
+    ///
+    /// Returns the manager, or `Error::EntryTooLarge` if there are more options than fit
+    /// into the option run of an entry.
+    pub fn new(
+        options: &'a [OptionRepr<'a>],
+        renewal_margin: Duration,
+        subscriptions: &'a mut [Option<Subscription>],
+    ) -> Result<Self, Error> {
+        if options.len() > OptionRun::MAX_COUNT as usize {
+            return Err(Error::EntryTooLarge);
+        }
+        Ok(SubscriptionManager {
+            options,
+            renewal_margin,
+            subscriptions,
+        })
+    }
+
+    /// Returns an iterator over the subscriptions
+    pub fn subscriptions(&self) -> impl Iterator<Item = &Subscription> + '_ {
+        self.subscriptions.iter().flatten()
+    }
+
+    /// Returns the subscription to an eventgroup, if it has been added.
+    pub fn get(
+        &self,
+        service_id: u16,
+        instance_id: u16,
+        eventgroup_id: u16,
+    ) -> Option<&Subscription> {
+        self.subscriptions().find(|subscription| {
+            subscription.is_identified_by(service_id, instance_id, eventgroup_id)
+        })
+    }
+
+    /// Returns the time at which [`SubscriptionManager::poll`] or
+    /// [`SubscriptionManager::expire`] must be called next, or None if nothing is scheduled.
+    pub fn poll_at(&self) -> Option<Duration> {
+        self.subscriptions()
+            .flat_map(|subscription| [subscription.renew_at, subscription.expires_at])
+            .flatten()
+            .min()
+    }
+
+    /// Adds a subscription in the Down state.
+    ///
+    /// # Arguments
+    ///
+    /// * `subscribe` - The SubscribeEventgroup entry to send; its option runs are replaced
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::StorageFull` if no slot is free. Adding an eventgroup twice
+    /// replaces the entry of the existing subscription.
+    pub fn add(&mut self, subscribe: EventgroupEntryRepr) -> Result<(), Error> {
+        let entry = EventgroupEntryRepr {
+            first_options: OptionRun::new(0, self.options.len() as u8),
+            second_options: OptionRun::new(0, 0),
+            ..subscribe
+        };
+
+        let id = (entry.service_id, entry.instance_id, entry.eventgroup_id);
+        if let Some(subscription) = self
+            .subscriptions
+            .iter_mut()
+            .flatten()
+            .find(|subscription| subscription.is_identified_by(id.0, id.1, id.2))
+        {
+            subscription.entry = entry;
+            return Ok(());
+        }
+
+        let slot = self
+            .subscriptions
+            .iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(Error::StorageFull)?;
+        *slot = Some(Subscription {
+            entry,
+            state: SubscriptionState::Down,
+            renew_at: None,
+            expires_at: None,
+        });
+        Ok(())
+    }
+
+    /// Removes a subscription.
+    ///
+    /// # Arguments
+    ///
+    /// * `service_id` - The service of the eventgroup
+    /// * `instance_id` - The service instance
+    /// * `eventgroup_id` - The eventgroup
+    /// * `buffer` - Scratch buffer holding the returned message
+    ///
+    /// # Returns
+    ///
+    /// Returns the StopSubscribeEventgroup message to send if the subscription was
+    /// requested or acknowledged, or an `Error` if the message does not fit into `buffer`.
+    pub fn remove<'b>(
+        &mut self,
+        service_id: u16,
+        instance_id: u16,
+        eventgroup_id: u16,
+        buffer: &'b mut [u8],
+    ) -> Result<Option<SdRepr<'b>>, Error> {
+        let Some(slot) = self.subscriptions.iter_mut().find(|slot| {
+            slot.is_some_and(|subscription| {
+                subscription.is_identified_by(service_id, instance_id, eventgroup_id)
+            })
+        }) else {
+            return Ok(None);
+        };
+        match slot.take() {
+            Some(subscription) if subscription.is_active() => {
+                let entry = EntryRepr::Eventgroup(subscription.entry.to_stop_subscribe());
+                SdRepr::from_entries(&[entry], self.options, buffer).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Sends the next due SubscribeEventgroup.
+    ///
+    /// Only one entry is returned per call; call repeatedly until it returns None.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    /// * `buffer` - Scratch buffer holding the returned message
+    ///
+    /// # Returns
+    ///
+    /// Returns the SubscribeEventgroup message to send if one is due, or an `Error` if
+    /// the message does not fit into `buffer`.
+    pub fn poll<'b>(
+        &mut self,
+        now: Duration,
+        buffer: &'b mut [u8],
+    ) -> Result<Option<SdRepr<'b>>, Error> {
+        let renewal_margin = self.renewal_margin;
+        let Some(subscription) = self
+            .subscriptions
+            .iter_mut()
+            .flatten()
+            .find(|subscription| subscription.renew_at.is_some_and(|at| at <= now))
+        else {
+            return Ok(None);
+        };
+
+        subscription.renew_at = match subscription.entry.ttl {
+            TTL_INFINITE => None,
+            ttl => {
+                let ttl = Duration::from_secs(ttl as u64);
+                Some(now + ttl.saturating_sub(renewal_margin).max(ttl / 2))
+            }
+        };
+        let entry = EntryRepr::Eventgroup(subscription.entry);
+        SdRepr::from_entries(&[entry], self.options, buffer).map(Some)
+    }
+
+    /// Processes a received entry.
+    ///
+    /// OfferService entries request the subscriptions of the offered instance that
+    /// are not active yet, StopOfferService entries tear them down, and
+    /// SubscribeEventgroupAck/Nack entries answer them. Other entries are ignored.
+    ///
+    /// A StopOfferService tears down one active subscription per call; call repeatedly
+    /// with the same entry until it returns None to tear down all of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    /// * `entry` - The received entry
+    ///
+    /// # Returns
+    ///
+    /// Returns the resulting state change of a subscription, if any.
+    pub fn process_entry(&mut self, now: Duration, entry: &EntryRepr) -> Option<SubscriptionEvent> {
+        match entry {
+            EntryRepr::Service(offer) if offer.is_stop_offer_service() => {
+                self.process_stop_offer(offer)
+            }
+            EntryRepr::Service(offer) => {
+                self.process_offer(now, offer);
+                None
+            }
+            EntryRepr::Eventgroup(answer) => self.process_answer(now, answer),
+        }
+    }
+
+    fn process_offer(&mut self, now: Duration, offer: &ServiceEntryRepr) {
+        if !offer.is_offer_service() {
+            return;
+        }
+        for subscription in self.subscriptions.iter_mut().flatten() {
+            if subscription.is_offered_by(offer) && !subscription.is_active() {
+                subscription.state = SubscriptionState::Requested;
+                subscription.renew_at = Some(now);
+            }
+        }
+    }
+
+    fn process_stop_offer(&mut self, offer: &ServiceEntryRepr) -> Option<SubscriptionEvent> {
+        for subscription in self.subscriptions.iter_mut().flatten() {
+            if !subscription.is_offered_by(offer) {
+                continue;
+            }
+            let active = subscription.is_active();
+            subscription.stop();
+            if active {
+                return Some(SubscriptionEvent::Stopped(subscription.entry));
+            }
+        }
+        None
+    }
+
+    fn process_answer(
+        &mut self,
+        now: Duration,
+        answer: &EventgroupEntryRepr,
+    ) -> Option<SubscriptionEvent> {
+        let subscription = self
+            .subscriptions
+            .iter_mut()
+            .flatten()
+            .find(|subscription| subscription.is_active() && answer.answers(&subscription.entry))?;
+
+        if answer.is_nack() {
+            subscription.stop();
+            subscription.state = SubscriptionState::Rejected;
+            return Some(SubscriptionEvent::Rejected(subscription.entry));
+        }
+
+        subscription.expires_at = match answer.ttl {
+            TTL_INFINITE => None,
+            ttl => Some(now + Duration::from_secs(ttl as u64)),
+        };
+        if subscription.state == SubscriptionState::Subscribed {
+            return None;
+        }
+        subscription.state = SubscriptionState::Subscribed;
+        Some(SubscriptionEvent::Subscribed(subscription.entry))
+    }
+
+    /// Tears down a subscription whose acknowledgement has expired at `now`.
+    ///
+    /// The subscription is requested again on the next offer. Call repeatedly until
+    /// it returns None to tear down all expired subscriptions.
+    ///
+    /// # Returns
+    ///
+    /// Returns the `SubscriptionEvent::Expired` event of the subscription, if any.
+    pub fn expire(&mut self, now: Duration) -> Option<SubscriptionEvent> {
+        let subscription = self
+            .subscriptions
+            .iter_mut()
+            .flatten()
+            .find(|subscription| subscription.expires_at.is_some_and(|at| at <= now))?;
+        subscription.stop();
+        Some(SubscriptionEvent::Expired(subscription.entry))
+    }
+}
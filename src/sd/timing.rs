@@ -0,0 +1,157 @@
+//! SD timing module
+//!
+//! This module contains the timing parameters and phases shared by the SD state machines.
+//! Time is passed in by the caller as a monotonic `Duration` since an arbitrary epoch, so
+//! the state machines do not depend on any clock or runtime.
+
+use core::fmt::{self, Display};
+use core::time::Duration;
+
+/// Timing parameters of the SD Initial Wait, Repetition and Main phases.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Timing {
+    /// Lower bound of the random delay of the Initial Wait phase
+    pub initial_delay_min: Duration,
+    /// Upper bound of the random delay of the Initial Wait phase
+    pub initial_delay_max: Duration,
+    /// Delay before the first message of the Repetition phase, doubled for every repetition
+    pub repetitions_base_delay: Duration,
+    /// Number of messages sent in the Repetition phase
+    pub repetitions_max: u8,
+    /// Interval of cyclic messages in the Main phase, zero to disable them
+    pub cyclic_offer_delay: Duration,
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Timing {
+            initial_delay_min: Duration::from_millis(10),
+            initial_delay_max: Duration::from_millis(100),
+            repetitions_base_delay: Duration::from_millis(200),
+            repetitions_max: 3,
+            cyclic_offer_delay: Duration::from_millis(2000),
+        }
+    }
+}
+
+impl Timing {
+    /// Returns the Initial Wait delay selected by `random`.
+    ///
+    /// The delay is uniformly spread between the minimum and maximum delay with a
+    /// resolution of one microsecond.
+    pub fn initial_delay(&self, random: u32) -> Duration {
+        let range = self
+            .initial_delay_max
+            .saturating_sub(self.initial_delay_min)
+            .as_micros() as u64;
+        self.initial_delay_min + Duration::from_micros(random as u64 % (range + 1))
+    }
+
+    /// Returns the delay before the given repetition (starting at zero).
+    pub fn repetition_delay(&self, repetition: u8) -> Duration {
+        self.repetitions_base_delay
+            .saturating_mul(1u32.checked_shl(repetition as u32).unwrap_or(u32::MAX))
+    }
+}
+
+/// Phase of an SD state machine
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Phase {
+    /// Not active: the service is not offered or not searched for
+    Down,
+    /// Waiting a random delay before the first message
+    InitialWait,
+    /// Sending messages with exponentially increasing delays
+    Repetition,
+    /// Sending cyclic messages, if enabled
+    Main,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Down => write!(f, "Down"),
+            Phase::InitialWait => write!(f, "Initial Wait"),
+            Phase::Repetition => write!(f, "Repetition"),
+            Phase::Main => write!(f, "Main"),
+        }
+    }
+}
+
+/// Drives the phase transitions and send deadlines shared by the SD state machines.
+#[derive(Debug, Clone)]
+pub(crate) struct Scheduler {
+    timing: Timing,
+    phase: Phase,
+    deadline: Option<Duration>,
+    repetition: u8,
+}
+
+impl Scheduler {
+    pub(crate) const fn new(timing: Timing) -> Self {
+        Scheduler {
+            timing,
+            phase: Phase::Down,
+            deadline: None,
+            repetition: 0,
+        }
+    }
+
+    pub(crate) const fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    pub(crate) const fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    pub(crate) const fn poll_at(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    /// Enters the Initial Wait phase.
+    pub(crate) fn start(&mut self, now: Duration, random: u32) {
+        self.phase = Phase::InitialWait;
+        self.deadline = Some(now + self.timing.initial_delay(random));
+        self.repetition = 0;
+    }
+
+    /// Returns to the Down phase.
+    pub(crate) fn stop(&mut self) {
+        self.phase = Phase::Down;
+        self.deadline = None;
+    }
+
+    /// Advances the phases and returns true if a message is due at `now`.
+    pub(crate) fn poll(&mut self, now: Duration) -> bool {
+        match self.deadline {
+            Some(deadline) if deadline <= now => {}
+            _ => return false,
+        }
+
+        match self.phase {
+            Phase::InitialWait if self.timing.repetitions_max > 0 => {
+                self.phase = Phase::Repetition;
+                self.repetition = 0;
+                self.deadline = Some(now + self.timing.repetition_delay(0));
+            }
+            Phase::Repetition if self.repetition + 1 < self.timing.repetitions_max => {
+                self.repetition += 1;
+                self.deadline = Some(now + self.timing.repetition_delay(self.repetition));
+            }
+            Phase::InitialWait | Phase::Repetition | Phase::Main => self.enter_main(now),
+            Phase::Down => return false,
+        }
+        true
+    }
+
+    /// Enters the Main phase, scheduling the next cyclic message if enabled.
+    pub(crate) fn enter_main(&mut self, now: Duration) {
+        self.phase = Phase::Main;
+        self.deadline = if self.timing.cyclic_offer_delay.is_zero() {
+            None
+        } else {
+            Some(now + self.timing.cyclic_offer_delay)
+        };
+    }
+}
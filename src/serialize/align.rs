@@ -0,0 +1,170 @@
+//! Alignment module
+//!
+//! This module implements the alignment options of the SOME/IP serialization. Padding
+//! with zeros is inserted after dynamic length data elements to align the following
+//! data element to an 8, 16, 32 or 64-bit boundary, counted from the start of the
+//! payload. Data elements such as the structs of an array can also be padded to a fixed
+//! size.
+
+use crate::error::Error;
+use crate::serialize::{Reader, SomeIpDeserialize, SomeIpSerialize, Writer};
+
+/// Boundary data elements are aligned to, counted from the start of the payload.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub enum Alignment {
+    /// 8-bit alignment, no padding
+    #[default]
+    Bits8,
+    /// 16-bit alignment
+    Bits16,
+    /// 32-bit alignment
+    Bits32,
+    /// 64-bit alignment
+    Bits64,
+}
+
+impl Alignment {
+    /// Returns the alignment for a number of bits, as configured in the interface
+    /// description.
+    ///
+    /// # Arguments
+    ///
+    /// * `bits` - The alignment in bits: 8, 16, 32 or 64
+    ///
+    /// # Returns
+    ///
+    /// * `Option<Alignment>` - The alignment, or `None` for any other number of bits
+    pub const fn from_bits(bits: u32) -> Option<Alignment> {
+        match bits {
+            8 => Some(Alignment::Bits8),
+            16 => Some(Alignment::Bits16),
+            32 => Some(Alignment::Bits32),
+            64 => Some(Alignment::Bits64),
+            _ => None,
+        }
+    }
+
+    /// Returns the alignment in bytes
+    pub const fn size(&self) -> usize {
+        match self {
+            Alignment::Bits8 => 1,
+            Alignment::Bits16 => 2,
+            Alignment::Bits32 => 4,
+            Alignment::Bits64 => 8,
+        }
+    }
+
+    /// Pads with zeros up to the next boundary.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to pad
+    pub fn pad(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        writer.align(self.size())
+    }
+
+    /// Skips the padding up to the next boundary.
+    ///
+    /// Padding missing at the end of the payload is tolerated, since no data element
+    /// follows it.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to skip the padding of
+    pub fn skip(&self, reader: &mut Reader<'_>) -> Result<(), Error> {
+        if reader.is_empty() {
+            return Ok(());
+        }
+        reader.align(self.size())
+    }
+}
+
+/// Fixed size a data element is padded to, such as the structs of an array.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Padding {
+    /// Size of the padded data element in bytes
+    pub size: usize,
+}
+
+impl Padding {
+    /// Create a new padding.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Size of the padded data element in bytes
+    pub const fn new(size: usize) -> Self {
+        Padding { size }
+    }
+
+    /// Writes a data element and pads it with zeros to the fixed size.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `f` - Writes the data element
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::PaddingOverflow` if the data element exceeds the fixed size,
+    /// or the error returned by `f`. The cursor does not move on error.
+    pub fn write<F>(&self, writer: &mut Writer<'_>, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Writer<'_>) -> Result<(), Error>,
+    {
+        let start = writer.position();
+        let result = f(writer).and_then(|()| {
+            let length = writer.position() - start;
+            if length > self.size {
+                return Err(Error::PaddingOverflow(length as u32));
+            }
+            writer.advance(self.size - length)?.fill(0);
+            Ok(())
+        });
+        if result.is_err() {
+            writer.truncate(start);
+        }
+        result
+    }
+
+    /// Reads a data element of the fixed size.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize from
+    ///
+    /// # Returns
+    ///
+    /// Returns a reader over the data element and its padding, or `Error::Truncated` if
+    /// the payload is shorter than the fixed size.
+    pub fn read<'a>(&self, reader: &mut Reader<'a>) -> Result<Reader<'a>, Error> {
+        reader.limit(self.size)
+    }
+
+    /// Serializes a value padded to the fixed size.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `value` - The value to serialize
+    pub fn serialize<T>(&self, writer: &mut Writer<'_>, value: &T) -> Result<(), Error>
+    where
+        T: SomeIpSerialize + ?Sized,
+    {
+        self.write(writer, |writer| value.serialize(writer))
+    }
+
+    /// Deserializes a value padded to the fixed size, skipping the padding.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize from
+    pub fn deserialize<'a, T>(&self, reader: &mut Reader<'a>) -> Result<T, Error>
+    where
+        T: SomeIpDeserialize<'a>,
+    {
+        let mut peek = reader.clone();
+        let value = self.read(&mut peek)?.read()?;
+        *reader = peek;
+        Ok(value)
+    }
+}
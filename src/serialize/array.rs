@@ -0,0 +1,467 @@
+//! Array module
+//!
+//! This module implements the serialization of SOME/IP arrays:
+//!
+//! - Fixed length arrays `[T; N]`, serialized as their elements without a length field
+//! - Dynamic length arrays, serialized as their elements preceded by a `LengthField`
+//! - Multidimensional arrays, where every dimension has its own length field
+//!
+//! Deserialized arrays borrow from the payload: [`ArrayRef`] iterates over elements of a
+//! fixed size in place, and [`Elements`] deserializes any other elements one by one.
+
+use crate::error::Error;
+use crate::serialize::{
+    FixedField, LengthField, Reader, SomeIpDeserialize, SomeIpSerialize, Writer,
+};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::slice::ChunksExact;
+
+impl<T: SomeIpSerialize, const N: usize> SomeIpSerialize for [T; N] {
+    /// Serializes the elements back to back, without a length field.
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        self.as_slice().serialize(writer)
+    }
+}
+
+impl<'a, T: SomeIpDeserialize<'a>, const N: usize> SomeIpDeserialize<'a> for [T; N] {
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+        let mut peek = reader.clone();
+        let mut error = None;
+        let elements: [Option<T>; N] = core::array::from_fn(|_| match error {
+            Some(_) => None,
+            None => peek.read().map_err(|e| error = Some(e)).ok(),
+        });
+        if let Some(error) = error {
+            return Err(error);
+        }
+        *reader = peek;
+        // Every element is present without an error
+        Ok(elements.map(|element| element.unwrap()))
+    }
+}
+
+/// A dynamic length array of fixed size elements, borrowed from the payload.
+///
+/// Elements are read in place on access. As a data element, the array takes all
+/// remaining bytes, so it is usually preceded by a length field.
+///
+/// # Example
+///
+/// ```rust
+/// use someip_wire::serialize::{ArrayRef, LengthField, Reader};
+///
+/// let payload = [0x00, 0x04, 0x01, 0x02, 0x03, 0x04];
+/// let mut reader = Reader::new(&payload);
+/// let array: ArrayRef<u16> = LengthField::U16.deserialize(&mut reader).unwrap();
+/// assert_eq!(array.len(), 2);
+/// assert_eq!(array.get(1), Some(0x0304));
+/// assert!(array.iter().eq([0x0102, 0x0304]));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ArrayRef<'a, T> {
+    data: &'a [u8],
+    element: PhantomData<T>,
+}
+
+impl<'a, T: FixedField> ArrayRef<'a, T> {
+    /// Creates an array over serialized elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The serialized elements
+    ///
+    /// # Returns
+    ///
+    /// Returns the array, or `Error::Truncated` if the last element is incomplete.
+    pub fn new(data: &'a [u8]) -> Result<Self, Error> {
+        if !data.len().is_multiple_of(Self::element_size()) {
+            return Err(Error::Truncated);
+        }
+        Ok(ArrayRef {
+            data,
+            element: PhantomData,
+        })
+    }
+
+    fn element_size() -> usize {
+        T::SIZE.max(1)
+    }
+
+    /// Returns the number of elements
+    pub fn len(&self) -> usize {
+        self.data.len() / Self::element_size()
+    }
+
+    /// Returns true if the array has no elements
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the element at an index, or `None` if the index is out of bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the element
+    pub fn get(&self, index: usize) -> Option<T> {
+        let size = Self::element_size();
+        let start = index.checked_mul(size)?;
+        self.data
+            .get(start..start.checked_add(size)?)
+            .map(T::read_from)
+    }
+
+    /// Returns an iterator over the elements
+    pub fn iter(&self) -> ArrayIter<'a, T> {
+        ArrayIter {
+            chunks: self.data.chunks_exact(Self::element_size()),
+            element: PhantomData,
+        }
+    }
+
+    /// Returns the serialized elements
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+impl<'a, T: FixedField> IntoIterator for ArrayRef<'a, T> {
+    type Item = T;
+    type IntoIter = ArrayIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T: FixedField> SomeIpDeserialize<'a> for ArrayRef<'a, T> {
+    /// Takes all remaining bytes.
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+        let array = ArrayRef::new(reader.rest())?;
+        reader.take(array.data.len())?;
+        Ok(array)
+    }
+}
+
+impl<T: FixedField> SomeIpSerialize for ArrayRef<'_, T> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        writer.write_bytes(self.data)
+    }
+}
+
+/// An iterator over the elements of an `ArrayRef`.
+#[derive(Debug, Clone)]
+pub struct ArrayIter<'a, T> {
+    chunks: ChunksExact<'a, u8>,
+    element: PhantomData<T>,
+}
+
+impl<T: FixedField> Iterator for ArrayIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.chunks.next().map(T::read_from)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<T> {
+        self.chunks.nth(n).map(T::read_from)
+    }
+}
+
+impl<T: FixedField> DoubleEndedIterator for ArrayIter<'_, T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.chunks.next_back().map(T::read_from)
+    }
+}
+
+impl<T: FixedField> ExactSizeIterator for ArrayIter<'_, T> {}
+
+impl<T: FixedField> FusedIterator for ArrayIter<'_, T> {}
+
+/// An iterator deserializing the elements of an array one by one.
+///
+/// Suited for elements of variable size, such as structs containing strings. As a data
+/// element, the array takes all remaining bytes. Iteration stops after the first error.
+#[derive(Debug, Clone)]
+pub struct Elements<'a, T> {
+    reader: Reader<'a>,
+    element: PhantomData<T>,
+}
+
+impl<'a, T: SomeIpDeserialize<'a>> Elements<'a, T> {
+    /// Creates an iterator over the elements in a reader.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader over the serialized elements
+    pub fn new(reader: Reader<'a>) -> Self {
+        Elements {
+            reader,
+            element: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: SomeIpDeserialize<'a>> Iterator for Elements<'a, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.is_empty() {
+            return None;
+        }
+        let element = self.reader.read();
+        if element.is_err() {
+            self.reader = Reader::new(&[]);
+        }
+        Some(element)
+    }
+}
+
+impl<'a, T: SomeIpDeserialize<'a>> SomeIpDeserialize<'a> for Elements<'a, T> {
+    /// Takes all remaining bytes.
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+        let remaining = reader.remaining();
+        Ok(Elements::new(reader.limit(remaining)?))
+    }
+}
+
+/// Layout of a multidimensional array, with one length field per dimension.
+///
+/// Length fields are given from the outermost to the innermost dimension. Every
+/// dimension but the innermost is a sequence of arrays of the next dimension, each
+/// preceded by its own length field. Only the outermost dimension can go without a
+/// length field.
+///
+/// # Example
+///
+/// ```rust
+/// use someip_wire::serialize::{ArrayFormat, ArrayRef, LengthField, Reader, Writer};
+///
+/// // A grid of 2 rows with 3 cells each
+/// let format = ArrayFormat::new(&[LengthField::U16, LengthField::U8]);
+/// let cells = [1u8, 2, 3, 4, 5, 6];
+///
+/// let mut buffer = [0u8; 16];
+/// let mut writer = Writer::new(&mut buffer);
+/// format.serialize(&mut writer, &[2, 3], &cells).unwrap();
+/// assert_eq!(writer.written(), &[0, 8, 3, 1, 2, 3, 3, 4, 5, 6]);
+///
+/// let mut reader = Reader::new(&buffer[..10]);
+/// let grid = format.read(&mut reader).unwrap();
+/// for (row, expected) in grid.rows().zip(cells.chunks(3)) {
+///     let row: ArrayRef<u8> = row.unwrap().array().unwrap();
+///     assert!(row.iter().eq(expected.iter().copied()));
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ArrayFormat<'f> {
+    /// Length fields of the dimensions, outermost first
+    pub length_fields: &'f [LengthField],
+}
+
+impl<'f> ArrayFormat<'f> {
+    /// Create a new array format.
+    ///
+    /// # Arguments
+    ///
+    /// * `length_fields` - Length fields of the dimensions, outermost first
+    pub const fn new(length_fields: &'f [LengthField]) -> Self {
+        ArrayFormat { length_fields }
+    }
+
+    /// Returns the number of dimensions
+    pub const fn dimensions(&self) -> usize {
+        self.length_fields.len()
+    }
+
+    /// Serializes a multidimensional array from its elements in row-major order.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `shape` - The number of elements in every dimension, outermost first
+    /// * `elements` - The elements, with the innermost dimension stored contiguously
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::InvalidArrayShape` if an inner dimension has no length field or the
+    /// shape does not match the dimensions or the number of elements, or
+    /// `Error::LengthFieldOverflow` if a dimension is too long for its length field. The
+    /// cursor does not move on error.
+    pub fn serialize<T>(
+        &self,
+        writer: &mut Writer<'_>,
+        shape: &[usize],
+        elements: &[T],
+    ) -> Result<(), Error>
+    where
+        T: SomeIpSerialize,
+    {
+        self.check()?;
+        let count = shape
+            .iter()
+            .try_fold(1usize, |count, &len| count.checked_mul(len));
+        if shape.len() != self.dimensions() || count != Some(elements.len()) {
+            return Err(Error::InvalidArrayShape);
+        }
+        let start = writer.position();
+        let result = Self::emit(writer, self.length_fields, shape, elements);
+        if result.is_err() {
+            writer.truncate(start);
+        }
+        result
+    }
+
+    /// Checks that every inner dimension has a length field, as rows without one cannot
+    /// be told apart.
+    fn check(&self) -> Result<(), Error> {
+        let inner = self.length_fields.get(1..).unwrap_or_default();
+        if inner.contains(&LengthField::None) {
+            return Err(Error::InvalidArrayShape);
+        }
+        Ok(())
+    }
+
+    fn emit<T: SomeIpSerialize>(
+        writer: &mut Writer<'_>,
+        length_fields: &[LengthField],
+        shape: &[usize],
+        elements: &[T],
+    ) -> Result<(), Error> {
+        let (Some((length_field, inner)), Some((&len, inner_shape))) =
+            (length_fields.split_first(), shape.split_first())
+        else {
+            return elements.serialize(writer);
+        };
+        length_field.write(writer, |writer| {
+            if inner.is_empty() {
+                return elements.serialize(writer);
+            }
+            let stride = elements.len() / len.max(1);
+            (0..len).try_for_each(|index| {
+                let row = &elements[index * stride..(index + 1) * stride];
+                Self::emit(writer, inner, inner_shape, row)
+            })
+        })
+    }
+
+    /// Reads the outermost dimension of a multidimensional array.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize from
+    ///
+    /// # Returns
+    ///
+    /// Returns the outermost dimension, `Error::InvalidArrayShape` if an inner dimension
+    /// has no length field, or `Error::Truncated` if the payload is shorter than its
+    /// length field announces.
+    pub fn read<'a>(&self, reader: &mut Reader<'a>) -> Result<Dimension<'a, 'f>, Error> {
+        self.check()?;
+        match self.length_fields.split_first() {
+            Some((length_field, inner)) => Ok(Dimension {
+                reader: length_field.read(reader)?,
+                length_fields: inner,
+            }),
+            None => Err(Error::InvalidArrayShape),
+        }
+    }
+}
+
+/// One dimension of a multidimensional array, borrowed from the payload.
+#[derive(Debug, Clone)]
+pub struct Dimension<'a, 'f> {
+    reader: Reader<'a>,
+    length_fields: &'f [LengthField],
+}
+
+impl<'a, 'f> Dimension<'a, 'f> {
+    /// Returns true if this is the innermost dimension, holding the elements
+    pub fn is_innermost(&self) -> bool {
+        self.length_fields.is_empty()
+    }
+
+    /// Returns a reader over the serialized content of the dimension
+    pub fn reader(&self) -> Reader<'a> {
+        self.reader.clone()
+    }
+
+    /// Returns an iterator over the arrays of the next dimension.
+    ///
+    /// The innermost dimension has no rows; its elements are read with `array` or
+    /// `elements` instead.
+    pub fn rows(&self) -> Rows<'a, 'f> {
+        match self.length_fields.split_first() {
+            Some((&length_field, inner)) => Rows {
+                reader: self.reader.clone(),
+                length_field,
+                length_fields: inner,
+            },
+            None => Rows {
+                reader: Reader::new(&[]),
+                length_field: LengthField::None,
+                length_fields: &[],
+            },
+        }
+    }
+
+    /// Returns the elements of the innermost dimension, read in place.
+    ///
+    /// # Returns
+    ///
+    /// Returns the elements, or `Error::InvalidArrayShape` if this is not the innermost
+    /// dimension.
+    pub fn array<T: FixedField>(&self) -> Result<ArrayRef<'a, T>, Error> {
+        if !self.is_innermost() {
+            return Err(Error::InvalidArrayShape);
+        }
+        ArrayRef::new(self.reader.rest())
+    }
+
+    /// Returns an iterator deserializing the elements of the innermost dimension.
+    ///
+    /// # Returns
+    ///
+    /// Returns the iterator, or `Error::InvalidArrayShape` if this is not the innermost
+    /// dimension.
+    pub fn elements<T: SomeIpDeserialize<'a>>(&self) -> Result<Elements<'a, T>, Error> {
+        if !self.is_innermost() {
+            return Err(Error::InvalidArrayShape);
+        }
+        Ok(Elements::new(self.reader.clone()))
+    }
+}
+
+/// An iterator over the arrays of the next dimension.
+///
+/// Iteration stops after the first error.
+#[derive(Debug, Clone)]
+pub struct Rows<'a, 'f> {
+    reader: Reader<'a>,
+    length_field: LengthField,
+    length_fields: &'f [LengthField],
+}
+
+impl<'a, 'f> Iterator for Rows<'a, 'f> {
+    type Item = Result<Dimension<'a, 'f>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.is_empty() {
+            return None;
+        }
+        match self.length_field.read(&mut self.reader) {
+            Ok(reader) => Some(Ok(Dimension {
+                reader,
+                length_fields: self.length_fields,
+            })),
+            Err(e) => {
+                self.reader = Reader::new(&[]);
+                Some(Err(e))
+            }
+        }
+    }
+}
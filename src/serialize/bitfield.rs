@@ -0,0 +1,190 @@
+//! Bitfield module
+//!
+//! This module implements bitfields: named bits packed into an unsigned integer of 8, 16
+//! or 32 bits, which is serialized like the integer itself.
+//!
+//! Bitfields are declared with the [`bitfield!`](crate::bitfield) macro. Bits without a
+//! name are kept when deserializing, so that bits added by newer interface versions
+//! survive a round trip.
+
+/// Declares a bitfield type with named bits.
+///
+/// The type wraps the raw integer and implements `SomeIpSerialize`, `SomeIpDeserialize`
+/// and `FixedField`, so it can be used in payload structs as well as in payload views.
+/// Named bits are associated constants and are combined with `|`.
+///
+/// # Example
+///
+/// ```rust
+/// use someip_wire::bitfield;
+/// use someip_wire::serialize;
+///
+/// bitfield! {
+///     /// Open doors of a vehicle.
+///     pub struct Doors: u8 {
+///         /// Driver door
+///         const DRIVER = 0x01;
+///         /// Passenger door
+///         const PASSENGER = 0x02;
+///         /// Trunk lid
+///         const TRUNK = 0x80;
+///     }
+/// }
+///
+/// let mut doors = Doors::DRIVER | Doors::TRUNK;
+/// doors.set(Doors::TRUNK, false);
+/// assert!(doors.contains(Doors::DRIVER));
+/// assert_eq!(doors.bits(), 0x01);
+///
+/// let mut buffer = [0u8; 1];
+/// assert_eq!(serialize::to_slice(&doors, &mut buffer), Ok(1));
+/// assert_eq!(serialize::from_slice(&[0x03]), Ok(Doors::DRIVER | Doors::PASSENGER));
+/// assert_eq!(Doors::from_bits(0x04), None);
+/// ```
+#[macro_export]
+macro_rules! bitfield {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident: $ty:ty {
+            $(
+                $(#[$bit_meta:meta])*
+                const $bit:ident = $value:expr;
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+        $vis struct $name {
+            bits: $ty,
+        }
+
+        #[allow(dead_code)]
+        impl $name {
+            $(
+                $(#[$bit_meta])*
+                pub const $bit: Self = $name { bits: $value };
+            )*
+
+            /// All named bits
+            pub const ALL: Self = $name { bits: 0 $(| $value)* };
+
+            /// Returns a bitfield without any bits set
+            pub const fn empty() -> Self {
+                $name { bits: 0 }
+            }
+
+            /// Creates a bitfield from its raw value.
+            ///
+            /// # Arguments
+            ///
+            /// * `bits` - The raw value
+            ///
+            /// # Returns
+            ///
+            /// Returns the bitfield, or `None` if the raw value has bits without a name.
+            pub const fn from_bits(bits: $ty) -> ::core::option::Option<Self> {
+                if bits & !Self::ALL.bits == 0 {
+                    ::core::option::Option::Some($name { bits })
+                } else {
+                    ::core::option::Option::None
+                }
+            }
+
+            /// Creates a bitfield from its raw value, keeping bits without a name.
+            ///
+            /// # Arguments
+            ///
+            /// * `bits` - The raw value
+            pub const fn from_bits_retain(bits: $ty) -> Self {
+                $name { bits }
+            }
+
+            /// Returns the raw value
+            pub const fn bits(&self) -> $ty {
+                self.bits
+            }
+
+            /// Returns true if no bits are set
+            pub const fn is_empty(&self) -> bool {
+                self.bits == 0
+            }
+
+            /// Returns true if all bits of `other` are set
+            pub const fn contains(&self, other: Self) -> bool {
+                self.bits & other.bits == other.bits
+            }
+
+            /// Sets the bits of `other`
+            pub fn insert(&mut self, other: Self) {
+                self.bits |= other.bits;
+            }
+
+            /// Clears the bits of `other`
+            pub fn remove(&mut self, other: Self) {
+                self.bits &= !other.bits;
+            }
+
+            /// Sets or clears the bits of `other`
+            pub fn set(&mut self, other: Self, value: bool) {
+                if value {
+                    self.insert(other);
+                } else {
+                    self.remove(other);
+                }
+            }
+        }
+
+        impl ::core::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, other: Self) -> Self {
+                $name { bits: self.bits | other.bits }
+            }
+        }
+
+        impl ::core::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, other: Self) {
+                self.bits |= other.bits;
+            }
+        }
+
+        impl ::core::ops::BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, other: Self) -> Self {
+                $name { bits: self.bits & other.bits }
+            }
+        }
+
+        impl $crate::serialize::SomeIpSerialize for $name {
+            fn serialize(
+                &self,
+                writer: &mut $crate::serialize::Writer<'_>,
+            ) -> ::core::result::Result<(), $crate::error::Error> {
+                writer.write(&self.bits)
+            }
+        }
+
+        impl<'a> $crate::serialize::SomeIpDeserialize<'a> for $name {
+            fn deserialize(
+                reader: &mut $crate::serialize::Reader<'a>,
+            ) -> ::core::result::Result<Self, $crate::error::Error> {
+                ::core::result::Result::Ok($name { bits: reader.read()? })
+            }
+        }
+
+        impl $crate::serialize::FixedField for $name {
+            const SIZE: usize = <$ty as $crate::serialize::FixedField>::SIZE;
+
+            fn read_from(bytes: &[u8]) -> Self {
+                $name {
+                    bits: <$ty as $crate::serialize::FixedField>::read_from(bytes),
+                }
+            }
+
+            fn write_to(&self, bytes: &mut [u8]) {
+                $crate::serialize::FixedField::write_to(&self.bits, bytes);
+            }
+        }
+    };
+}
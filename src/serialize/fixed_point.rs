@@ -0,0 +1,164 @@
+//! Fixed-point module
+//!
+//! This module implements scaled physical values. A physical value such as a speed or a
+//! temperature is transported as a raw integer, related to it by a linear conversion:
+//!
+//! ```text
+//! physical = raw * factor + offset
+//! ```
+//!
+//! The physical value can be limited to a valid range, which is checked in both
+//! directions.
+
+use crate::error::Error;
+use crate::serialize::{Reader, SomeIpDeserialize, SomeIpSerialize, Writer};
+use core::marker::PhantomData;
+
+/// An integer type carrying the raw value of a scaled physical value.
+pub trait RawValue: Copy + SomeIpSerialize + for<'a> SomeIpDeserialize<'a> {
+    /// Converts the raw value to a floating point number
+    fn to_f64(self) -> f64;
+
+    /// Converts a rounded floating point number to the raw value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value, already rounded to an integer
+    ///
+    /// # Returns
+    ///
+    /// Returns the raw value, or `None` if it does not fit the integer type.
+    fn from_integer(value: i128) -> Option<Self>;
+}
+
+macro_rules! impl_raw_value {
+    ($($ty:ty),*) => {
+        $(
+            impl RawValue for $ty {
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_integer(value: i128) -> Option<Self> {
+                    <$ty>::try_from(value).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_raw_value!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+/// Linear conversion between a physical value and its raw integer of type `R`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct FixedPoint<R> {
+    /// Physical value of one raw unit
+    pub factor: f64,
+    /// Physical value of the raw value zero
+    pub offset: f64,
+    /// Smallest valid physical value
+    pub min: f64,
+    /// Largest valid physical value
+    pub max: f64,
+    raw: PhantomData<R>,
+}
+
+impl<R: RawValue> FixedPoint<R> {
+    /// Create a new conversion without range limits.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor` - Physical value of one raw unit
+    /// * `offset` - Physical value of the raw value zero
+    pub const fn new(factor: f64, offset: f64) -> Self {
+        FixedPoint {
+            factor,
+            offset,
+            min: f64::NEG_INFINITY,
+            max: f64::INFINITY,
+            raw: PhantomData,
+        }
+    }
+
+    /// Returns the conversion limited to a range of physical values.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - Smallest valid physical value
+    /// * `max` - Largest valid physical value
+    pub const fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = min;
+        self.max = max;
+        self
+    }
+
+    /// Returns true if the physical value is within the valid range
+    pub fn contains(&self, physical: f64) -> bool {
+        physical >= self.min && physical <= self.max
+    }
+
+    /// Converts a raw value to its physical value.
+    ///
+    /// # Arguments
+    ///
+    /// * `raw` - The raw value
+    ///
+    /// # Returns
+    ///
+    /// Returns the physical value, or `Error::ValueOutOfRange` if it is outside the
+    /// valid range.
+    pub fn to_physical(&self, raw: R) -> Result<f64, Error> {
+        let physical = raw.to_f64() * self.factor + self.offset;
+        if self.contains(physical) {
+            Ok(physical)
+        } else {
+            Err(Error::ValueOutOfRange)
+        }
+    }
+
+    /// Converts a physical value to the nearest raw value.
+    ///
+    /// # Arguments
+    ///
+    /// * `physical` - The physical value
+    ///
+    /// # Returns
+    ///
+    /// Returns the raw value, or `Error::ValueOutOfRange` if the physical value is
+    /// outside the valid range or the raw value does not fit its integer type.
+    pub fn to_raw(&self, physical: f64) -> Result<R, Error> {
+        if !self.contains(physical) {
+            return Err(Error::ValueOutOfRange);
+        }
+        let raw = (physical - self.offset) / self.factor;
+        // Rejects NaN and values beyond any integer type before the saturating cast
+        if raw.is_nan() || raw.abs() >= 1e38 {
+            return Err(Error::ValueOutOfRange);
+        }
+        // Round half away from zero, as `f64::round` is not available without std
+        let rounded = (if raw < 0.0 { raw - 0.5 } else { raw + 0.5 }) as i128;
+        R::from_integer(rounded).ok_or(Error::ValueOutOfRange)
+    }
+
+    /// Serializes a physical value as its raw value.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `physical` - The physical value
+    pub fn serialize(&self, writer: &mut Writer<'_>, physical: f64) -> Result<(), Error> {
+        writer.write(&self.to_raw(physical)?)
+    }
+
+    /// Deserializes a raw value and converts it to its physical value.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize from
+    pub fn deserialize(&self, reader: &mut Reader<'_>) -> Result<f64, Error> {
+        let mut peek = reader.clone();
+        let physical = self.to_physical(peek.read()?)?;
+        *reader = peek;
+        Ok(physical)
+    }
+}
@@ -0,0 +1,235 @@
+//! Length field module
+//!
+//! This module contains the `LengthField` type, which describes the length field
+//! preceding dynamic length data elements such as arrays, strings and structs.
+
+use crate::error::Error;
+use crate::serialize::{Alignment, Reader, SomeIpDeserialize, SomeIpSerialize, Writer};
+use byteorder::{ByteOrder, NetworkEndian};
+
+/// Size of the length field preceding a data element.
+///
+/// The length field holds the number of bytes of the data element that follows it,
+/// excluding the length field itself. Its size is configured per data element in the
+/// interface definition and defaults to 32 bits.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub enum LengthField {
+    /// No length field, the data element extends to the end of the enclosing data
+    None,
+    /// 8-bit length field
+    U8,
+    /// 16-bit length field
+    U16,
+    /// 32-bit length field
+    #[default]
+    U32,
+}
+
+impl LengthField {
+    /// Returns the size of the length field in bytes
+    pub const fn size(&self) -> usize {
+        match self {
+            LengthField::None => 0,
+            LengthField::U8 => 1,
+            LengthField::U16 => 2,
+            LengthField::U32 => 4,
+        }
+    }
+
+    /// Returns the largest length the length field can hold
+    pub const fn max_length(&self) -> usize {
+        match self {
+            LengthField::None => usize::MAX,
+            LengthField::U8 => u8::MAX as usize,
+            LengthField::U16 => u16::MAX as usize,
+            LengthField::U32 => u32::MAX as usize,
+        }
+    }
+
+    /// Writes a data element preceded by the length field.
+    ///
+    /// Space for the length field is reserved first; it is filled in once the data
+    /// element has been written and its length is known.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `f` - Writes the data element
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::LengthFieldOverflow` if the data element is too long for the
+    /// length field, or the error returned by `f`. The cursor does not move on error.
+    pub fn write<F>(&self, writer: &mut Writer<'_>, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Writer<'_>) -> Result<(), Error>,
+    {
+        let start = writer.position();
+        writer.advance(self.size())?;
+        let result = f(writer).and_then(|()| {
+            let length = writer.position() - start - self.size();
+            self.write_length(writer, start, length)
+        });
+        if result.is_err() {
+            writer.truncate(start);
+        }
+        result
+    }
+
+    /// Fills in a length field reserved earlier.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer holding the reserved length field
+    /// * `at` - The position of the length field
+    /// * `length` - The length of the data element
+    pub(crate) fn write_length(
+        &self,
+        writer: &mut Writer<'_>,
+        at: usize,
+        length: usize,
+    ) -> Result<(), Error> {
+        if length > self.max_length() {
+            return Err(Error::LengthFieldOverflow(length as u32));
+        }
+        let field = &mut writer.written_mut()[at..at + self.size()];
+        match self {
+            LengthField::None => {}
+            LengthField::U8 => field[0] = length as u8,
+            LengthField::U16 => NetworkEndian::write_u16(field, length as u16),
+            LengthField::U32 => NetworkEndian::write_u32(field, length as u32),
+        }
+        Ok(())
+    }
+
+    /// Reads the length field, or returns the remaining length without a length field.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader positioned at the length field
+    pub(crate) fn read_length(&self, reader: &mut Reader<'_>) -> Result<usize, Error> {
+        Ok(match self {
+            LengthField::None => reader.remaining(),
+            LengthField::U8 => reader.read::<u8>()? as usize,
+            LengthField::U16 => reader.read::<u16>()? as usize,
+            LengthField::U32 => reader.read::<u32>()? as usize,
+        })
+    }
+
+    /// Reads the length field and the data element following it.
+    ///
+    /// Without a length field, the data element takes all remaining bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize from
+    ///
+    /// # Returns
+    ///
+    /// Returns a reader over the data element, or `Error::Truncated` if the payload is
+    /// shorter than the length field announces.
+    pub fn read<'a>(&self, reader: &mut Reader<'a>) -> Result<Reader<'a>, Error> {
+        let mut peek = reader.clone();
+        let length = self.read_length(&mut peek)?;
+        let data = peek.limit(length)?;
+        *reader = peek;
+        Ok(data)
+    }
+
+    /// Serializes a value preceded by the length field.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `value` - The value to serialize
+    pub fn serialize<T>(&self, writer: &mut Writer<'_>, value: &T) -> Result<(), Error>
+    where
+        T: SomeIpSerialize + ?Sized,
+    {
+        self.write(writer, |writer| value.serialize(writer))
+    }
+
+    /// Deserializes a value preceded by the length field.
+    ///
+    /// Bytes covered by the length field but not consumed by the value are skipped, so
+    /// that data elements extended by newer interface versions can still be read.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize from
+    pub fn deserialize<'a, T>(&self, reader: &mut Reader<'a>) -> Result<T, Error>
+    where
+        T: SomeIpDeserialize<'a>,
+    {
+        self.read(reader)?.read()
+    }
+
+    /// Serializes a value preceded by the length field and followed by the padding up to
+    /// the next boundary.
+    ///
+    /// The padding is not covered by the length field.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `value` - The value to serialize
+    /// * `alignment` - The boundary the following data element is aligned to
+    pub fn serialize_aligned<T>(
+        &self,
+        writer: &mut Writer<'_>,
+        value: &T,
+        alignment: Alignment,
+    ) -> Result<(), Error>
+    where
+        T: SomeIpSerialize + ?Sized,
+    {
+        let start = writer.position();
+        let result = self
+            .serialize(writer, value)
+            .and_then(|()| alignment.pad(writer));
+        if result.is_err() {
+            writer.truncate(start);
+        }
+        result
+    }
+
+    /// Deserializes a value preceded by the length field and skips the padding up to the
+    /// next boundary.
+    ///
+    /// Padding covered by the length field, as written by some implementations, is
+    /// skipped along with the data element.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize from
+    /// * `alignment` - The boundary the following data element is aligned to
+    pub fn deserialize_aligned<'a, T>(
+        &self,
+        reader: &mut Reader<'a>,
+        alignment: Alignment,
+    ) -> Result<T, Error>
+    where
+        T: SomeIpDeserialize<'a>,
+    {
+        let mut peek = reader.clone();
+        let value = self.deserialize(&mut peek)?;
+        alignment.skip(&mut peek)?;
+        *reader = peek;
+        Ok(value)
+    }
+}
+
+impl<T: SomeIpSerialize> SomeIpSerialize for [T] {
+    /// Serializes the elements back to back, without a length field.
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        self.iter()
+            .try_for_each(|element| element.serialize(writer))
+    }
+}
+
+impl<'a> SomeIpDeserialize<'a> for &'a [u8] {
+    /// Borrows all remaining bytes, typically the data element behind a length field.
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+        reader.take(reader.remaining())
+    }
+}
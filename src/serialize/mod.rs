@@ -0,0 +1,1175 @@
+//! SOME/IP payload serialization
+//!
+//! The SOME/IP serialization rules (the "SOME/IP transformer") define how the data
+//! types of a service interface are laid out in the payload of a message. All data is
+//! written in big-endian byte order without implicit padding.
+//!
+//! Types implement [`SomeIpSerialize`] to be written into a [`Writer`], and
+//! [`SomeIpDeserialize`] to be read from a [`Reader`]. Both cursors work on plain byte
+//! slices and check every access, so a payload is serialized directly into the buffer
+//! of a packet and deserialized from `Repr::data`.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use someip_wire::serialize::{self, Reader, Writer};
+//!
+//! let mut buffer = [0u8; 7];
+//! let mut writer = Writer::new(&mut buffer);
+//! writer.write(&true).unwrap();
+//! writer.write(&0x1234u16).unwrap();
+//! writer.write(&-1.5f32).unwrap();
+//! assert_eq!(writer.position(), 7);
+//! assert_eq!(buffer, [0x01, 0x12, 0x34, 0xBF, 0xC0, 0x00, 0x00]);
+//!
+//! let mut reader = Reader::new(&buffer);
+//! assert_eq!(reader.read::<bool>(), Ok(true));
+//! assert_eq!(reader.read::<u16>(), Ok(0x1234));
+//! assert_eq!(reader.read::<f32>(), Ok(-1.5));
+//!
+//! assert_eq!(serialize::to_slice(&0xAABBu16, &mut buffer), Ok(2));
+//! assert_eq!(serialize::from_slice::<u16>(&buffer), Ok(0xAABB));
+//! ```
+//!
+//! ## Length fields
+//!
+//! Dynamic length data elements are preceded by a length field of 0, 8, 16 or 32 bits,
+//! configured per data element with [`LengthField`]:
+//!
+//! ```rust
+//! use someip_wire::serialize::{LengthField, Reader, Writer};
+//!
+//! let mut buffer = [0u8; 8];
+//! let mut writer = Writer::new(&mut buffer);
+//! LengthField::U16.serialize(&mut writer, &[0x0102u16, 0x0304][..]).unwrap();
+//! assert_eq!(writer.written(), &[0x00, 0x04, 0x01, 0x02, 0x03, 0x04]);
+//!
+//! let mut reader = Reader::new(&buffer[..6]);
+//! let data: &[u8] = LengthField::U16.deserialize(&mut reader).unwrap();
+//! assert_eq!(data, &[0x01, 0x02, 0x03, 0x04]);
+//! ```
+//!
+//! ## Strings
+//!
+//! Strings start with a byte order mark and end with a null terminator. UTF-8 strings
+//! are deserialized into a `&str` borrowed from the payload:
+//!
+//! ```rust
+//! use someip_wire::serialize::{
+//!     EncodedStr, Encoding, LengthField, Reader, StringLength, StringRef, Writer,
+//! };
+//!
+//! let mut buffer = [0u8; 16];
+//! let mut writer = Writer::new(&mut buffer);
+//! LengthField::U8.serialize(&mut writer, "hi").unwrap();
+//! let utf16 = EncodedStr::new("hi", Encoding::Utf16Le);
+//! StringLength::Fixed(8).serialize(&mut writer, &utf16).unwrap();
+//! assert_eq!(writer.position(), 15);
+//!
+//! let mut reader = Reader::new(&buffer);
+//! let value: &str = LengthField::U8.deserialize(&mut reader).unwrap();
+//! assert_eq!(value, "hi");
+//!
+//! let mut decoded = [0u8; 8];
+//! let value: StringRef = StringLength::Fixed(8).deserialize(&mut reader).unwrap();
+//! assert_eq!(value.decode_into(&mut decoded), Ok("hi"));
+//! ```
+//!
+//! ## Arrays
+//!
+//! Fixed length arrays `[T; N]` are serialized without a length field, dynamic length
+//! arrays are serialized from slices with a [`LengthField`], and multidimensional arrays
+//! with an [`ArrayFormat`] giving one length field per dimension. Received arrays of
+//! fixed size elements are read in place from `Repr::data` through [`ArrayRef`]:
+//!
+//! ```rust
+//! use someip_wire::serialize::{ArrayFormat, ArrayRef, LengthField, Reader};
+//!
+//! // A radar grid of 2 rows with 2 cells each
+//! let data = [0x00, 0x0A, 0x04, 0x00, 0x01, 0x00, 0x02, 0x04, 0x00, 0x03, 0x00, 0x04];
+//! let format = ArrayFormat::new(&[LengthField::U16, LengthField::U8]);
+//! let grid = format.read(&mut Reader::new(&data)).unwrap();
+//! let mut sum = 0;
+//! for row in grid.rows() {
+//!     let cells: ArrayRef<u16> = row.unwrap().array().unwrap();
+//!     sum += cells.iter().sum::<u16>();
+//! }
+//! assert_eq!(sum, 10);
+//! ```
+//!
+//! ## Alignment
+//!
+//! Dynamic length data elements can be followed by padding that aligns the next data
+//! element relative to the start of the payload, and data elements can be padded to a
+//! fixed size with [`Padding`]:
+//!
+//! ```rust
+//! use someip_wire::serialize::{Alignment, LengthField, Padding, Reader, Writer};
+//!
+//! let mut buffer = [0u8; 16];
+//! let mut writer = Writer::new(&mut buffer);
+//! LengthField::U8.serialize_aligned(&mut writer, &[0xAAu8][..], Alignment::Bits32).unwrap();
+//! Padding::new(4).serialize(&mut writer, &0x1234u16).unwrap();
+//! assert_eq!(writer.written(), &[0x01, 0xAA, 0x00, 0x00, 0x12, 0x34, 0x00, 0x00]);
+//!
+//! let mut reader = Reader::new(&buffer[..8]);
+//! let data: &[u8] = LengthField::U8.deserialize_aligned(&mut reader, Alignment::Bits32).unwrap();
+//! assert_eq!(data, &[0xAA]);
+//! assert_eq!(Padding::new(4).deserialize::<u16>(&mut reader), Ok(0x1234));
+//! ```
+//!
+//! ## Bitfields and scaled values
+//!
+//! Named bits packed into an integer are declared with [`bitfield!`](crate::bitfield).
+//! Physical values transported as scaled integers are converted with [`FixedPoint`]:
+//!
+//! ```rust
+//! use someip_wire::serialize::{FixedPoint, Reader, Writer};
+//!
+//! // Temperature in 0.5 °C steps from -40 °C, valid up to 85 °C
+//! let temperature = FixedPoint::<u8>::new(0.5, -40.0).with_range(-40.0, 85.0);
+//!
+//! let mut buffer = [0u8; 1];
+//! temperature.serialize(&mut Writer::new(&mut buffer), 21.5).unwrap();
+//! assert_eq!(buffer, [123]);
+//! assert_eq!(temperature.deserialize(&mut Reader::new(&buffer)), Ok(21.5));
+//! assert!(temperature.to_raw(90.0).is_err());
+//! ```
+//!
+//! ## Fixed-layout views
+//!
+//! Payloads with a fully fixed layout can be accessed in place, without deserializing
+//! them, through a view declared with [`payload_view!`](crate::payload_view). See the
+//! [`view`] module.
+
+use crate::error::Error;
+
+/// Alignment and padding of data elements.
+pub mod align;
+/// Fixed length, dynamic length and multidimensional arrays.
+pub mod array;
+/// Bitfields of named bits.
+pub mod bitfield;
+/// Scaled physical values.
+pub mod fixed_point;
+/// Length fields of dynamic length data elements.
+pub mod length;
+/// Serialization of the basic data types.
+pub mod primitive;
+/// Cursor for reading serialized payloads.
+pub mod reader;
+/// Strings with byte order mark in UTF-8 and UTF-16.
+pub mod string;
+/// Tag-length-value encoding of struct members.
+pub mod tlv;
+/// Unions with type selector.
+pub mod union;
+/// Zero-copy views of fixed-layout payloads.
+pub mod view;
+/// Cursor for writing serialized payloads.
+pub mod writer;
+
+#[cfg(feature = "derive")]
+pub use someip_wire_derive::{SomeIpDeserialize, SomeIpSerialize};
+
+pub use align::{Alignment, Padding};
+pub use array::{ArrayFormat, ArrayIter, ArrayRef, Dimension, Elements, Rows};
+pub use fixed_point::{FixedPoint, RawValue};
+pub use length::LengthField;
+pub use reader::Reader;
+pub use string::{EncodedStr, Encoding, StringLength, StringRef};
+pub use tlv::{Tag, TlvFormat, TlvMember, WireType};
+pub use union::{SelectorField, SomeIpUnion, UnionFormat};
+pub use view::FixedField;
+pub use writer::Writer;
+
+/// A type that can be serialized into a SOME/IP payload.
+pub trait SomeIpSerialize {
+    /// Serializes the value at the current position of the writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize the value into
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::BufferTooShort` if the value does not fit into the remaining buffer.
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error>;
+}
+
+/// A type that can be deserialized from a SOME/IP payload.
+///
+/// The lifetime allows types to borrow from the payload instead of copying it.
+pub trait SomeIpDeserialize<'a>: Sized {
+    /// Deserializes a value at the current position of the reader.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize the value from
+    ///
+    /// # Returns
+    ///
+    /// Returns the value, `Error::Truncated` if the payload ends early, or another
+    /// `Error` if the data is malformed.
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error>;
+}
+
+impl<T: SomeIpSerialize + ?Sized> SomeIpSerialize for &T {
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        (**self).serialize(writer)
+    }
+}
+
+/// Serializes a value into the start of a buffer.
+///
+/// # Arguments
+///
+/// * `value` - The value to serialize
+/// * `buffer` - The buffer to write into
+///
+/// # Returns
+///
+/// Returns the number of bytes written, or an `Error` if the value does not fit.
+pub fn to_slice<T: SomeIpSerialize + ?Sized>(value: &T, buffer: &mut [u8]) -> Result<usize, Error> {
+    let mut writer = Writer::new(buffer);
+    value.serialize(&mut writer)?;
+    Ok(writer.position())
+}
+
+/// Deserializes a value from the start of a payload.
+///
+/// Trailing bytes are ignored, so that payloads extended by newer interface versions
+/// can still be read.
+///
+/// # Arguments
+///
+/// * `buffer` - The serialized payload
+///
+/// # Returns
+///
+/// Returns the value, or an `Error` if the payload is truncated or malformed.
+pub fn from_slice<'a, T: SomeIpDeserialize<'a>>(buffer: &'a [u8]) -> Result<T, Error> {
+    Reader::new(buffer).read()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::serialize::{
+        self, Alignment, ArrayFormat, ArrayRef, Elements, EncodedStr, Encoding, FixedPoint,
+        LengthField, Padding, Reader, SelectorField, SomeIpUnion, StringLength, StringRef, Tag,
+        TlvFormat, TlvMember, UnionFormat, WireType, Writer,
+    };
+
+    #[test]
+    fn test_primitives_round_trip() {
+        let mut buffer = [0u8; 43];
+        let mut writer = Writer::new(&mut buffer);
+        writer.write(&false).unwrap();
+        writer.write(&0x12u8).unwrap();
+        writer.write(&-2i8).unwrap();
+        writer.write(&0x1234u16).unwrap();
+        writer.write(&-2i16).unwrap();
+        writer.write(&0x1234_5678u32).unwrap();
+        writer.write(&i32::MIN).unwrap();
+        writer.write(&0x0102_0304_0506_0708u64).unwrap();
+        writer.write(&-2i64).unwrap();
+        writer.write(&1.0f32).unwrap();
+        writer.write(&-0.5f64).unwrap();
+        assert_eq!(writer.position(), 43);
+        assert_eq!(writer.remaining(), 0);
+
+        assert_eq!(
+            buffer,
+            [
+                0x00, // bool
+                0x12, // u8
+                0xFE, // i8
+                0x12, 0x34, // u16
+                0xFF, 0xFE, // i16
+                0x12, 0x34, 0x56, 0x78, // u32
+                0x80, 0x00, 0x00, 0x00, // i32
+                0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, // u64
+                0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFE, // i64
+                0x3F, 0x80, 0x00, 0x00, // f32
+                0xBF, 0xE0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // f64
+            ]
+        );
+
+        let mut reader = Reader::new(&buffer);
+        assert_eq!(reader.read::<bool>(), Ok(false));
+        assert_eq!(reader.read::<u8>(), Ok(0x12));
+        assert_eq!(reader.read::<i8>(), Ok(-2));
+        assert_eq!(reader.read::<u16>(), Ok(0x1234));
+        assert_eq!(reader.read::<i16>(), Ok(-2));
+        assert_eq!(reader.read::<u32>(), Ok(0x1234_5678));
+        assert_eq!(reader.read::<i32>(), Ok(i32::MIN));
+        assert_eq!(reader.read::<u64>(), Ok(0x0102_0304_0506_0708));
+        assert_eq!(reader.read::<i64>(), Ok(-2));
+        assert_eq!(reader.read::<f32>(), Ok(1.0));
+        assert_eq!(reader.read::<f64>(), Ok(-0.5));
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut buffer = [0u8; 3];
+        let mut writer = Writer::new(&mut buffer);
+        writer.write(&0xAAu8).unwrap();
+        assert_eq!(writer.write(&0u32), Err(Error::BufferTooShort));
+        // A failed write leaves the cursor in place
+        assert_eq!(writer.position(), 1);
+        writer.write(&0xBBCCu16).unwrap();
+        assert_eq!(writer.written(), &[0xAA, 0xBB, 0xCC]);
+        assert_eq!(writer.write(&true), Err(Error::BufferTooShort));
+
+        let mut reader = Reader::new(&buffer);
+        assert_eq!(reader.read::<u32>(), Err(Error::Truncated));
+        assert_eq!(reader.position(), 0);
+        assert_eq!(reader.read::<u16>(), Ok(0xAABB));
+        assert_eq!(reader.rest(), &[0xCC]);
+        assert_eq!(reader.read::<u16>(), Err(Error::Truncated));
+
+        assert_eq!(
+            serialize::to_slice(&1u64, &mut buffer),
+            Err(Error::BufferTooShort)
+        );
+        assert_eq!(serialize::from_slice::<f32>(&buffer), Err(Error::Truncated));
+    }
+
+    #[test]
+    fn test_bool() {
+        assert_eq!(serialize::from_slice::<bool>(&[0x01]), Ok(true));
+        assert_eq!(serialize::from_slice::<bool>(&[0x00]), Ok(false));
+        assert_eq!(
+            serialize::from_slice::<bool>(&[0x02]),
+            Err(Error::InvalidBoolean(0x02))
+        );
+        assert_eq!(serialize::from_slice::<bool>(&[]), Err(Error::Truncated));
+
+        let mut buffer = [0u8; 1];
+        assert_eq!(serialize::to_slice(&&true, &mut buffer), Ok(1));
+        assert_eq!(buffer, [0x01]);
+    }
+
+    #[test]
+    fn test_length_fields() {
+        let data = [0xA0u8, 0xA1, 0xA2];
+        let mut buffer = [0u8; 16];
+        let mut writer = Writer::new(&mut buffer);
+        LengthField::None.serialize(&mut writer, &data[..]).unwrap();
+        LengthField::U8.serialize(&mut writer, &data[..]).unwrap();
+        LengthField::U16.serialize(&mut writer, &data[..]).unwrap();
+        // A failed write leaves the cursor in place
+        assert_eq!(
+            LengthField::U32.serialize(&mut writer, &data[..]),
+            Err(Error::BufferTooShort)
+        );
+        assert_eq!(
+            writer.written(),
+            &[
+                0xA0, 0xA1, 0xA2, // no length field
+                0x03, 0xA0, 0xA1, 0xA2, // 8-bit length field
+                0x00, 0x03, 0xA0, 0xA1, 0xA2, // 16-bit length field
+            ][..]
+        );
+        writer.truncate(3);
+        LengthField::U32.serialize(&mut writer, &data[..]).unwrap();
+        assert_eq!(
+            writer.written(),
+            &[0xA0, 0xA1, 0xA2, 0x00, 0x00, 0x00, 0x03, 0xA0, 0xA1, 0xA2][..]
+        );
+
+        let buffer = [0x03, 0xA0, 0xA1, 0xA2, 0x00, 0x03, 0xA0, 0xA1, 0xA2];
+        let mut reader = Reader::new(&buffer);
+        for length_field in [LengthField::U8, LengthField::U16] {
+            let element: &[u8] = length_field.deserialize(&mut reader).unwrap();
+            assert_eq!(element, &data);
+        }
+        assert!(reader.is_empty());
+
+        // The length field announces more bytes than available
+        let buffer = [0x00, 0x00, 0x00, 0x04, 0xA0, 0xA1, 0xA2];
+        let mut reader = Reader::new(&buffer);
+        assert_eq!(
+            LengthField::U32.deserialize::<&[u8]>(&mut reader),
+            Err(Error::Truncated)
+        );
+        assert_eq!(reader.position(), 0);
+        let rest: &[u8] = LengthField::None.deserialize(&mut reader).unwrap();
+        assert_eq!(rest, &buffer);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_length_field_struct() {
+        // A struct with an 8-bit length field, extended by a newer interface version
+        let buffer = [0x04, 0x12, 0x34, 0xFF, 0xFF, 0x56];
+        let mut reader = Reader::new(&buffer);
+        let mut element = LengthField::U8.read(&mut reader).unwrap();
+        assert_eq!(element.read::<u16>(), Ok(0x1234));
+        assert_eq!(element.remaining(), 2);
+        // The unknown trailing member is skipped
+        assert_eq!(reader.read::<u8>(), Ok(0x56));
+
+        let mut buffer = [0u8; 300];
+        let mut writer = Writer::new(&mut buffer);
+        assert_eq!(
+            LengthField::U8.write(&mut writer, |writer| writer.write_bytes(&[0; 256])),
+            Err(Error::LengthFieldOverflow(256))
+        );
+        assert_eq!(writer.position(), 0);
+        LengthField::U8
+            .write(&mut writer, |writer| {
+                writer.write(&0x1234u16)?;
+                writer.write(&true)
+            })
+            .unwrap();
+        assert_eq!(writer.written(), &[0x03, 0x12, 0x34, 0x01]);
+
+        assert_eq!(LengthField::default(), LengthField::U32);
+        assert_eq!(LengthField::U16.size(), 2);
+        assert_eq!(LengthField::U16.max_length(), 0xFFFF);
+    }
+
+    #[test]
+    fn test_string_encodings() {
+        let mut buffer = [0u8; 32];
+        let value = "aé€";
+
+        let len = serialize::to_slice(value, &mut buffer).unwrap();
+        assert_eq!(
+            &buffer[..len],
+            &[0xEF, 0xBB, 0xBF, 0x61, 0xC3, 0xA9, 0xE2, 0x82, 0xAC, 0x00]
+        );
+        assert_eq!(serialize::from_slice::<&str>(&buffer[..len]), Ok(value));
+
+        let utf16 = EncodedStr::new(value, Encoding::Utf16Be);
+        assert_eq!(utf16.buffer_len(), 10);
+        let len = serialize::to_slice(&utf16, &mut buffer).unwrap();
+        assert_eq!(
+            &buffer[..len],
+            &[0xFE, 0xFF, 0x00, 0x61, 0x00, 0xE9, 0x20, 0xAC, 0x00, 0x00]
+        );
+        let string = serialize::from_slice::<StringRef>(&buffer[..len]).unwrap();
+        assert_eq!(string.encoding(), Encoding::Utf16Be);
+        assert_eq!(string.as_str(), None);
+        assert!(string.chars().eq(value.chars()));
+        // A UTF-16 string is not accepted as `&str`
+        assert_eq!(
+            serialize::from_slice::<&str>(&buffer[..len]),
+            Err(Error::InvalidString)
+        );
+
+        let utf16 = EncodedStr::new("a😀", Encoding::Utf16Le);
+        let len = serialize::to_slice(&utf16, &mut buffer).unwrap();
+        assert_eq!(
+            &buffer[..len],
+            &[0xFF, 0xFE, 0x61, 0x00, 0x3D, 0xD8, 0x00, 0xDE, 0x00, 0x00]
+        );
+        let string = serialize::from_slice::<StringRef>(&buffer[..len]).unwrap();
+        let mut decoded = [0u8; 5];
+        assert_eq!(string.decode_into(&mut decoded), Ok("a😀"));
+        assert_eq!(
+            string.decode_into(&mut decoded[..4]),
+            Err(Error::BufferTooShort)
+        );
+        assert_eq!(format!("{}", string), "a😀");
+    }
+
+    #[test]
+    fn test_string_errors() {
+        // Missing BOM
+        assert_eq!(StringRef::parse(&[0x61, 0x00]), Err(Error::InvalidString));
+        // Invalid UTF-8
+        assert_eq!(
+            StringRef::parse(&[0xEF, 0xBB, 0xBF, 0xFF, 0x00]),
+            Err(Error::InvalidString)
+        );
+        // Odd UTF-16 length
+        assert_eq!(
+            StringRef::parse(&[0xFE, 0xFF, 0x00, 0x61, 0x00]),
+            Err(Error::InvalidString)
+        );
+        // Unpaired surrogate
+        assert_eq!(
+            StringRef::parse(&[0xFE, 0xFF, 0xD8, 0x3D, 0x00, 0x00]),
+            Err(Error::InvalidString)
+        );
+        // A missing terminator is tolerated
+        assert_eq!(
+            StringRef::parse(&[0xEF, 0xBB, 0xBF, 0x61]),
+            Ok(StringRef::Utf8("a"))
+        );
+        assert_eq!(
+            StringRef::parse(&[0xEF, 0xBB, 0xBF]),
+            Ok(StringRef::Utf8(""))
+        );
+    }
+
+    #[test]
+    fn test_string_lengths() {
+        let mut buffer = [0xFFu8; 16];
+        let mut writer = Writer::new(&mut buffer);
+        let value = EncodedStr::new("ab", Encoding::Utf8);
+        StringLength::Fixed(8)
+            .serialize(&mut writer, &value)
+            .unwrap();
+        assert_eq!(
+            StringLength::Fixed(5).serialize(&mut writer, &value),
+            Err(Error::LengthFieldOverflow(6))
+        );
+        StringLength::Dynamic(LengthField::U16)
+            .serialize(&mut writer, &value)
+            .unwrap();
+        assert_eq!(
+            writer.written(),
+            &[
+                0xEF, 0xBB, 0xBF, 0x61, 0x62, 0x00, 0x00, 0x00, // fixed length, padded
+                0x00, 0x06, 0xEF, 0xBB, 0xBF, 0x61, 0x62, 0x00, // dynamic length
+            ][..]
+        );
+
+        let mut reader = Reader::new(&buffer);
+        assert_eq!(StringLength::Fixed(8).deserialize(&mut reader), Ok("ab"));
+        assert_eq!(reader.position(), 8);
+        assert_eq!(
+            StringLength::default().deserialize::<&str>(&mut reader.clone()),
+            Err(Error::Truncated)
+        );
+        assert_eq!(
+            StringLength::Dynamic(LengthField::U16).deserialize(&mut reader),
+            Ok(StringRef::Utf8("ab"))
+        );
+        assert!(reader.is_empty());
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Variant<'a> {
+        Empty,
+        Flag(bool),
+        Name(&'a str),
+    }
+
+    impl<'a> SomeIpUnion<'a> for Variant<'a> {
+        fn selector(&self) -> u32 {
+            match self {
+                Variant::Empty => 0,
+                Variant::Flag(_) => 1,
+                Variant::Name(_) => 0x100,
+            }
+        }
+
+        fn serialize_member(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+            match self {
+                Variant::Empty => Ok(()),
+                Variant::Flag(value) => writer.write(value),
+                Variant::Name(value) => writer.write(*value),
+            }
+        }
+
+        fn deserialize_member(selector: u32, reader: &mut Reader<'a>) -> Result<Self, Error> {
+            match selector {
+                0 => Ok(Variant::Empty),
+                1 => Ok(Variant::Flag(reader.read()?)),
+                0x100 => Ok(Variant::Name(reader.read()?)),
+                _ => Err(Error::InvalidSelector(selector)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_union() {
+        let format = UnionFormat::new(LengthField::U8, SelectorField::U16);
+        let mut buffer = [0u8; 32];
+        let mut writer = Writer::new(&mut buffer);
+        format.serialize(&mut writer, &Variant::Flag(true)).unwrap();
+        format.serialize(&mut writer, &Variant::Empty).unwrap();
+        format.serialize(&mut writer, &Variant::Name("a")).unwrap();
+        assert_eq!(
+            writer.written(),
+            &[
+                0x01, 0x00, 0x01, 0x01, // Flag
+                0x00, 0x00, 0x00, // Empty
+                0x05, 0x01, 0x00, 0xEF, 0xBB, 0xBF, 0x61, 0x00, // Name
+            ][..]
+        );
+
+        let mut reader = Reader::new(writer.written());
+        assert_eq!(format.deserialize(&mut reader), Ok(Variant::Flag(true)));
+        assert_eq!(format.deserialize(&mut reader), Ok(Variant::Empty));
+        assert_eq!(format.deserialize(&mut reader), Ok(Variant::Name("a")));
+        assert!(reader.is_empty());
+
+        // The selector does not fit an 8-bit selector field
+        let format = UnionFormat::new(LengthField::U8, SelectorField::U8);
+        let mut writer = Writer::new(&mut buffer);
+        assert_eq!(
+            format.serialize(&mut writer, &Variant::Name("a")),
+            Err(Error::InvalidSelector(0x100))
+        );
+        assert_eq!(writer.position(), 0);
+
+        let mut reader = Reader::new(&[0x01, 0x07, 0x00]);
+        assert_eq!(
+            format.deserialize::<Variant>(&mut reader),
+            Err(Error::InvalidSelector(7))
+        );
+        assert_eq!(reader.position(), 0);
+    }
+
+    #[test]
+    fn test_union_padding() {
+        let format = UnionFormat::default().with_padding(4);
+        let mut buffer = [0xFFu8; 32];
+        let mut writer = Writer::new(&mut buffer);
+        format
+            .serialize(&mut writer, &Variant::Flag(false))
+            .unwrap();
+        assert_eq!(
+            writer.written(),
+            &[
+                0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00
+            ][..]
+        );
+        assert_eq!(
+            format.serialize(&mut writer, &Variant::Name("ab")),
+            Err(Error::PaddingOverflow(6))
+        );
+        assert_eq!(writer.position(), 12);
+
+        // Without a length field, the padded size delimits the member
+        let format = UnionFormat::new(LengthField::None, SelectorField::U8).with_padding(2);
+        format.serialize(&mut writer, &Variant::Flag(true)).unwrap();
+        writer.write(&0xABu8).unwrap();
+        assert_eq!(&writer.written()[12..], &[0x01, 0x01, 0x00, 0xAB]);
+
+        let mut reader = Reader::new(&buffer[..16]);
+        assert_eq!(
+            UnionFormat::default()
+                .with_padding(4)
+                .deserialize(&mut reader),
+            Ok(Variant::Flag(false))
+        );
+        assert_eq!(format.deserialize(&mut reader), Ok(Variant::Flag(true)));
+        assert_eq!(reader.read::<u8>(), Ok(0xAB));
+    }
+
+    #[test]
+    fn test_tlv_serialize() {
+        let format = TlvFormat::new(LengthField::U16);
+        let mut buffer = [0u8; 64];
+        let mut writer = Writer::new(&mut buffer);
+        format
+            .serialize(&mut writer, Tag::new(WireType::Base8, 0x001), &true)
+            .unwrap();
+        format
+            .serialize(&mut writer, Tag::new(WireType::Base64, 0xFFF), &-1i64)
+            .unwrap();
+        format
+            .serialize(&mut writer, Tag::new(WireType::Complex, 0x010), "a")
+            .unwrap();
+        format
+            .serialize(
+                &mut writer,
+                Tag::new(WireType::Complex32, 0x011),
+                &[1u16, 2][..],
+            )
+            .unwrap();
+        assert_eq!(
+            writer.written(),
+            &[
+                0x00, 0x01, 0x01, // Base8
+                0x3F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // Base64
+                0x40, 0x10, 0x00, 0x05, 0xEF, 0xBB, 0xBF, 0x61, 0x00, // Complex, 16 bits
+                0x70, 0x11, 0x00, 0x00, 0x00, 0x04, 0x00, 0x01, 0x00, 0x02, // Complex32
+            ][..]
+        );
+
+        // Errors leave the cursor in place
+        let position = writer.position();
+        assert_eq!(
+            format.serialize(&mut writer, Tag::new(WireType::Base8, 0x1000), &0u8),
+            Err(Error::InvalidDataId(0x1000))
+        );
+        assert_eq!(
+            format.serialize(&mut writer, Tag::new(WireType::Base32, 1), &0u16),
+            Err(Error::InvalidWireType(2))
+        );
+        assert_eq!(
+            TlvFormat::new(LengthField::None).serialize(
+                &mut writer,
+                Tag::new(WireType::Complex, 1),
+                "a"
+            ),
+            Err(Error::InvalidWireType(4))
+        );
+        assert_eq!(writer.position(), position);
+
+        assert_eq!(Tag::from_u16(0xC123), Tag::new(WireType::Complex, 0x123));
+        assert_eq!(Tag::new(WireType::Complex16, 0x123).as_u16(), 0x6123);
+        assert_eq!(WireType::from_u8(8), None);
+    }
+
+    #[test]
+    fn test_tlv_skip_unknown() {
+        // Version 2 of a struct: members 1 and 3 are known, 2 and 4 were added later
+        let buffer = [
+            0x20, 0x03, 0x00, 0x00, 0x00, 0x2A, // data ID 3, u32
+            0x50, 0x02, 0x02, 0xAA, 0xBB, // data ID 2, 8-bit length field
+            0x10, 0x01, 0x12, 0x34, // data ID 1, u16
+            0x40, 0x04, 0x00, 0x00, 0x00, 0x01, 0xCC, // data ID 4, configured length field
+        ];
+
+        let format = TlvFormat::default();
+        let (mut first, mut third) = (None, None);
+        for member in format.members(Reader::new(&buffer)) {
+            let member = member.unwrap();
+            match member.data_id() {
+                1 => first = Some(member.deserialize::<u16>().unwrap()),
+                3 => third = Some(member.deserialize::<u32>().unwrap()),
+                _ => {}
+            }
+        }
+        assert_eq!(first, Some(0x1234));
+        assert_eq!(third, Some(42));
+
+        let mut reader = Reader::new(&buffer);
+        reader.take(6).unwrap();
+        let member = format.read_member(&mut reader).unwrap();
+        assert_eq!(
+            member,
+            TlvMember {
+                tag: Tag::new(WireType::Complex8, 2),
+                data: &[0xAA, 0xBB],
+                offset: 9,
+            }
+        );
+        assert_eq!(member.reader().payload_position(), 9);
+
+        // A truncated member ends the iteration with an error
+        let mut members = format.members(Reader::new(&buffer[..9]));
+        assert!(members.next().unwrap().is_ok());
+        assert_eq!(members.next(), Some(Err(Error::Truncated)));
+        assert_eq!(members.next(), None);
+    }
+
+    #[test]
+    fn test_alignment() {
+        assert_eq!(Alignment::from_bits(32), Some(Alignment::Bits32));
+        assert_eq!(Alignment::from_bits(24), None);
+        assert_eq!(Alignment::Bits64.size(), 8);
+
+        let mut buffer = [0xFFu8; 14];
+        let mut writer = Writer::new(&mut buffer);
+        LengthField::U8
+            .serialize_aligned(&mut writer, "a", Alignment::Bits64)
+            .unwrap();
+        writer.write(&0x12u8).unwrap();
+        LengthField::U16
+            .serialize_aligned(&mut writer, &[0x34u8][..], Alignment::Bits16)
+            .unwrap();
+        assert_eq!(
+            writer.written(),
+            &[
+                0x05, 0xEF, 0xBB, 0xBF, 0x61, 0x00, 0x00, 0x00, // string and padding
+                0x12, 0x00, 0x01, 0x34, // already aligned
+            ]
+        );
+
+        // Padding that does not fit leaves the cursor unchanged
+        assert_eq!(
+            LengthField::U8.serialize_aligned(&mut writer, &[0x56u8][..], Alignment::Bits64),
+            Err(Error::BufferTooShort)
+        );
+        assert_eq!(writer.position(), 12);
+
+        let mut reader = Reader::new(&buffer[..12]);
+        let value: &str = LengthField::U8
+            .deserialize_aligned(&mut reader, Alignment::Bits64)
+            .unwrap();
+        assert_eq!(value, "a");
+        assert_eq!(reader.position(), 8);
+        assert_eq!(reader.read::<u8>(), Ok(0x12));
+        let data: &[u8] = LengthField::U16
+            .deserialize_aligned(&mut reader, Alignment::Bits64)
+            .unwrap();
+        assert_eq!(data, &[0x34]);
+        assert!(reader.is_empty());
+
+        // Padding covered by the length field
+        let buffer = [0x04, 0xAA, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x12];
+        let mut reader = Reader::new(&buffer);
+        let value: u8 = LengthField::U8
+            .deserialize_aligned(&mut reader, Alignment::Bits64)
+            .unwrap();
+        assert_eq!(value, 0xAA);
+        assert_eq!(reader.read::<u8>(), Ok(0x12));
+
+        // Alignment is counted from the start of the payload, also within a length field
+        let buffer = [0x00, 0x00, 0x00, 0x06, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02];
+        let mut reader = Reader::new(&buffer);
+        let mut data = LengthField::U32.read(&mut reader).unwrap();
+        assert_eq!(data.read::<u8>(), Ok(1));
+        Alignment::Bits64.skip(&mut data).unwrap();
+        assert_eq!(data.read::<u16>(), Ok(2));
+        let mut reader = Reader::new(&buffer[..6]);
+        reader.take(5).unwrap();
+        assert_eq!(Alignment::Bits64.skip(&mut reader), Err(Error::Truncated));
+    }
+
+    #[test]
+    fn test_padding() {
+        let padding = Padding::new(4);
+        let mut buffer = [0xFFu8; 10];
+        let mut writer = Writer::new(&mut buffer);
+        for value in [0x0102u16, 0x0304] {
+            padding.serialize(&mut writer, &value).unwrap();
+        }
+        assert_eq!(
+            writer.written(),
+            &[0x01, 0x02, 0x00, 0x00, 0x03, 0x04, 0x00, 0x00]
+        );
+        assert_eq!(
+            padding.serialize(&mut writer, &0u8),
+            Err(Error::BufferTooShort)
+        );
+        assert_eq!(
+            Padding::new(2).serialize(&mut Writer::new(&mut [0u8; 8]), &0u32),
+            Err(Error::PaddingOverflow(4))
+        );
+        assert_eq!(writer.position(), 8);
+
+        let mut reader = Reader::new(&buffer[..8]);
+        assert_eq!(padding.deserialize::<u16>(&mut reader), Ok(0x0102));
+        assert_eq!(padding.deserialize::<u16>(&mut reader), Ok(0x0304));
+        assert_eq!(
+            padding.deserialize::<u16>(&mut reader),
+            Err(Error::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_fixed_arrays() {
+        let mut buffer = [0u8; 8];
+        let value = [[0x0102u16, 0x0304], [0x0506, 0x0708]];
+        assert_eq!(serialize::to_slice(&value, &mut buffer), Ok(8));
+        assert_eq!(buffer, [1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(serialize::from_slice(&buffer), Ok(value));
+
+        let mut reader = Reader::new(&buffer[..7]);
+        assert_eq!(reader.read::<[u32; 2]>(), Err(Error::Truncated));
+        assert_eq!(reader.position(), 0);
+        assert_eq!(reader.read::<[u8; 0]>(), Ok([]));
+        assert_eq!(
+            serialize::from_slice::<[bool; 2]>(&[1, 2]),
+            Err(Error::InvalidBoolean(2))
+        );
+    }
+
+    #[test]
+    fn test_dynamic_arrays() {
+        let mut buffer = [0u8; 16];
+        let mut writer = Writer::new(&mut buffer);
+        LengthField::U8
+            .serialize(&mut writer, &[-1i16, 2, 3][..])
+            .unwrap();
+        assert_eq!(writer.written(), &[6, 0xFF, 0xFF, 0, 2, 0, 3]);
+
+        let mut reader = Reader::new(&buffer[..7]);
+        let array: ArrayRef<i16> = LengthField::U8.deserialize(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(array.len(), 3);
+        assert_eq!(array.get(0), Some(-1));
+        assert_eq!(array.get(3), None);
+        assert!(array.iter().rev().eq([3, 2, -1]));
+        assert_eq!(array.iter().nth(1), Some(2));
+        assert_eq!(array.iter().len(), 3);
+        assert_eq!(array.as_bytes(), &buffer[1..7]);
+
+        // Serializing the borrowed array copies the elements unchanged
+        let mut copy = [0u8; 6];
+        assert_eq!(serialize::to_slice(&array, &mut copy), Ok(6));
+        assert_eq!(&copy, array.as_bytes());
+
+        // An incomplete last element
+        let mut reader = Reader::new(&[0x03, 0x00, 0x01, 0x00]);
+        assert_eq!(
+            LengthField::U8.deserialize::<ArrayRef<u16>>(&mut reader),
+            Err(Error::Truncated)
+        );
+
+        // Elements are deserialized one by one, stopping at the first error
+        let mut reader = Reader::new(&[0x03, 0x01, 0x00, 0x02, 0x01]);
+        let mut elements: Elements<bool> = LengthField::U8.deserialize(&mut reader).unwrap();
+        assert_eq!(reader.remaining(), 1);
+        assert_eq!(elements.next(), Some(Ok(true)));
+        assert_eq!(elements.next(), Some(Ok(false)));
+        assert_eq!(elements.next(), Some(Err(Error::InvalidBoolean(2))));
+        assert_eq!(elements.next(), None);
+    }
+
+    #[test]
+    fn test_multidimensional_arrays() {
+        // 2 planes of 2 rows with 2 elements, with different length fields per dimension
+        let format = ArrayFormat::new(&[LengthField::U32, LengthField::U8, LengthField::U16]);
+        assert_eq!(format.dimensions(), 3);
+        let elements = [1u8, 2, 3, 4, 5, 6, 7, 8];
+
+        let mut buffer = [0xFFu8; 32];
+        let mut writer = Writer::new(&mut buffer);
+        format
+            .serialize(&mut writer, &[2, 2, 2], &elements)
+            .unwrap();
+        assert_eq!(
+            writer.written(),
+            &[
+                0x00, 0x00, 0x00, 0x12, // planes
+                0x08, 0x00, 0x02, 0x01, 0x02, 0x00, 0x02, 0x03, 0x04, // first plane
+                0x08, 0x00, 0x02, 0x05, 0x06, 0x00, 0x02, 0x07, 0x08, // second plane
+            ]
+        );
+        let len = writer.position();
+
+        let mut reader = Reader::new(&buffer[..len]);
+        let planes = format.read(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        assert!(!planes.is_innermost());
+        assert_eq!(planes.array::<u8>(), Err(Error::InvalidArrayShape));
+        let mut values = elements.iter().copied();
+        let mut rows = 0;
+        for plane in planes.rows() {
+            for row in plane.unwrap().rows() {
+                let row = row.unwrap();
+                assert!(row.is_innermost());
+                assert!(row.rows().next().is_none());
+                assert!(
+                    row.array::<u8>()
+                        .unwrap()
+                        .iter()
+                        .eq(values.by_ref().take(2))
+                );
+                rows += 1;
+            }
+        }
+        assert_eq!(rows, 4);
+        assert_eq!(values.next(), None);
+
+        // Empty dimensions
+        let mut writer = Writer::new(&mut buffer);
+        format
+            .serialize::<u8>(&mut writer, &[2, 0, 5], &[])
+            .unwrap();
+        assert_eq!(writer.written(), &[0, 0, 0, 2, 0, 0]);
+
+        // The shape must match the dimensions and the elements
+        let mut writer = Writer::new(&mut buffer);
+        assert_eq!(
+            format.serialize(&mut writer, &[2, 4], &elements),
+            Err(Error::InvalidArrayShape)
+        );
+        assert_eq!(
+            format.serialize(&mut writer, &[2, 2, 3], &elements),
+            Err(Error::InvalidArrayShape)
+        );
+        let narrow = ArrayFormat::new(&[LengthField::U8, LengthField::U8]);
+        let wide = [0u16; 200];
+        let mut large = [0u8; 512];
+        let mut writer = Writer::new(&mut large);
+        assert_eq!(
+            narrow.serialize(&mut writer, &[1, 200], &wide),
+            Err(Error::LengthFieldOverflow(400))
+        );
+        assert_eq!(writer.position(), 0);
+        assert_eq!(
+            ArrayFormat::new(&[])
+                .read(&mut Reader::new(&buffer))
+                .map(|_| ()),
+            Err(Error::InvalidArrayShape)
+        );
+
+        // Only the outermost dimension can go without a length field
+        let unbounded = ArrayFormat::new(&[LengthField::None, LengthField::U8]);
+        let mut writer = Writer::new(&mut large);
+        unbounded
+            .serialize(&mut writer, &[2, 1], &[1u8, 2])
+            .unwrap();
+        assert_eq!(writer.written(), &[0x01, 0x01, 0x01, 0x02]);
+        let rows = ArrayFormat::new(&[LengthField::U8, LengthField::None]);
+        let mut writer = Writer::new(&mut large);
+        assert_eq!(
+            rows.serialize(&mut writer, &[2, 1], &[1u8, 2]),
+            Err(Error::InvalidArrayShape)
+        );
+        assert_eq!(
+            rows.read(&mut Reader::new(&[0x02, 0x01, 0x02])).map(|_| ()),
+            Err(Error::InvalidArrayShape)
+        );
+
+        // A row exceeding its plane ends the iteration with an error
+        let buffer = [0x00, 0x00, 0x00, 0x03, 0x04, 0x00, 0x01];
+        let planes = format.read(&mut Reader::new(&buffer)).unwrap();
+        let mut rows = planes.rows();
+        assert_eq!(
+            rows.next().map(|row| row.map(|_| ())),
+            Some(Err(Error::Truncated))
+        );
+        assert!(rows.next().is_none());
+
+        // Elements of variable size in the innermost dimension
+        let grid = ArrayFormat::new(&[LengthField::U8, LengthField::U8]);
+        let buffer = [0x04, 0x02, 0x01, 0x00, 0x00];
+        let rows = grid.read(&mut Reader::new(&buffer)).unwrap();
+        let mut row = rows
+            .rows()
+            .next()
+            .unwrap()
+            .unwrap()
+            .elements::<bool>()
+            .unwrap();
+        assert_eq!(row.next(), Some(Ok(true)));
+        assert_eq!(row.next(), Some(Ok(false)));
+        assert_eq!(row.next(), None);
+        assert!(rows.elements::<bool>().is_err());
+    }
+
+    crate::bitfield! {
+        /// Test lamp states
+        struct Lamps: u16 {
+            /// Low beam
+            const LOW_BEAM = 0x0001;
+            /// High beam
+            const HIGH_BEAM = 0x0002;
+            /// Hazard lights
+            const HAZARD = 0x8000;
+        }
+    }
+
+    #[test]
+    fn test_bitfield() {
+        assert_eq!(Lamps::ALL.bits(), 0x8003);
+        assert!(Lamps::empty().is_empty());
+        assert_eq!(Lamps::default(), Lamps::empty());
+
+        let mut lamps = Lamps::LOW_BEAM | Lamps::HAZARD;
+        assert!(lamps.contains(Lamps::HAZARD));
+        assert!(!lamps.contains(Lamps::LOW_BEAM | Lamps::HIGH_BEAM));
+        lamps.set(Lamps::HIGH_BEAM, true);
+        lamps.remove(Lamps::LOW_BEAM);
+        assert_eq!(lamps, Lamps::HIGH_BEAM | Lamps::HAZARD);
+        assert_eq!(lamps & Lamps::HAZARD, Lamps::HAZARD);
+
+        let mut buffer = [0u8; 2];
+        assert_eq!(serialize::to_slice(&lamps, &mut buffer), Ok(2));
+        assert_eq!(buffer, [0x80, 0x02]);
+        assert_eq!(serialize::from_slice(&buffer), Ok(lamps));
+
+        // Unknown bits are kept when deserializing, but rejected by `from_bits`
+        let lamps: Lamps = serialize::from_slice(&[0x40, 0x01]).unwrap();
+        assert_eq!(lamps.bits(), 0x4001);
+        assert!(lamps.contains(Lamps::LOW_BEAM));
+        assert_eq!(Lamps::from_bits(0x4001), None);
+        assert_eq!(
+            Lamps::from_bits(0x0003),
+            Some(Lamps::LOW_BEAM | Lamps::HIGH_BEAM)
+        );
+        assert_eq!(
+            serialize::from_slice::<Lamps>(&[0x00]),
+            Err(Error::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        // Speed in 0.01 km/h, valid up to 300 km/h
+        let speed = FixedPoint::<u16>::new(0.01, 0.0).with_range(0.0, 300.0);
+        assert_eq!(speed.to_raw(123.456), Ok(12346));
+        assert_eq!(speed.to_raw(0.004), Ok(0));
+        assert_eq!(speed.to_raw(300.5), Err(Error::ValueOutOfRange));
+        assert_eq!(speed.to_raw(-0.01), Err(Error::ValueOutOfRange));
+        assert_eq!(speed.to_raw(f64::NAN), Err(Error::ValueOutOfRange));
+        assert_eq!(speed.to_physical(30000), Ok(300.0));
+        assert_eq!(speed.to_physical(30001), Err(Error::ValueOutOfRange));
+
+        // Without range limits, the raw value must still fit its type
+        let unlimited = FixedPoint::<u16>::new(0.01, 0.0);
+        assert_eq!(unlimited.to_raw(655.35), Ok(u16::MAX));
+        assert_eq!(unlimited.to_raw(655.36), Err(Error::ValueOutOfRange));
+        assert_eq!(unlimited.to_raw(f64::INFINITY), Err(Error::ValueOutOfRange));
+
+        // Signed raw values with an offset round half away from zero
+        let torque = FixedPoint::<i16>::new(0.5, 100.0);
+        assert_eq!(torque.to_raw(99.75), Ok(-1));
+        assert_eq!(torque.to_raw(100.25), Ok(1));
+        assert_eq!(torque.to_physical(-200), Ok(0.0));
+
+        let mut buffer = [0u8; 4];
+        let mut writer = Writer::new(&mut buffer);
+        speed.serialize(&mut writer, 50.0).unwrap();
+        torque.serialize(&mut writer, 0.0).unwrap();
+        assert_eq!(
+            speed.serialize(&mut writer, 400.0),
+            Err(Error::ValueOutOfRange)
+        );
+        assert_eq!(writer.position(), 4);
+        assert_eq!(buffer, [0x13, 0x88, 0xFF, 0x38]);
+
+        let mut reader = Reader::new(&buffer);
+        assert_eq!(speed.deserialize(&mut reader), Ok(50.0));
+        assert_eq!(torque.deserialize(&mut reader), Ok(0.0));
+
+        // An out of range value leaves the cursor unchanged
+        let mut reader = Reader::new(&[0xFF, 0xFF]);
+        assert_eq!(speed.deserialize(&mut reader), Err(Error::ValueOutOfRange));
+        assert_eq!(reader.position(), 0);
+    }
+
+    crate::payload_view! {
+        /// View of a test status payload
+        struct StatusView {
+            /// Mode
+            mode, set_mode: u8 = 0,
+            /// Odometer
+            odometer, set_odometer: i32 = 4,
+            /// Flags
+            flags, set_flags: [bool; 2] = 8,
+            /// Temperature
+            temperature, set_temperature: f32 = 12,
+        }
+    }
+
+    #[test]
+    fn test_payload_view() {
+        assert_eq!(StatusView::<&[u8]>::LENGTH, 16);
+
+        let mut buffer = [0u8; 18];
+        let mut view = StatusView::new_checked(&mut buffer[..]).unwrap();
+        view.set_mode(3);
+        view.set_odometer(-2);
+        view.set_flags([false, true]);
+        view.set_temperature(1.0);
+        assert_eq!(
+            buffer,
+            [
+                0x03, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFE, 0x00, 0x01, 0x00, 0x00, 0x3F, 0x80,
+                0x00, 0x00, 0x00, 0x00,
+            ]
+        );
+
+        let view = StatusView::new_checked(&buffer[..]).unwrap();
+        assert_eq!(view.mode(), 3);
+        assert_eq!(view.odometer(), -2);
+        assert_eq!(view.flags(), [false, true]);
+        assert_eq!(view.temperature(), 1.0);
+        assert_eq!(view.as_slice().len(), 18);
+
+        // The view agrees with the serialization of the same fields
+        let mut reader = Reader::new(&buffer[4..8]);
+        assert_eq!(reader.read::<i32>(), Ok(view.odometer()));
+
+        assert_eq!(
+            StatusView::new_checked(&buffer[..15]),
+            Err(Error::BufferTooShort)
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Primitive types module
+//!
+//! This module implements the serialization of the SOME/IP basic data types: booleans,
+//! signed and unsigned integers of 8 to 64 bits and IEEE 754 floating point numbers,
+//! all in big-endian byte order.
+
+use crate::error::Error;
+use crate::serialize::{Reader, SomeIpDeserialize, SomeIpSerialize, Writer};
+use byteorder::{ByteOrder, NetworkEndian};
+
+impl SomeIpSerialize for bool {
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        writer.write_bytes(&[*self as u8])
+    }
+}
+
+impl<'a> SomeIpDeserialize<'a> for bool {
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+        match reader.take(1)?[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            byte => Err(Error::InvalidBoolean(byte)),
+        }
+    }
+}
+
+impl SomeIpSerialize for u8 {
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        writer.write_bytes(&[*self])
+    }
+}
+
+impl<'a> SomeIpDeserialize<'a> for u8 {
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+        Ok(reader.take(1)?[0])
+    }
+}
+
+impl SomeIpSerialize for i8 {
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        writer.write_bytes(&[*self as u8])
+    }
+}
+
+impl<'a> SomeIpDeserialize<'a> for i8 {
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+        Ok(reader.take(1)?[0] as i8)
+    }
+}
+
+macro_rules! impl_primitive {
+    ($ty:ty, $len:expr, $read:ident, $write:ident) => {
+        impl SomeIpSerialize for $ty {
+            fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+                NetworkEndian::$write(writer.advance($len)?, *self);
+                Ok(())
+            }
+        }
+
+        impl<'a> SomeIpDeserialize<'a> for $ty {
+            fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+                Ok(NetworkEndian::$read(reader.take($len)?))
+            }
+        }
+    };
+}
+
+impl_primitive!(u16, 2, read_u16, write_u16);
+impl_primitive!(u32, 4, read_u32, write_u32);
+impl_primitive!(u64, 8, read_u64, write_u64);
+impl_primitive!(i16, 2, read_i16, write_i16);
+impl_primitive!(i32, 4, read_i32, write_i32);
+impl_primitive!(i64, 8, read_i64, write_i64);
+impl_primitive!(f32, 4, read_f32, write_f32);
+impl_primitive!(f64, 8, read_f64, write_f64);
@@ -0,0 +1,129 @@
+//! Deserialization reader module
+//!
+//! This module contains the `Reader`, a bounds-checked cursor over a serialized payload.
+
+use crate::error::Error;
+use crate::serialize::SomeIpDeserialize;
+
+/// A bounds-checked cursor reading a serialized payload.
+///
+/// Data borrowed from the reader lives as long as the underlying buffer, which allows
+/// zero-copy deserialization of byte slices and strings.
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    buffer: &'a [u8],
+    position: usize,
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Creates a new reader at the start of the buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The serialized payload, e.g. `Repr::data`
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Reader {
+            buffer,
+            position: 0,
+            offset: 0,
+        }
+    }
+
+    /// Creates a new reader over data found at `offset` within the payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The data
+    /// * `offset` - Position of the data within the payload
+    pub(crate) fn with_offset(buffer: &'a [u8], offset: usize) -> Self {
+        Reader {
+            buffer,
+            position: 0,
+            offset,
+        }
+    }
+
+    /// Returns the number of bytes read so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the position within the payload, including the offset of a limited reader
+    pub fn payload_position(&self) -> usize {
+        self.offset + self.position
+    }
+
+    /// Returns the number of bytes left to read
+    pub fn remaining(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    /// Returns true if all bytes have been read
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Returns the bytes left to read without consuming them
+    pub fn rest(&self) -> &'a [u8] {
+        &self.buffer[self.position..]
+    }
+
+    /// Consumes raw bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The number of bytes to consume
+    ///
+    /// # Returns
+    ///
+    /// Returns the consumed bytes, or `Error::Truncated` if fewer than `len` bytes are
+    /// left. The cursor does not move on error.
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if len > self.remaining() {
+            return Err(Error::Truncated);
+        }
+        let start = self.position;
+        self.position += len;
+        Ok(&self.buffer[start..self.position])
+    }
+
+    /// Consumes bytes into a limited reader.
+    ///
+    /// The limited reader keeps track of its position within the payload, so that
+    /// alignment stays relative to the payload start.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The number of bytes to consume
+    ///
+    /// # Returns
+    ///
+    /// Returns a reader over the consumed bytes, or `Error::Truncated` if fewer than
+    /// `len` bytes are left. The cursor does not move on error.
+    pub fn limit(&mut self, len: usize) -> Result<Reader<'a>, Error> {
+        let offset = self.payload_position();
+        Ok(Reader::with_offset(self.take(len)?, offset))
+    }
+
+    /// Skips the padding up to the next multiple of `alignment` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `alignment` - The alignment in bytes, counted from the start of the payload
+    pub fn align(&mut self, alignment: usize) -> Result<(), Error> {
+        let position = self.payload_position();
+        self.take(position.next_multiple_of(alignment.max(1)) - position)?;
+        Ok(())
+    }
+
+    /// Deserializes a value.
+    ///
+    /// # Returns
+    ///
+    /// Returns the value read at the current position, or an `Error` if the data is
+    /// truncated or malformed.
+    pub fn read<T: SomeIpDeserialize<'a>>(&mut self) -> Result<T, Error> {
+        T::deserialize(self)
+    }
+}
@@ -0,0 +1,363 @@
+//! String module
+//!
+//! This module implements the serialization of SOME/IP strings. A string starts with a
+//! byte order mark (BOM) identifying its encoding, UTF-8 or UTF-16 in either byte
+//! order, and ends with a null terminator of one code unit.
+
+use crate::error::Error;
+use crate::serialize::{LengthField, Reader, SomeIpDeserialize, SomeIpSerialize, Writer};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use core::char::DecodeUtf16;
+use core::fmt;
+use core::iter::Map;
+use core::slice::ChunksExact;
+
+/// Encoding of a SOME/IP string.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub enum Encoding {
+    /// UTF-8, BOM `EF BB BF`
+    #[default]
+    Utf8,
+    /// UTF-16 big-endian, BOM `FE FF`
+    Utf16Be,
+    /// UTF-16 little-endian, BOM `FF FE`
+    Utf16Le,
+}
+
+impl Encoding {
+    /// Returns the byte order mark of the encoding
+    pub const fn bom(&self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => &[0xEF, 0xBB, 0xBF],
+            Encoding::Utf16Be => &[0xFE, 0xFF],
+            Encoding::Utf16Le => &[0xFF, 0xFE],
+        }
+    }
+
+    /// Returns the size of a code unit, and thus of the null terminator, in bytes
+    pub const fn unit_size(&self) -> usize {
+        match self {
+            Encoding::Utf8 => 1,
+            Encoding::Utf16Be | Encoding::Utf16Le => 2,
+        }
+    }
+
+    /// Detects the encoding from the byte order mark at the start of the data
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The serialized string
+    ///
+    /// # Returns
+    ///
+    /// Returns the encoding, or None if the data does not start with a known BOM.
+    pub fn from_bom(data: &[u8]) -> Option<Self> {
+        [Encoding::Utf8, Encoding::Utf16Be, Encoding::Utf16Le]
+            .into_iter()
+            .find(|encoding| data.starts_with(encoding.bom()))
+    }
+}
+
+/// A string to be serialized in a given encoding.
+///
+/// Plain `str` values are serialized as UTF-8; this wrapper selects UTF-16 instead.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct EncodedStr<'s> {
+    /// The string value
+    pub value: &'s str,
+    /// The encoding used on the wire
+    pub encoding: Encoding,
+}
+
+impl<'s> EncodedStr<'s> {
+    /// Creates a new encoded string.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The string value
+    /// * `encoding` - The encoding used on the wire
+    pub const fn new(value: &'s str, encoding: Encoding) -> Self {
+        EncodedStr { value, encoding }
+    }
+
+    /// Returns the serialized length including the BOM and the null terminator.
+    pub fn buffer_len(&self) -> usize {
+        let body = match self.encoding {
+            Encoding::Utf8 => self.value.len(),
+            Encoding::Utf16Be | Encoding::Utf16Le => self.value.encode_utf16().count() * 2,
+        };
+        self.encoding.bom().len() + body + self.encoding.unit_size()
+    }
+}
+
+impl SomeIpSerialize for EncodedStr<'_> {
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        let buffer = writer.advance(self.buffer_len())?;
+        let (bom, rest) = buffer.split_at_mut(self.encoding.bom().len());
+        bom.copy_from_slice(self.encoding.bom());
+        match self.encoding {
+            Encoding::Utf8 => rest[..self.value.len()].copy_from_slice(self.value.as_bytes()),
+            Encoding::Utf16Be => {
+                for (unit, bytes) in self.value.encode_utf16().zip(rest.chunks_exact_mut(2)) {
+                    BigEndian::write_u16(bytes, unit);
+                }
+            }
+            Encoding::Utf16Le => {
+                for (unit, bytes) in self.value.encode_utf16().zip(rest.chunks_exact_mut(2)) {
+                    LittleEndian::write_u16(bytes, unit);
+                }
+            }
+        }
+        let terminator = rest.len() - self.encoding.unit_size();
+        rest[terminator..].fill(0);
+        Ok(())
+    }
+}
+
+impl SomeIpSerialize for str {
+    /// Serializes the string as UTF-8, including the BOM and the null terminator.
+    fn serialize(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+        EncodedStr::new(self, Encoding::Utf8).serialize(writer)
+    }
+}
+
+/// A deserialized string borrowed from the payload.
+///
+/// UTF-8 strings are available as `&str` without copying. UTF-16 strings are kept as
+/// raw bytes and decoded on the fly by [`StringRef::chars`] or [`StringRef::decode_into`].
+/// The BOM and the null terminator are not part of the data.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StringRef<'a> {
+    /// A UTF-8 string
+    Utf8(&'a str),
+    /// A validated UTF-16 big-endian string
+    Utf16Be(&'a [u8]),
+    /// A validated UTF-16 little-endian string
+    Utf16Le(&'a [u8]),
+}
+
+impl<'a> StringRef<'a> {
+    /// Parse a serialized string.
+    ///
+    /// The string ends at the first null terminator, anything following it is padding.
+    /// A missing terminator is tolerated.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The serialized string, starting with the BOM
+    ///
+    /// # Returns
+    ///
+    /// Returns the string, or `Error::InvalidString` if the BOM is missing or the data
+    /// is not valid in its encoding.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        let encoding = Encoding::from_bom(data).ok_or(Error::InvalidString)?;
+        let data = &data[encoding.bom().len()..];
+        match encoding {
+            Encoding::Utf8 => {
+                let end = data
+                    .iter()
+                    .position(|&byte| byte == 0)
+                    .unwrap_or(data.len());
+                core::str::from_utf8(&data[..end])
+                    .map(StringRef::Utf8)
+                    .map_err(|_| Error::InvalidString)
+            }
+            Encoding::Utf16Be | Encoding::Utf16Le => {
+                if !data.len().is_multiple_of(2) {
+                    return Err(Error::InvalidString);
+                }
+                let end = data
+                    .chunks_exact(2)
+                    .position(|unit| unit == [0, 0])
+                    .map_or(data.len(), |index| index * 2);
+                let string = if encoding == Encoding::Utf16Be {
+                    StringRef::Utf16Be(&data[..end])
+                } else {
+                    StringRef::Utf16Le(&data[..end])
+                };
+                if string.units().any(|c| c.is_err()) {
+                    return Err(Error::InvalidString);
+                }
+                Ok(string)
+            }
+        }
+    }
+
+    /// Returns the encoding of the string
+    pub fn encoding(&self) -> Encoding {
+        match self {
+            StringRef::Utf8(_) => Encoding::Utf8,
+            StringRef::Utf16Be(_) => Encoding::Utf16Be,
+            StringRef::Utf16Le(_) => Encoding::Utf16Le,
+        }
+    }
+
+    /// Returns the string if it is UTF-8 encoded
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self {
+            StringRef::Utf8(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns an iterator over the characters of the string
+    pub fn chars(&self) -> Chars<'a> {
+        match self {
+            StringRef::Utf8(value) => Chars(CharsInner::Utf8(value.chars())),
+            _ => Chars(CharsInner::Utf16(self.units())),
+        }
+    }
+
+    /// Decodes the string into a buffer as UTF-8.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer to decode into
+    ///
+    /// # Returns
+    ///
+    /// Returns the decoded string, or `Error::BufferTooShort` if it does not fit.
+    pub fn decode_into<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b str, Error> {
+        let mut len = 0;
+        for c in self.chars() {
+            let end = len + c.len_utf8();
+            if end > buffer.len() {
+                return Err(Error::BufferTooShort);
+            }
+            c.encode_utf8(&mut buffer[len..end]);
+            len = end;
+        }
+        core::str::from_utf8(&buffer[..len]).map_err(|_| Error::InvalidString)
+    }
+
+    /// Decodes the UTF-16 code units of the string.
+    fn units(&self) -> Utf16Units<'a> {
+        let (data, read): (&[u8], ReadUnit) = match self {
+            StringRef::Utf8(_) => (&[], BigEndian::read_u16),
+            StringRef::Utf16Be(data) => (data, BigEndian::read_u16),
+            StringRef::Utf16Le(data) => (data, LittleEndian::read_u16),
+        };
+        char::decode_utf16(data.chunks_exact(2).map(read))
+    }
+}
+
+impl<'a> SomeIpDeserialize<'a> for StringRef<'a> {
+    /// Parses all remaining bytes as a string, typically the data element behind a
+    /// length field.
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+        let string = StringRef::parse(reader.rest())?;
+        reader.take(reader.remaining())?;
+        Ok(string)
+    }
+}
+
+impl<'a> SomeIpDeserialize<'a> for &'a str {
+    /// Parses all remaining bytes as a string that must be UTF-8 encoded.
+    fn deserialize(reader: &mut Reader<'a>) -> Result<Self, Error> {
+        let string = StringRef::parse(reader.rest())?;
+        let value = string.as_str().ok_or(Error::InvalidString)?;
+        reader.take(reader.remaining())?;
+        Ok(value)
+    }
+}
+
+impl fmt::Display for StringRef<'_> {
+    /// Formats the string.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.chars().try_for_each(|c| fmt::Write::write_char(f, c))
+    }
+}
+
+type ReadUnit = fn(&[u8]) -> u16;
+type Utf16Units<'a> = DecodeUtf16<Map<ChunksExact<'a, u8>, ReadUnit>>;
+
+/// An iterator over the characters of a [`StringRef`].
+#[derive(Debug, Clone)]
+pub struct Chars<'a>(CharsInner<'a>);
+
+#[derive(Debug, Clone)]
+enum CharsInner<'a> {
+    Utf8(core::str::Chars<'a>),
+    Utf16(Utf16Units<'a>),
+}
+
+impl Iterator for Chars<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        match &mut self.0 {
+            CharsInner::Utf8(chars) => chars.next(),
+            CharsInner::Utf16(units) => units
+                .next()
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)),
+        }
+    }
+}
+
+/// Length of a serialized string.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StringLength {
+    /// Fixed length string of the given size in bytes, including the BOM and the null
+    /// terminator; shorter strings are padded with zeros
+    Fixed(usize),
+    /// Dynamic length string preceded by a length field
+    Dynamic(LengthField),
+}
+
+impl Default for StringLength {
+    fn default() -> Self {
+        StringLength::Dynamic(LengthField::default())
+    }
+}
+
+impl StringLength {
+    /// Serializes a string.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `value` - The string to serialize
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::LengthFieldOverflow` if the string is longer than the fixed
+    /// length or its length field allows.
+    pub fn serialize(&self, writer: &mut Writer<'_>, value: &EncodedStr<'_>) -> Result<(), Error> {
+        match self {
+            StringLength::Fixed(len) => {
+                if value.buffer_len() > *len {
+                    return Err(Error::LengthFieldOverflow(value.buffer_len() as u32));
+                }
+                let buffer = writer.advance(*len)?;
+                let mut field = Writer::new(buffer);
+                field.write(value)?;
+                field.advance(field.remaining())?.fill(0);
+                Ok(())
+            }
+            StringLength::Dynamic(length_field) => length_field.serialize(writer, value),
+        }
+    }
+
+    /// Deserializes a string.
+    ///
+    /// The string type is either [`StringRef`], or `&str` if only UTF-8 is accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize from
+    pub fn deserialize<'a, T>(&self, reader: &mut Reader<'a>) -> Result<T, Error>
+    where
+        T: SomeIpDeserialize<'a>,
+    {
+        match self {
+            StringLength::Fixed(len) => {
+                let mut peek = reader.clone();
+                let value = peek.limit(*len)?.read()?;
+                *reader = peek;
+                Ok(value)
+            }
+            StringLength::Dynamic(length_field) => length_field.deserialize(reader),
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! TLV module
+//!
+//! This module implements the tag-length-value (TLV) encoding of SOME/IP struct
+//! members. Every member is preceded by a 16-bit tag holding its wire type and data ID:
+//!
+//! ```text
+//!  15 | 14 13 12  | 11 ... 0
+//! ----+-----------+---------
+//!  R  | Wire type | Data ID
+//! ```
+//!
+//! The wire type tells a receiver the size of the member, so that members with unknown
+//! data IDs, added by a newer interface version, can be skipped. Members may also be
+//! omitted or reordered.
+
+use crate::error::Error;
+use crate::serialize::{LengthField, Reader, SomeIpDeserialize, SomeIpSerialize, Writer};
+
+/// Mask of the wire type within the tag
+pub const WIRE_TYPE_MASK: u16 = 0x7000;
+/// Mask of the data ID within the tag
+pub const DATA_ID_MASK: u16 = 0x0FFF;
+
+/// Wire type of a TLV member.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum WireType {
+    /// 8-bit base data type
+    Base8 = 0,
+    /// 16-bit base data type
+    Base16 = 1,
+    /// 32-bit base data type
+    Base32 = 2,
+    /// 64-bit base data type
+    Base64 = 3,
+    /// Complex data type preceded by the length field configured for the member
+    Complex = 4,
+    /// Complex data type preceded by an 8-bit length field
+    Complex8 = 5,
+    /// Complex data type preceded by a 16-bit length field
+    Complex16 = 6,
+    /// Complex data type preceded by a 32-bit length field
+    Complex32 = 7,
+}
+
+impl WireType {
+    /// Converts the three wire type bits into a `WireType`
+    pub const fn from_u8(value: u8) -> Option<Self> {
+        if value > 7 {
+            return None;
+        }
+        Some(Self::from_bits(value))
+    }
+
+    /// Converts the lowest three bits into a `WireType`
+    const fn from_bits(value: u8) -> Self {
+        match value & 0x07 {
+            0 => WireType::Base8,
+            1 => WireType::Base16,
+            2 => WireType::Base32,
+            3 => WireType::Base64,
+            4 => WireType::Complex,
+            5 => WireType::Complex8,
+            6 => WireType::Complex16,
+            _ => WireType::Complex32,
+        }
+    }
+
+    /// Returns the wire type as its three-bit value
+    pub const fn as_u8(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Returns the size of a base data type, or None for complex data types
+    pub const fn base_size(&self) -> Option<usize> {
+        match self {
+            WireType::Base8 => Some(1),
+            WireType::Base16 => Some(2),
+            WireType::Base32 => Some(4),
+            WireType::Base64 => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Returns the length field preceding a complex data type.
+    ///
+    /// # Arguments
+    ///
+    /// * `configured` - The length field configured for wire type 4
+    ///
+    /// # Returns
+    ///
+    /// Returns the length field, or `Error::InvalidWireType` for base data types and
+    /// for wire type 4 without a configured length field.
+    fn length_field(&self, configured: LengthField) -> Result<LengthField, Error> {
+        match self {
+            WireType::Complex if configured != LengthField::None => Ok(configured),
+            WireType::Complex8 => Ok(LengthField::U8),
+            WireType::Complex16 => Ok(LengthField::U16),
+            WireType::Complex32 => Ok(LengthField::U32),
+            _ => Err(Error::InvalidWireType(self.as_u8())),
+        }
+    }
+}
+
+/// Tag preceding a TLV member.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Tag {
+    /// Wire type of the member
+    pub wire_type: WireType,
+    /// Data ID of the member (12 bits)
+    pub data_id: u16,
+}
+
+impl Tag {
+    /// Create a new tag.
+    ///
+    /// # Arguments
+    ///
+    /// * `wire_type` - Wire type of the member
+    /// * `data_id` - Data ID of the member, at most `0x0FFF`
+    pub const fn new(wire_type: WireType, data_id: u16) -> Self {
+        Tag { wire_type, data_id }
+    }
+
+    /// Converts a raw tag into a `Tag`, ignoring the reserved bit
+    pub const fn from_u16(value: u16) -> Self {
+        Tag {
+            wire_type: WireType::from_bits(((value & WIRE_TYPE_MASK) >> 12) as u8),
+            data_id: value & DATA_ID_MASK,
+        }
+    }
+
+    /// Returns the raw tag
+    pub const fn as_u16(&self) -> u16 {
+        ((self.wire_type.as_u8() as u16) << 12) | (self.data_id & DATA_ID_MASK)
+    }
+}
+
+/// A TLV member borrowed from the payload.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TlvMember<'a> {
+    /// Tag of the member
+    pub tag: Tag,
+    /// Data of the member, without the tag and the length field
+    pub data: &'a [u8],
+    /// Position of the data within the payload
+    pub offset: usize,
+}
+
+impl<'a> TlvMember<'a> {
+    /// Returns the data ID of the member
+    pub fn data_id(&self) -> u16 {
+        self.tag.data_id
+    }
+
+    /// Returns a reader over the data of the member, keeping its position within the
+    /// payload
+    pub fn reader(&self) -> Reader<'a> {
+        Reader::with_offset(self.data, self.offset)
+    }
+
+    /// Deserializes the data of the member.
+    ///
+    /// Dynamic length data elements take their length from the TLV length field, so
+    /// they are deserialized without a length field of their own.
+    pub fn deserialize<T: SomeIpDeserialize<'a>>(&self) -> Result<T, Error> {
+        self.reader().read()
+    }
+}
+
+/// TLV encoding of the members of a struct.
+///
+/// # Example
+///
+/// ```rust
+/// use someip_wire::serialize::{Reader, Tag, TlvFormat, WireType, Writer};
+///
+/// let format = TlvFormat::default();
+/// let mut buffer = [0u8; 16];
+/// let mut writer = Writer::new(&mut buffer);
+/// format.serialize(&mut writer, Tag::new(WireType::Base16, 1), &0x1234u16).unwrap();
+/// format.serialize(&mut writer, Tag::new(WireType::Complex8, 2), "ab").unwrap();
+/// let len = writer.position();
+///
+/// // A client knowing only data ID 1 skips the second member
+/// let mut value = None;
+/// for member in format.members(Reader::new(&buffer[..len])) {
+///     let member = member.unwrap();
+///     if member.data_id() == 1 {
+///         value = Some(member.deserialize::<u16>().unwrap());
+///     }
+/// }
+/// assert_eq!(value, Some(0x1234));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct TlvFormat {
+    /// Length field of members with wire type 4, 32 bits by default
+    pub length_field: LengthField,
+}
+
+impl TlvFormat {
+    /// Create a new TLV format.
+    ///
+    /// # Arguments
+    ///
+    /// * `length_field` - Length field of members with wire type 4
+    pub const fn new(length_field: LengthField) -> Self {
+        TlvFormat { length_field }
+    }
+
+    /// Serializes a member preceded by its tag.
+    ///
+    /// Complex data types are preceded by the length field given by the wire type.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `tag` - The tag of the member
+    /// * `value` - The member to serialize
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::InvalidDataId` if the data ID exceeds 12 bits, or
+    /// `Error::InvalidWireType` if the member does not match the wire type. The cursor
+    /// does not move on error.
+    pub fn serialize<T>(&self, writer: &mut Writer<'_>, tag: Tag, value: &T) -> Result<(), Error>
+    where
+        T: SomeIpSerialize + ?Sized,
+    {
+        if tag.data_id > DATA_ID_MASK {
+            return Err(Error::InvalidDataId(tag.data_id));
+        }
+        let start = writer.position();
+        let result = writer
+            .write(&tag.as_u16())
+            .and_then(|()| match tag.wire_type.base_size() {
+                Some(size) => {
+                    value.serialize(writer)?;
+                    if writer.position() - start - 2 != size {
+                        return Err(Error::InvalidWireType(tag.wire_type.as_u8()));
+                    }
+                    Ok(())
+                }
+                None => tag
+                    .wire_type
+                    .length_field(self.length_field)?
+                    .serialize(writer, value),
+            });
+        if result.is_err() {
+            writer.truncate(start);
+        }
+        result
+    }
+
+    /// Reads the next member.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader positioned at the tag of the member
+    ///
+    /// # Returns
+    ///
+    /// Returns the member, or an `Error` if it is truncated. The cursor does not move
+    /// on error.
+    pub fn read_member<'a>(&self, reader: &mut Reader<'a>) -> Result<TlvMember<'a>, Error> {
+        let mut peek = reader.clone();
+        let tag = Tag::from_u16(peek.read()?);
+        let data = match tag.wire_type.base_size() {
+            Some(size) => peek.limit(size)?,
+            None => tag
+                .wire_type
+                .length_field(self.length_field)?
+                .read(&mut peek)?,
+        };
+        *reader = peek;
+        Ok(TlvMember {
+            tag,
+            data: data.rest(),
+            offset: data.payload_position(),
+        })
+    }
+
+    /// Returns an iterator over the members of a TLV encoded struct.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - A reader limited to the members, e.g. by the length field of the struct
+    pub fn members<'a>(&self, reader: Reader<'a>) -> Members<'a> {
+        Members {
+            format: *self,
+            reader,
+        }
+    }
+}
+
+/// An iterator over the members of a TLV encoded struct.
+///
+/// Iteration stops after the first error.
+#[derive(Debug, Clone)]
+pub struct Members<'a> {
+    format: TlvFormat,
+    reader: Reader<'a>,
+}
+
+impl<'a> Iterator for Members<'a> {
+    type Item = Result<TlvMember<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.reader.is_empty() {
+            return None;
+        }
+        let member = self.format.read_member(&mut self.reader);
+        if member.is_err() {
+            self.reader = Reader::new(&[]);
+        }
+        Some(member)
+    }
+}
@@ -0,0 +1,285 @@
+//! Union module
+//!
+//! This module implements the serialization of SOME/IP unions (variants). A union is
+//! serialized as a length field, a type selector identifying the active member, and the
+//! member itself, optionally padded to a fixed size:
+//!
+//! ```text
+//! +--------------+----------------+--------+---------+
+//! | Length field | Selector field | Member | Padding |
+//! +--------------+----------------+--------+---------+
+//! ```
+//!
+//! The length field covers the member and the padding. Selector `0` is reserved for an
+//! empty union without an active member.
+
+use crate::error::Error;
+use crate::serialize::{LengthField, Reader, Writer};
+
+/// Selector value of an empty union
+pub const SELECTOR_NONE: u32 = 0;
+
+/// Size of the type selector field of a union.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+pub enum SelectorField {
+    /// 8-bit selector field
+    U8,
+    /// 16-bit selector field
+    U16,
+    /// 32-bit selector field
+    #[default]
+    U32,
+}
+
+impl SelectorField {
+    /// Returns the size of the selector field in bytes
+    pub const fn size(&self) -> usize {
+        match self {
+            SelectorField::U8 => 1,
+            SelectorField::U16 => 2,
+            SelectorField::U32 => 4,
+        }
+    }
+
+    /// Writes a selector value.
+    ///
+    /// Returns `Error::InvalidSelector` if the value does not fit the selector field.
+    fn write(&self, writer: &mut Writer<'_>, selector: u32) -> Result<(), Error> {
+        match self {
+            SelectorField::U8 => {
+                let value = u8::try_from(selector).map_err(|_| Error::InvalidSelector(selector))?;
+                writer.write(&value)
+            }
+            SelectorField::U16 => {
+                let value =
+                    u16::try_from(selector).map_err(|_| Error::InvalidSelector(selector))?;
+                writer.write(&value)
+            }
+            SelectorField::U32 => writer.write(&selector),
+        }
+    }
+
+    /// Reads a selector value.
+    fn read(&self, reader: &mut Reader<'_>) -> Result<u32, Error> {
+        Ok(match self {
+            SelectorField::U8 => reader.read::<u8>()? as u32,
+            SelectorField::U16 => reader.read::<u16>()? as u32,
+            SelectorField::U32 => reader.read::<u32>()?,
+        })
+    }
+}
+
+/// A type that is serialized as a SOME/IP union.
+///
+/// Typically implemented by an enum with one variant per union member, mapping each
+/// variant onto its selector value.
+///
+/// # Example
+///
+/// ```rust
+/// use someip_wire::error::Error;
+/// use someip_wire::serialize::{Reader, SomeIpUnion, UnionFormat, Writer};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Value {
+///     Counter(u16),
+///     Temperature(f32),
+/// }
+///
+/// impl<'a> SomeIpUnion<'a> for Value {
+///     fn selector(&self) -> u32 {
+///         match self {
+///             Value::Counter(_) => 1,
+///             Value::Temperature(_) => 2,
+///         }
+///     }
+///
+///     fn serialize_member(&self, writer: &mut Writer<'_>) -> Result<(), Error> {
+///         match self {
+///             Value::Counter(value) => writer.write(value),
+///             Value::Temperature(value) => writer.write(value),
+///         }
+///     }
+///
+///     fn deserialize_member(selector: u32, reader: &mut Reader<'a>) -> Result<Self, Error> {
+///         match selector {
+///             1 => Ok(Value::Counter(reader.read()?)),
+///             2 => Ok(Value::Temperature(reader.read()?)),
+///             _ => Err(Error::InvalidSelector(selector)),
+///         }
+///     }
+/// }
+///
+/// let mut buffer = [0u8; 10];
+/// let mut writer = Writer::new(&mut buffer);
+/// UnionFormat::default().serialize(&mut writer, &Value::Counter(7)).unwrap();
+/// assert_eq!(writer.written(), &[0, 0, 0, 2, 0, 0, 0, 1, 0, 7]);
+///
+/// let mut reader = Reader::new(&buffer);
+/// let value: Value = UnionFormat::default().deserialize(&mut reader).unwrap();
+/// assert_eq!(value, Value::Counter(7));
+/// ```
+pub trait SomeIpUnion<'a>: Sized {
+    /// Returns the selector value of the active member
+    fn selector(&self) -> u32;
+
+    /// Serializes the active member, without length and selector fields.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    fn serialize_member(&self, writer: &mut Writer<'_>) -> Result<(), Error>;
+
+    /// Deserializes the member identified by a selector value.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - The selector value read from the payload
+    /// * `reader` - A reader limited to the member and its padding
+    ///
+    /// # Returns
+    ///
+    /// Returns the union, or `Error::InvalidSelector` if the selector is unknown.
+    fn deserialize_member(selector: u32, reader: &mut Reader<'a>) -> Result<Self, Error>;
+}
+
+/// Layout of a serialized union.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct UnionFormat {
+    /// Length field preceding the union, 32 bits by default
+    pub length_field: LengthField,
+    /// Selector field following the length field, 32 bits by default
+    pub selector_field: SelectorField,
+    /// Size the member is padded to with zeros, if any
+    pub padded_size: Option<usize>,
+}
+
+impl UnionFormat {
+    /// Create a new union format.
+    ///
+    /// # Arguments
+    ///
+    /// * `length_field` - Length field preceding the union
+    /// * `selector_field` - Selector field following the length field
+    pub const fn new(length_field: LengthField, selector_field: SelectorField) -> Self {
+        UnionFormat {
+            length_field,
+            selector_field,
+            padded_size: None,
+        }
+    }
+
+    /// Returns the format with the member padded to a fixed size.
+    ///
+    /// # Arguments
+    ///
+    /// * `padded_size` - Size the member is padded to, usually the size of the largest member
+    pub const fn with_padding(mut self, padded_size: usize) -> Self {
+        self.padded_size = Some(padded_size);
+        self
+    }
+
+    /// Serializes a union.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `value` - The union to serialize
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::PaddingOverflow` if the member exceeds the padded size, or
+    /// `Error::LengthFieldOverflow` if it exceeds the length field. The cursor does not
+    /// move on error.
+    pub fn serialize<'a, U>(&self, writer: &mut Writer<'_>, value: &U) -> Result<(), Error>
+    where
+        U: SomeIpUnion<'a>,
+    {
+        self.write(writer, value.selector(), |writer| {
+            value.serialize_member(writer)
+        })
+    }
+
+    /// Writes a union with the given selector.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to serialize into
+    /// * `selector` - The selector value of the member
+    /// * `f` - Writes the member
+    ///
+    /// # Returns
+    ///
+    /// Returns `Error::PaddingOverflow` if the member exceeds the padded size,
+    /// `Error::LengthFieldOverflow` if it exceeds the length field, or the error returned
+    /// by `f`. The cursor does not move on error.
+    pub fn write<F>(&self, writer: &mut Writer<'_>, selector: u32, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Writer<'_>) -> Result<(), Error>,
+    {
+        let start = writer.position();
+        let result = self.emit(writer, selector, f);
+        if result.is_err() {
+            writer.truncate(start);
+        }
+        result
+    }
+
+    fn emit<F>(&self, writer: &mut Writer<'_>, selector: u32, f: F) -> Result<(), Error>
+    where
+        F: FnOnce(&mut Writer<'_>) -> Result<(), Error>,
+    {
+        let start = writer.position();
+        writer.advance(self.length_field.size())?;
+        self.selector_field.write(writer, selector)?;
+        let member = writer.position();
+        f(writer)?;
+
+        let mut length = writer.position() - member;
+        if let Some(padded_size) = self.padded_size {
+            if length > padded_size {
+                return Err(Error::PaddingOverflow(length as u32));
+            }
+            writer.advance(padded_size - length)?.fill(0);
+            length = padded_size;
+        }
+        self.length_field.write_length(writer, start, length)
+    }
+
+    /// Deserializes a union.
+    ///
+    /// The member is read from a reader limited to the length announced by the length
+    /// field; padding and unknown trailing data are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize from
+    pub fn deserialize<'a, U>(&self, reader: &mut Reader<'a>) -> Result<U, Error>
+    where
+        U: SomeIpUnion<'a>,
+    {
+        self.read(reader, U::deserialize_member)
+    }
+
+    /// Reads a union, passing the selector and the member to a closure.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to deserialize from
+    /// * `f` - Deserializes the member identified by the selector from a limited reader
+    pub fn read<'a, T, F>(&self, reader: &mut Reader<'a>, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(u32, &mut Reader<'a>) -> Result<T, Error>,
+    {
+        let mut peek = reader.clone();
+        let length = self.length_field.read_length(&mut peek)?;
+        let selector = self.selector_field.read(&mut peek)?;
+        let length = match self.length_field {
+            LengthField::None => self.padded_size.unwrap_or(peek.remaining()),
+            _ => length,
+        };
+        let value = f(selector, &mut peek.limit(length)?)?;
+        *reader = peek;
+        Ok(value)
+    }
+}
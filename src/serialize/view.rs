@@ -0,0 +1,245 @@
+//! Payload view module
+//!
+//! This module implements zero-copy views of payloads with a fully fixed layout. Like
+//! `Packet` for the header, a view wraps the payload buffer and reads or writes single
+//! fields at constant byte offsets, without deserializing the whole payload.
+//!
+//! Views are declared with the [`payload_view!`](crate::payload_view) macro. Fields are
+//! types implementing [`FixedField`]: the basic data types and fixed length arrays of
+//! them.
+
+use byteorder::{ByteOrder, NetworkEndian};
+
+/// A value with a fixed size that is read and written in place.
+pub trait FixedField: Sized {
+    /// Size of the serialized value in bytes
+    const SIZE: usize;
+
+    /// Reads the value from a slice of exactly `SIZE` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized value
+    fn read_from(bytes: &[u8]) -> Self;
+
+    /// Writes the value into a slice of exactly `SIZE` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The buffer to write the value into
+    fn write_to(&self, bytes: &mut [u8]);
+}
+
+/// Any non-zero byte reads as `true`, since a view cannot report invalid values.
+impl FixedField for bool {
+    const SIZE: usize = 1;
+
+    fn read_from(bytes: &[u8]) -> Self {
+        bytes[0] != 0
+    }
+
+    fn write_to(&self, bytes: &mut [u8]) {
+        bytes[0] = *self as u8;
+    }
+}
+
+impl FixedField for u8 {
+    const SIZE: usize = 1;
+
+    fn read_from(bytes: &[u8]) -> Self {
+        bytes[0]
+    }
+
+    fn write_to(&self, bytes: &mut [u8]) {
+        bytes[0] = *self;
+    }
+}
+
+impl FixedField for i8 {
+    const SIZE: usize = 1;
+
+    fn read_from(bytes: &[u8]) -> Self {
+        bytes[0] as i8
+    }
+
+    fn write_to(&self, bytes: &mut [u8]) {
+        bytes[0] = *self as u8;
+    }
+}
+
+macro_rules! impl_fixed_field {
+    ($ty:ty, $len:expr, $read:ident, $write:ident) => {
+        impl FixedField for $ty {
+            const SIZE: usize = $len;
+
+            fn read_from(bytes: &[u8]) -> Self {
+                NetworkEndian::$read(bytes)
+            }
+
+            fn write_to(&self, bytes: &mut [u8]) {
+                NetworkEndian::$write(bytes, *self);
+            }
+        }
+    };
+}
+
+impl_fixed_field!(u16, 2, read_u16, write_u16);
+impl_fixed_field!(u32, 4, read_u32, write_u32);
+impl_fixed_field!(u64, 8, read_u64, write_u64);
+impl_fixed_field!(i16, 2, read_i16, write_i16);
+impl_fixed_field!(i32, 4, read_i32, write_i32);
+impl_fixed_field!(i64, 8, read_i64, write_i64);
+impl_fixed_field!(f32, 4, read_f32, write_f32);
+impl_fixed_field!(f64, 8, read_f64, write_f64);
+
+impl<T: FixedField, const N: usize> FixedField for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+
+    fn read_from(bytes: &[u8]) -> Self {
+        core::array::from_fn(|index| T::read_from(&bytes[index * T::SIZE..][..T::SIZE]))
+    }
+
+    fn write_to(&self, bytes: &mut [u8]) {
+        for (value, chunk) in self.iter().zip(bytes.chunks_exact_mut(T::SIZE)) {
+            value.write_to(chunk);
+        }
+    }
+}
+
+/// Declares a zero-copy view of a payload with a fixed layout.
+///
+/// Every field is declared with a getter, a setter, its type and its byte offset within
+/// the payload. The view is a read/write wrapper around any `AsRef<[u8]>` buffer, such as
+/// `Repr::data`, with the same constructors as `Packet`. `LENGTH` is the minimum length
+/// of the payload covering all fields; `new_checked` returns `Error::BufferTooShort` for
+/// shorter buffers, and the accessors panic on buffers created with `new_unchecked`.
+///
+/// # Example
+///
+/// ```rust
+/// use someip_wire::payload_view;
+///
+/// payload_view! {
+///     /// Status notification of a vehicle.
+///     pub struct VehicleStatus {
+///         /// Speed in 0.01 km/h
+///         speed, set_speed: u16 = 0,
+///         /// Engine running
+///         engine_on, set_engine_on: bool = 2,
+///         /// Tire pressures in kPa
+///         tire_pressure, set_tire_pressure: [u16; 4] = 8,
+///     }
+/// }
+///
+/// let mut payload = [0u8; 16];
+/// let mut view = VehicleStatus::new_checked(&mut payload[..]).unwrap();
+/// view.set_speed(5000);
+/// view.set_engine_on(true);
+/// assert_eq!(VehicleStatus::<&[u8]>::LENGTH, 16);
+///
+/// let view = VehicleStatus::new_checked(&payload[..]).unwrap();
+/// assert_eq!(view.speed(), 5000);
+/// assert!(view.engine_on());
+/// assert_eq!(view.tire_pressure(), [0; 4]);
+/// ```
+#[macro_export]
+macro_rules! payload_view {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_meta:meta])*
+                $get:ident, $set:ident: $ty:ty = $offset:expr
+            ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, PartialEq, Eq, Clone)]
+        $vis struct $name<T: AsRef<[u8]>> {
+            buffer: T,
+        }
+
+        #[allow(dead_code)]
+        impl<T: AsRef<[u8]>> $name<T> {
+            /// Minimum length of the payload in bytes
+            pub const LENGTH: usize = {
+                let mut length = 0;
+                $(
+                    let end = $offset + <$ty as $crate::serialize::FixedField>::SIZE;
+                    if end > length {
+                        length = end;
+                    }
+                )+
+                length
+            };
+
+            /// Creates a new unchecked view.
+            ///
+            /// # Arguments
+            ///
+            /// * `buffer` - A buffer containing the payload.
+            pub const fn new_unchecked(buffer: T) -> Self {
+                $name { buffer }
+            }
+
+            /// Creates a new checked view.
+            ///
+            /// # Arguments
+            ///
+            /// * `buffer` - A buffer containing the payload.
+            ///
+            /// # Returns
+            ///
+            /// Returns the view, or `Error::BufferTooShort` if the buffer does not cover
+            /// all fields.
+            pub fn new_checked(buffer: T) -> ::core::result::Result<Self, $crate::error::Error> {
+                let view = Self::new_unchecked(buffer);
+                view.check_len()?;
+                ::core::result::Result::Ok(view)
+            }
+
+            /// Checks that the buffer covers all fields.
+            pub fn check_len(&self) -> ::core::result::Result<(), $crate::error::Error> {
+                if self.buffer.as_ref().len() < Self::LENGTH {
+                    ::core::result::Result::Err($crate::error::Error::BufferTooShort)
+                } else {
+                    ::core::result::Result::Ok(())
+                }
+            }
+
+            /// Returns the inner buffer.
+            pub fn into_inner(self) -> T {
+                self.buffer
+            }
+
+            /// Returns a reference to the inner buffer.
+            pub fn as_slice(&self) -> &[u8] {
+                self.buffer.as_ref()
+            }
+
+            $(
+                $(#[$field_meta])*
+                pub fn $get(&self) -> $ty {
+                    let size = <$ty as $crate::serialize::FixedField>::SIZE;
+                    <$ty as $crate::serialize::FixedField>::read_from(
+                        &self.buffer.as_ref()[$offset..$offset + size],
+                    )
+                }
+            )+
+        }
+
+        #[allow(dead_code)]
+        impl<T: AsRef<[u8]> + AsMut<[u8]>> $name<T> {
+            $(
+                #[doc = concat!("Sets the value returned by `", stringify!($get), "`.")]
+                pub fn $set(&mut self, value: $ty) {
+                    let size = <$ty as $crate::serialize::FixedField>::SIZE;
+                    $crate::serialize::FixedField::write_to(
+                        &value,
+                        &mut self.buffer.as_mut()[$offset..$offset + size],
+                    );
+                }
+            )+
+        }
+    };
+}
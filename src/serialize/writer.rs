@@ -0,0 +1,111 @@
+//! Serialization writer module
+//!
+//! This module contains the `Writer`, a bounds-checked cursor over the buffer a payload
+//! is serialized into.
+
+use crate::error::Error;
+use crate::serialize::SomeIpSerialize;
+
+/// A bounds-checked cursor writing a serialized payload into a byte buffer.
+#[derive(Debug)]
+pub struct Writer<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> Writer<'a> {
+    /// Creates a new writer at the start of the buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer the payload is written into, e.g. the payload of a packet
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Writer {
+            buffer,
+            position: 0,
+        }
+    }
+
+    /// Returns the number of bytes written so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the number of bytes left in the buffer
+    pub fn remaining(&self) -> usize {
+        self.buffer.len() - self.position
+    }
+
+    /// Returns the bytes written so far
+    pub fn written(&self) -> &[u8] {
+        &self.buffer[..self.position]
+    }
+
+    /// Returns a mutable reference to the bytes written so far.
+    ///
+    /// Used to fill in fields whose value is only known after the following data has
+    /// been written, such as length fields.
+    pub fn written_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer[..self.position]
+    }
+
+    /// Moves the cursor back, discarding the bytes written after `len`.
+    ///
+    /// Has no effect if `len` is not less than the current position.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The number of bytes to keep
+    pub fn truncate(&mut self, len: usize) {
+        self.position = self.position.min(len);
+    }
+
+    /// Advances the cursor, returning the skipped bytes to be filled in by the caller.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The number of bytes to advance
+    ///
+    /// # Returns
+    ///
+    /// Returns the skipped bytes, or `Error::BufferTooShort` if fewer than `len` bytes
+    /// are left. The cursor does not move on error.
+    pub fn advance(&mut self, len: usize) -> Result<&mut [u8], Error> {
+        if len > self.remaining() {
+            return Err(Error::BufferTooShort);
+        }
+        let start = self.position;
+        self.position += len;
+        Ok(&mut self.buffer[start..self.position])
+    }
+
+    /// Writes raw bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The bytes to copy into the buffer
+    pub fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.advance(data.len())?.copy_from_slice(data);
+        Ok(())
+    }
+
+    /// Pads with zeros up to the next multiple of `alignment` bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `alignment` - The alignment in bytes, counted from the start of the payload
+    pub fn align(&mut self, alignment: usize) -> Result<(), Error> {
+        let padding = self.position.next_multiple_of(alignment.max(1)) - self.position;
+        self.advance(padding)?.fill(0);
+        Ok(())
+    }
+
+    /// Serializes a value.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value to serialize at the current position
+    pub fn write<T: SomeIpSerialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+}
@@ -0,0 +1,497 @@
+//! SOME/IP Transport Protocol (SOME/IP-TP)
+//!
+//! SOME/IP-TP splits messages that are too large for a single UDP datagram into
+//! segments. Each segment is a regular SOME/IP message whose message type has the TP
+//! flag (`0x20`) set; its payload starts with a 4-byte TP header holding the offset of
+//! the segment within the original payload and a More Segments flag.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use someip_wire::prelude::*;
+//! use someip_wire::tp::TpRepr;
+//!
+//! let buffer = [
+//!     0x12, 0x34, 0x00, 0x01, // Message ID
+//!     0x00, 0x00, 0x00, 0x1C, // Length (8 header bytes + 4 TP bytes + 16 data bytes)
+//!     0x00, 0x01, 0x00, 0x01, // Request ID
+//!     0x01,                   // Protocol version
+//!     0x01,                   // Interface version
+//!     0x22,                   // Message type (TP Notification)
+//!     0x00,                   // Return code (E_OK)
+//!     0x00, 0x00, 0x00, 0x21, // Offset (32 bytes) + More Segments
+//!     0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+//!     0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+//! ];
+//!
+//! let packet = Packet::new_checked(&buffer[..]).unwrap();
+//! let repr = Repr::parse(&packet).unwrap();
+//!
+//! let segment = TpRepr::from_repr(&repr).unwrap().unwrap();
+//! assert_eq!(segment.offset, 32);
+//! assert!(segment.more_segments);
+//! assert_eq!(segment.data.len(), 16);
+//! ```
+
+/// Low-level access to the TP header and segment data.
+pub mod packet;
+/// Reassembly of segments into complete messages.
+pub mod reassembler;
+/// High-level TP segment representation.
+pub mod repr;
+/// Segmentation of large messages.
+pub mod segmenter;
+
+pub use packet::TpPacket;
+pub use reassembler::{Reassembler, ReassemblyBuffer, ReassemblyKey, ReassemblySlot};
+pub use repr::TpRepr;
+pub use segmenter::{MAX_UDP_SEGMENT_LENGTH, Segment, Segmenter};
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::field;
+    use crate::packet::Packet;
+    use crate::repr::Repr;
+    use crate::tp::{
+        MAX_UDP_SEGMENT_LENGTH, Reassembler, ReassemblyKey, ReassemblySlot, Segmenter, TpPacket,
+        TpRepr,
+    };
+    use crate::types::{ClientId, MessageId, MessageType, RequestId, ReturnCode};
+    use core::net::SocketAddr;
+    use core::time::Duration;
+
+    static TP_PAYLOAD: [u8; 20] = [
+        0x00, 0x00, 0x01, 0x0B, // Offset (16 units = 256 bytes), Reserved (0b101), More
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, // Segment data
+        0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, // Segment data
+    ];
+
+    static PAYLOAD: [u8; 40] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, //
+        0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, //
+        0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x1B, 0x1C, 0x1D, //
+        0x1E, 0x1F, 0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26, 0x27, //
+    ];
+
+    fn tp_repr(session_id: u16, data: &[u8]) -> Repr<'_> {
+        Repr::new(
+            MessageId {
+                service_id: 0x1234,
+                method_id: 0x8001,
+            },
+            RequestId {
+                client_id: ClientId::from_u16(0x0001),
+                session_id,
+            },
+            0x01,
+            0x02,
+            MessageType::TPNotification,
+            ReturnCode::E_OK,
+            data,
+        )
+    }
+
+    /// Builds the TP payload of the segment covering `range` of [`PAYLOAD`].
+    fn segment(range: core::ops::Range<usize>, buffer: &mut [u8; 44]) -> &[u8] {
+        let repr = TpRepr {
+            offset: range.start as u32,
+            more_segments: range.end < PAYLOAD.len(),
+            data: &PAYLOAD[range],
+        };
+        repr.emit(&mut TpPacket::new_unchecked(&mut buffer[..]));
+        &buffer[..repr.buffer_len()]
+    }
+
+    #[test]
+    fn test_tp_parse() {
+        let packet = TpPacket::new_checked(&TP_PAYLOAD[..]).unwrap();
+        assert_eq!(packet.offset_field(), 0x0000_010B);
+        assert_eq!(packet.offset(), 256);
+        assert_eq!(packet.reserved(), 0b101);
+        assert!(packet.more_segments());
+        assert_eq!(packet.segment_length(), 16);
+        assert_eq!(packet.segment(), &TP_PAYLOAD[4..]);
+
+        let repr = TpRepr::parse(&packet).unwrap();
+        assert_eq!(
+            repr,
+            TpRepr {
+                offset: 256,
+                more_segments: true,
+                data: &TP_PAYLOAD[4..],
+            }
+        );
+        assert_eq!(repr.buffer_len(), 20);
+    }
+
+    #[test]
+    fn test_tp_round_trip() {
+        let packet = TpPacket::new_checked(&TP_PAYLOAD[..]).unwrap();
+        let repr = TpRepr::parse(&packet).unwrap();
+
+        let mut buffer = [0xFFu8; 20];
+        let mut packet = TpPacket::new_unchecked(&mut buffer[..]);
+        repr.emit(&mut packet);
+
+        // Reserved bits are cleared on emission
+        assert_eq!(&buffer[..4], &[0x00, 0x00, 0x01, 0x01]);
+        assert_eq!(&buffer[4..], &TP_PAYLOAD[4..]);
+    }
+
+    #[test]
+    fn test_tp_setters() {
+        let mut buffer = [0u8; 4];
+        let mut packet = TpPacket::new_unchecked(&mut buffer[..]);
+        packet.set_offset_field(0x0000_000E);
+        packet.set_more_segments(true);
+        packet.set_offset(0x1234_5678);
+        assert_eq!(packet.offset(), 0x1234_5670);
+        assert_eq!(packet.reserved(), 0b111);
+        assert!(packet.more_segments());
+
+        packet.clear_reserved();
+        packet.set_more_segments(false);
+        assert_eq!(packet.offset_field(), 0x1234_5670);
+        assert_eq!(packet.segment_length(), 0);
+    }
+
+    #[test]
+    fn test_tp_errors() {
+        assert_eq!(
+            TpPacket::new_checked(&TP_PAYLOAD[..3]).unwrap_err(),
+            Error::BufferTooShort
+        );
+
+        // Only the last segment may have a length that is not a multiple of 16
+        let packet = TpPacket::new_checked(&TP_PAYLOAD[..19]).unwrap();
+        assert_eq!(
+            TpRepr::parse(&packet).unwrap_err(),
+            Error::InvalidSegmentLength(15)
+        );
+
+        let mut last = TP_PAYLOAD;
+        last[3] = 0x00;
+        let packet = TpPacket::new_checked(&last[..19]).unwrap();
+        let repr = TpRepr::parse(&packet).unwrap();
+        assert!(!repr.more_segments);
+        assert_eq!(repr.data.len(), 15);
+    }
+
+    #[test]
+    fn test_tp_from_repr() {
+        let mut repr = Repr::new(
+            MessageId {
+                service_id: 0x1234,
+                method_id: 0x8001,
+            },
+            RequestId {
+                client_id: ClientId::from_u16(0x0001),
+                session_id: 0x0001,
+            },
+            0x01,
+            0x01,
+            MessageType::Notification,
+            ReturnCode::E_OK,
+            &TP_PAYLOAD,
+        );
+        assert_eq!(TpRepr::from_repr(&repr), Ok(None));
+
+        repr.message_type = MessageType::TPNotification;
+        let mut buffer = [0u8; 36];
+        repr.emit(&mut Packet::new_unchecked(&mut buffer));
+
+        let packet = Packet::new_checked(&buffer[..]).unwrap();
+        let repr = Repr::parse(&packet).unwrap();
+        let segment = TpRepr::from_repr(&repr).unwrap().unwrap();
+        assert_eq!(segment.offset, 256);
+        assert_eq!(segment.data, &TP_PAYLOAD[4..]);
+
+        let repr = Repr {
+            data: &TP_PAYLOAD[..2],
+            ..repr
+        };
+        assert_eq!(TpRepr::from_repr(&repr), Err(Error::BufferTooShort));
+    }
+
+    #[test]
+    fn test_reassemble_out_of_order() {
+        let source: SocketAddr = "192.168.0.1:30501".parse().unwrap();
+        let mut slots = [ReassemblySlot::new([0u8; 64])];
+        let mut reassembler = Reassembler::new(&mut slots, 64, Duration::from_secs(1));
+        let mut buffers = [[0u8; 44]; 3];
+        let [first, second, last] = &mut buffers;
+        let first = segment(0..16, first);
+        let second = segment(16..32, second);
+        let last = segment(32..40, last);
+
+        let now = Duration::from_secs(5);
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(1, last)),
+            Ok(None)
+        );
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(1, first)),
+            Ok(None)
+        );
+        // Duplicate segments are accepted
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(1, last)),
+            Ok(None)
+        );
+        assert_eq!(reassembler.len(), 1);
+        assert_eq!(reassembler.poll_at(), Some(now + Duration::from_secs(1)));
+
+        let repr = reassembler
+            .process(now, source, &tp_repr(1, second))
+            .unwrap()
+            .unwrap();
+        assert_eq!(repr.message_type, MessageType::Notification);
+        assert_eq!(repr.interface_version, 0x02);
+        assert_eq!(repr.request_id.session_id, 1);
+        assert_eq!(repr.length, 48);
+        assert_eq!(repr.data, &PAYLOAD[..]);
+
+        // The slot is released on the next call
+        assert_eq!(reassembler.expire(now), None);
+        assert!(reassembler.is_empty());
+        assert_eq!(reassembler.poll_at(), None);
+    }
+
+    #[test]
+    fn test_reassemble_errors() {
+        let source: SocketAddr = "192.168.0.1:30501".parse().unwrap();
+        let other: SocketAddr = "192.168.0.2:30501".parse().unwrap();
+        let mut slots = [ReassemblySlot::new([0u8; 32])];
+        let mut reassembler = Reassembler::new(&mut slots, 40, Duration::from_secs(1));
+        let mut buffers = [[0u8; 44]; 3];
+        let [first, second, last] = &mut buffers;
+        let first = segment(0..16, first);
+        let second = segment(16..32, second);
+        let last = segment(32..40, last);
+        let now = Duration::ZERO;
+
+        // Segments must have the TP flag set
+        let repr = Repr {
+            message_type: MessageType::Notification,
+            ..tp_repr(1, first)
+        };
+        assert_eq!(
+            reassembler.process(now, source, &repr),
+            Err(Error::InvalidMessageType(0x02))
+        );
+
+        // The slot buffer only holds 32 bytes
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(1, first)),
+            Ok(None)
+        );
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(1, last)),
+            Err(Error::MessageTooLarge(40))
+        );
+        assert!(reassembler.is_empty());
+
+        // Only one message can be reassembled at a time
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(1, first)),
+            Ok(None)
+        );
+        assert_eq!(
+            reassembler.process(now, other, &tp_repr(1, first)),
+            Err(Error::StorageFull)
+        );
+
+        // A new session replaces the incomplete message
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(2, second)),
+            Ok(None)
+        );
+        let key = ReassemblyKey {
+            message_id: tp_repr(2, second).message_id,
+            request_id: tp_repr(2, second).request_id,
+            source,
+        };
+        assert_eq!(reassembler.expire(Duration::from_millis(999)), None);
+        assert_eq!(reassembler.expire(Duration::from_secs(1)), Some(key));
+        assert!(reassembler.is_empty());
+
+        // A last segment that ends before data already received
+        let mut buffer = [0u8; 44];
+        let short_last = TpRepr {
+            offset: 0,
+            more_segments: false,
+            data: &PAYLOAD[..8],
+        };
+        short_last.emit(&mut TpPacket::new_unchecked(&mut buffer[..]));
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(3, second)),
+            Ok(None)
+        );
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(3, &buffer[..12])),
+            Err(Error::InconsistentSegments)
+        );
+
+        let mut reassembler = Reassembler::new(&mut slots, 16, Duration::from_secs(1));
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(1, second)),
+            Err(Error::MessageTooLarge(32))
+        );
+
+        // A segment beyond the maximum size discards the partially reassembled message
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(4, first)),
+            Ok(None)
+        );
+        assert!(!reassembler.is_empty());
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(4, second)),
+            Err(Error::MessageTooLarge(32))
+        );
+        assert!(reassembler.is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_reassemble_alloc() {
+        let source: SocketAddr = "192.168.0.1:30501".parse().unwrap();
+        let mut slots = [ReassemblySlot::new(Vec::new())];
+        let mut reassembler = Reassembler::new(&mut slots, 1024, Duration::from_secs(1));
+        let mut buffers = [[0u8; 44]; 2];
+        let [first, last] = &mut buffers;
+        let first = segment(0..32, first);
+        let last = segment(32..40, last);
+
+        let now = Duration::ZERO;
+        assert_eq!(
+            reassembler.process(now, source, &tp_repr(1, first)),
+            Ok(None)
+        );
+        let repr = reassembler
+            .process(now, source, &tp_repr(1, last))
+            .unwrap()
+            .unwrap();
+        assert_eq!(repr.data, &PAYLOAD[..]);
+
+        reassembler.expire(now);
+        assert_eq!(slots[0].key(), None);
+    }
+
+    #[test]
+    fn test_segmenter() {
+        let repr = Repr {
+            message_type: MessageType::Notification,
+            ..tp_repr(1, &PAYLOAD)
+        };
+        assert_eq!(
+            Segmenter::new(repr, 20).unwrap_err(),
+            Error::InvalidSegmentLength(20)
+        );
+        assert_eq!(
+            Segmenter::new(repr, 0).unwrap_err(),
+            Error::InvalidSegmentLength(0)
+        );
+        // Every message type that can be segmented carries the TP flag on its segments
+        for message_type in [
+            MessageType::Request,
+            MessageType::RequestNoReturn,
+            MessageType::Response,
+            MessageType::Error,
+        ] {
+            let repr = Repr {
+                message_type,
+                ..repr
+            };
+            let segment = Segmenter::new(repr, 16).unwrap().next().unwrap();
+            assert!(segment.message_type().is_tp());
+        }
+
+        let mut segmenter = Segmenter::new(repr, 16).unwrap();
+        assert_eq!(segmenter.len(), 3);
+
+        let segment = segmenter.next().unwrap();
+        assert_eq!(segment.message_type(), MessageType::TPNotification);
+        assert_eq!(segment.message_id(), repr.message_id);
+        assert_eq!(segment.request_id(), repr.request_id);
+        assert_eq!(segment.length(), 8 + 4 + 16);
+        assert_eq!(segment.buffer_len(), 36);
+        assert_eq!(
+            segment.tp,
+            TpRepr {
+                offset: 0,
+                more_segments: true,
+                data: &PAYLOAD[..16],
+            }
+        );
+
+        let mut buffer = [0u8; 36];
+        segment.emit(&mut Packet::new_unchecked(&mut buffer[..]));
+        let packet = Packet::new_checked(&buffer[..]).unwrap();
+        let parsed = Repr::parse(&packet).unwrap();
+        assert_eq!(parsed.request_id, repr.request_id);
+        assert_eq!(TpRepr::from_repr(&parsed), Ok(Some(segment.tp)));
+
+        assert_eq!(segmenter.len(), 2);
+        let segment = segmenter.next().unwrap();
+        assert_eq!(segment.tp.offset, 16);
+        assert!(segment.tp.more_segments);
+        let segment = segmenter.next().unwrap();
+        assert_eq!(segment.tp.offset, 32);
+        assert!(!segment.tp.more_segments);
+        assert_eq!(segment.tp.data, &PAYLOAD[32..]);
+        assert_eq!(segment.length(), 8 + 4 + 8);
+        assert_eq!(segmenter.len(), 0);
+        assert_eq!(segmenter.next(), None);
+
+        // An empty payload is sent as a single segment
+        let empty = Repr { data: &[], ..repr };
+        let segments: Vec<_> = Segmenter::new(empty, 16).unwrap().collect();
+        assert_eq!(segments.len(), 1);
+        assert!(!segments[0].tp.more_segments);
+    }
+
+    #[test]
+    fn test_max_udp_segment_length() {
+        let payload = [0u8; 2 * MAX_UDP_SEGMENT_LENGTH];
+        let mut segmenter = Segmenter::new(tp_repr(1, &payload), MAX_UDP_SEGMENT_LENGTH).unwrap();
+        let segment = segmenter.next().unwrap();
+        assert_eq!(segment.length(), 8 + 4 + 1392);
+        assert_eq!(segment.buffer_len(), 16 + 4 + 1392);
+
+        // The largest multiple of the offset unit whose TP header and data fit 1400 bytes
+        assert_eq!(MAX_UDP_SEGMENT_LENGTH % field::tp::OFFSET_UNIT, 0);
+        assert!(segment.tp.buffer_len() <= 1400);
+        assert!(segment.tp.buffer_len() + field::tp::OFFSET_UNIT > 1400);
+    }
+
+    #[test]
+    fn test_segment_and_reassemble() {
+        let source: SocketAddr = "192.168.0.1:30501".parse().unwrap();
+        let mut slots = [ReassemblySlot::new([0u8; 64])];
+        let mut reassembler = Reassembler::new(&mut slots, 64, Duration::from_secs(1));
+        let repr = Repr {
+            message_type: MessageType::Response,
+            ..tp_repr(7, &PAYLOAD)
+        };
+
+        let mut buffers = [[0u8; 48]; 3];
+        let mut segments = Segmenter::new(repr, 16).unwrap();
+        for buffer in buffers.iter_mut().rev() {
+            let segment = segments.next().unwrap();
+            segment.emit(&mut Packet::new_unchecked(&mut buffer[..]));
+        }
+
+        // Process the segments in reverse order
+        let now = Duration::ZERO;
+        for buffer in &buffers[..2] {
+            let packet = Packet::new_checked(&buffer[..]).unwrap();
+            let segment = Repr::parse(&packet).unwrap();
+            assert_eq!(reassembler.process(now, source, &segment), Ok(None));
+        }
+        let packet = Packet::new_checked(&buffers[2][..]).unwrap();
+        let segment = Repr::parse(&packet).unwrap();
+        let reassembled = reassembler.process(now, source, &segment).unwrap().unwrap();
+        assert_eq!(reassembled, repr);
+    }
+}
@@ -0,0 +1,204 @@
+//! TP packet module
+//!
+//! This module contains the `TpPacket` type, which is a read/write wrapper around the payload
+//! of a SOME/IP-TP segment.
+
+use crate::error::Error;
+use crate::field;
+use crate::packet::Result;
+use byteorder::{ByteOrder, NetworkEndian};
+use core::fmt;
+
+/// A read/write wrapper around the payload of a SOME/IP-TP segment.
+///
+/// The buffer is the payload of a SOME/IP message whose message type has the TP flag
+/// set, i.e. the TP header followed by the segment data.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TpPacket<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+impl<T: AsRef<[u8]>> TpPacket<T> {
+    /// Creates a new unchecked `TpPacket`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - A buffer containing the TP header and segment data.
+    ///
+    /// # Returns
+    ///
+    /// * `TpPacket` - A new `TpPacket` instance.
+    pub const fn new_unchecked(buffer: T) -> TpPacket<T> {
+        TpPacket { buffer }
+    }
+
+    /// Creates a new checked `TpPacket`.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - A buffer containing the TP header and segment data.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<TpPacket>` - A new `TpPacket` instance if the buffer is valid.
+    pub fn new_checked(buffer: T) -> Result<TpPacket<T>> {
+        let packet = Self::new_unchecked(buffer);
+        packet.check_len()?;
+        Ok(packet)
+    }
+
+    /// Checks the length of the TP segment.
+    ///
+    /// # Returns
+    ///
+    /// * `Result<()>` - Ok if the buffer holds the TP header, otherwise an error.
+    pub fn check_len(&self) -> Result<()> {
+        if self.buffer.as_ref().len() < field::tp::HEADER_LENGTH {
+            Err(Error::BufferTooShort)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the inner buffer.
+    ///
+    /// # Returns
+    ///
+    /// * `T` - The inner buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    /// Returns a reference to the inner buffer.
+    ///
+    /// # Returns
+    ///
+    /// * `&[u8]` - A reference to the buffer.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+
+    /// Returns the Offset field including the reserved bits and the More Segments flag (raw u32)
+    ///
+    /// # Returns
+    ///
+    /// * `u32` - The raw 32-bit offset field
+    pub fn offset_field(&self) -> u32 {
+        NetworkEndian::read_u32(&self.buffer.as_ref()[field::tp::OFFSET])
+    }
+
+    /// Returns the Offset of the segment data within the original payload
+    ///
+    /// # Returns
+    ///
+    /// * `u32` - The offset in bytes (always a multiple of 16)
+    pub fn offset(&self) -> u32 {
+        self.offset_field() & field::tp::OFFSET_MASK
+    }
+
+    /// Returns the Reserved bits
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The three reserved bits, shifted to the lowest bits
+    pub fn reserved(&self) -> u8 {
+        ((self.offset_field() & field::tp::RESERVED_MASK) >> 1) as u8
+    }
+
+    /// Returns the More Segments flag
+    ///
+    /// # Returns
+    ///
+    /// * `bool` - True if further segments follow this one
+    pub fn more_segments(&self) -> bool {
+        self.offset_field() & field::tp::MORE_SEGMENTS_FLAG != 0
+    }
+
+    /// Returns the length of the segment data
+    ///
+    /// # Returns
+    ///
+    /// * `usize` - The number of bytes following the TP header
+    pub fn segment_length(&self) -> usize {
+        self.buffer.as_ref().len() - field::tp::HEADER_LENGTH
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> TpPacket<T> {
+    /// Sets the Offset field including the reserved bits and the More Segments flag (raw u32)
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The new raw 32-bit offset field
+    pub fn set_offset_field(&mut self, value: u32) {
+        NetworkEndian::write_u32(&mut self.buffer.as_mut()[field::tp::OFFSET], value);
+    }
+
+    /// Sets the Offset of the segment data, keeping the flags
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The offset in bytes, the lowest four bits are ignored
+    pub fn set_offset(&mut self, offset: u32) {
+        let flags = self.offset_field() & !field::tp::OFFSET_MASK;
+        self.set_offset_field((offset & field::tp::OFFSET_MASK) | flags);
+    }
+
+    /// Clears the Reserved bits
+    pub fn clear_reserved(&mut self) {
+        let value = self.offset_field() & !field::tp::RESERVED_MASK;
+        self.set_offset_field(value);
+    }
+
+    /// Sets the More Segments flag
+    ///
+    /// # Arguments
+    ///
+    /// * `more_segments` - True if further segments follow this one
+    pub fn set_more_segments(&mut self, more_segments: bool) {
+        let value = self.offset_field() & !field::tp::MORE_SEGMENTS_FLAG;
+        if more_segments {
+            self.set_offset_field(value | field::tp::MORE_SEGMENTS_FLAG);
+        } else {
+            self.set_offset_field(value);
+        }
+    }
+}
+
+impl<'a, T: AsRef<[u8]> + ?Sized> TpPacket<&'a T> {
+    /// Returns a reference to the segment data.
+    ///
+    /// # Returns
+    ///
+    /// * `&'a [u8]` - A reference to the bytes following the TP header.
+    #[inline]
+    pub fn segment(&self) -> &'a [u8] {
+        &self.buffer.as_ref()[field::tp::HEADER_LENGTH..]
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]> + ?Sized> TpPacket<&mut T> {
+    /// Returns a mutable reference to the segment data.
+    ///
+    /// # Returns
+    ///
+    /// * `&mut [u8]` - A mutable reference to the bytes following the TP header.
+    #[inline]
+    pub fn segment_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer.as_mut()[field::tp::HEADER_LENGTH..]
+    }
+}
+
+impl<T: AsRef<[u8]> + ?Sized> fmt::Display for TpPacket<&T> {
+    /// Formats the TP packet as a string
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SOME/IP-TP Segment: offset={}, more_segments={}, segment_len={}",
+            self.offset(),
+            self.more_segments(),
+            self.segment_length()
+        )
+    }
+}
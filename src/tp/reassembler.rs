@@ -0,0 +1,503 @@
+//! TP reassembler module
+//!
+//! This module contains the `Reassembler`, which collects SOME/IP-TP segments and
+//! reassembles them into complete SOME/IP messages.
+
+use crate::error::Error;
+use crate::repr::Repr;
+use crate::tp::repr::TpRepr;
+use crate::types::{MessageId, MessageType, RequestId, ReturnCode};
+use core::fmt::{self, Display};
+use core::net::SocketAddr;
+use core::time::Duration;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Maximum number of disjoint byte ranges tracked per message
+const CONTIG_COUNT: usize = 8;
+
+/// Storage holding the payload of a message being reassembled.
+///
+/// Implemented for byte slices and arrays, which provide a fixed capacity, and with
+/// the `alloc` feature for `Vec<u8>`, which grows up to the maximum message size.
+pub trait ReassemblyBuffer {
+    /// Makes at least `len` bytes available, returning false if the buffer cannot hold them.
+    fn reserve(&mut self, len: usize) -> bool;
+
+    /// Releases the buffer after a message has been delivered or discarded.
+    fn release(&mut self) {}
+
+    /// Returns the buffer contents
+    fn as_slice(&self) -> &[u8];
+
+    /// Returns the mutable buffer contents
+    fn as_mut_slice(&mut self) -> &mut [u8];
+}
+
+impl ReassemblyBuffer for &mut [u8] {
+    fn reserve(&mut self, len: usize) -> bool {
+        len <= self.len()
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+impl<const N: usize> ReassemblyBuffer for [u8; N] {
+    fn reserve(&mut self, len: usize) -> bool {
+        len <= N
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ReassemblyBuffer for Vec<u8> {
+    fn reserve(&mut self, len: usize) -> bool {
+        if len > self.len() {
+            self.resize(len, 0);
+        }
+        true
+    }
+
+    fn release(&mut self) {
+        self.clear();
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+/// Identifies the message a SOME/IP-TP segment belongs to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ReassemblyKey {
+    /// Message ID of the segments
+    pub message_id: MessageId,
+    /// Request ID of the segments
+    pub request_id: RequestId,
+    /// Source address of the segments
+    pub source: SocketAddr,
+}
+
+impl ReassemblyKey {
+    /// Checks if `other` is a later or earlier message of the same sender and method
+    fn supersedes(&self, other: &ReassemblyKey) -> bool {
+        self.message_id == other.message_id
+            && self.request_id.client_id == other.request_id.client_id
+            && self.source == other.source
+            && self.request_id.session_id != other.request_id.session_id
+    }
+}
+
+impl Display for ReassemblyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} from {}",
+            self.message_id, self.request_id, self.source
+        )
+    }
+}
+
+/// Set of received byte ranges, kept sorted and merged.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Assembler {
+    ranges: [(u32, u32); CONTIG_COUNT],
+    count: usize,
+}
+
+impl Assembler {
+    const fn new() -> Self {
+        Assembler {
+            ranges: [(0, 0); CONTIG_COUNT],
+            count: 0,
+        }
+    }
+
+    /// Adds a range, returning false if too many disjoint ranges would be needed.
+    fn add(&mut self, start: u32, end: u32) -> bool {
+        if start == end {
+            return true;
+        }
+
+        let (mut start, mut end) = (start, end);
+        let mut merged = [(0, 0); CONTIG_COUNT];
+        let mut count = 0;
+        let mut inserted = false;
+        for &(range_start, range_end) in &self.ranges[..self.count] {
+            if range_end < start {
+                merged[count] = (range_start, range_end);
+                count += 1;
+            } else if end < range_start {
+                if !inserted {
+                    if count == CONTIG_COUNT {
+                        return false;
+                    }
+                    merged[count] = (start, end);
+                    count += 1;
+                    inserted = true;
+                }
+                if count == CONTIG_COUNT {
+                    return false;
+                }
+                merged[count] = (range_start, range_end);
+                count += 1;
+            } else {
+                start = start.min(range_start);
+                end = end.max(range_end);
+            }
+        }
+        if !inserted {
+            if count == CONTIG_COUNT {
+                return false;
+            }
+            merged[count] = (start, end);
+            count += 1;
+        }
+
+        self.ranges = merged;
+        self.count = count;
+        true
+    }
+
+    /// Checks if exactly the bytes `0..total` have been received
+    fn is_complete(&self, total: u32) -> bool {
+        match self.ranges[..self.count] {
+            [] => total == 0,
+            [(0, end)] => end == total,
+            _ => false,
+        }
+    }
+
+    /// Returns the end of the last received range
+    fn end(&self) -> u32 {
+        match self.count {
+            0 => 0,
+            count => self.ranges[count - 1].1,
+        }
+    }
+}
+
+/// State of a message being reassembled
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Reassembly {
+    key: ReassemblyKey,
+    protocol_version: u8,
+    interface_version: u8,
+    message_type: MessageType,
+    return_code: ReturnCode,
+    started_at: Duration,
+    total: Option<u32>,
+    assembler: Assembler,
+    delivered: bool,
+}
+
+/// A reassembly slot: a buffer together with the state of the message stored in it.
+#[derive(Debug)]
+pub struct ReassemblySlot<B: ReassemblyBuffer> {
+    buffer: B,
+    reassembly: Option<Reassembly>,
+}
+
+impl<B: ReassemblyBuffer> ReassemblySlot<B> {
+    /// Create a new, unused slot around a buffer.
+    pub const fn new(buffer: B) -> Self {
+        ReassemblySlot {
+            buffer,
+            reassembly: None,
+        }
+    }
+
+    /// Returns the key of the message being reassembled in this slot, if any.
+    pub fn key(&self) -> Option<&ReassemblyKey> {
+        self.reassembly.as_ref().map(|reassembly| &reassembly.key)
+    }
+
+    /// Returns the inner buffer.
+    pub fn into_inner(self) -> B {
+        self.buffer
+    }
+
+    fn release(&mut self) {
+        self.reassembly = None;
+        self.buffer.release();
+    }
+}
+
+/// Reassembles SOME/IP-TP segments into complete messages.
+///
+/// Segments are grouped by Message ID, Request ID and source address. They may arrive
+/// out of order and more than once. A message is discarded if it grows beyond the
+/// maximum size, if it is not complete within the timeout after its first segment,
+/// or if a segment with a different session ID arrives for the same method and client.
+///
+/// Every message occupies one caller-provided slot while it is being reassembled.
+///
+/// # Example
+///
+/// ```rust
+/// use core::net::SocketAddr;
+/// use core::time::Duration;
+/// use someip_wire::prelude::*;
+/// use someip_wire::tp::reassembler::{Reassembler, ReassemblySlot};
+///
+/// let source: SocketAddr = "192.168.0.1:30501".parse().unwrap();
+/// let mut slots = [ReassemblySlot::new([0u8; 64]), ReassemblySlot::new([0u8; 64])];
+/// let mut reassembler = Reassembler::new(&mut slots, 64, Duration::from_secs(1));
+///
+/// let segment = |payload: &'static [u8]| Repr::new(
+///     MessageId { service_id: 0x1234, method_id: 0x8001 },
+///     RequestId { client_id: ClientId::from_u16(0), session_id: 1 },
+///     0x01,
+///     0x01,
+///     MessageType::TPNotification,
+///     ReturnCode::E_OK,
+///     payload,
+/// );
+///
+/// static SECOND: [u8; 6] = [0x00, 0x00, 0x00, 0x10, 0xAA, 0xBB];
+/// static FIRST: [u8; 20] = [0x00, 0x00, 0x00, 0x01, 0x11, 0x11, 0x11, 0x11, 0x11,
+///     0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11];
+///
+/// let now = Duration::ZERO;
+/// assert_eq!(reassembler.process(now, source, &segment(&SECOND)), Ok(None));
+/// let message = reassembler.process(now, source, &segment(&FIRST)).unwrap().unwrap();
+/// assert_eq!(message.message_type, MessageType::Notification);
+/// assert_eq!(message.data.len(), 18);
+/// ```
+#[derive(Debug)]
+pub struct Reassembler<'a, B: ReassemblyBuffer> {
+    slots: &'a mut [ReassemblySlot<B>],
+    max_size: u32,
+    timeout: Duration,
+}
+
+impl<'a, B: ReassemblyBuffer> Reassembler<'a, B> {
+    /// Create a new reassembler.
+    ///
+    /// # Arguments
+    ///
+    /// * `slots` - Storage for the messages being reassembled
+    /// * `max_size` - Maximum payload size of a reassembled message in bytes
+    /// * `timeout` - Time after the first segment within which a message must be complete
+    pub fn new(slots: &'a mut [ReassemblySlot<B>], max_size: u32, timeout: Duration) -> Self {
+        Reassembler {
+            slots,
+            max_size,
+            timeout,
+        }
+    }
+
+    /// Returns the number of messages currently being reassembled
+    pub fn len(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| {
+                slot.reassembly
+                    .is_some_and(|reassembly| !reassembly.delivered)
+            })
+            .count()
+    }
+
+    /// Checks if no message is being reassembled
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the time at which [`Reassembler::expire`] must be called next, or None
+    /// if no message is being reassembled.
+    pub fn poll_at(&self) -> Option<Duration> {
+        self.slots
+            .iter()
+            .filter_map(|slot| slot.reassembly)
+            .filter(|reassembly| !reassembly.delivered)
+            .map(|reassembly| reassembly.started_at + self.timeout)
+            .min()
+    }
+
+    /// Discards a message that has not been completed within the timeout.
+    ///
+    /// Call repeatedly until it returns None to discard all timed out messages.
+    ///
+    /// # Returns
+    ///
+    /// Returns the key of the discarded message, if any.
+    pub fn expire(&mut self, now: Duration) -> Option<ReassemblyKey> {
+        self.release_delivered();
+        let timeout = self.timeout;
+        let slot = self.slots.iter_mut().find(|slot| {
+            slot.reassembly
+                .is_some_and(|reassembly| reassembly.started_at + timeout <= now)
+        })?;
+        let key = slot.key().copied();
+        slot.release();
+        key
+    }
+
+    /// Processes a received SOME/IP-TP segment.
+    ///
+    /// The message returned on completion borrows the slot buffer; the slot is released
+    /// on the next call to `process` or `expire`.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time
+    /// * `source` - The source address of the segment
+    /// * `repr` - The received SOME/IP message, with the TP flag set
+    ///
+    /// # Returns
+    ///
+    /// Returns the reassembled message with the TP flag cleared once all segments have
+    /// been received, None while segments are missing, or an `Error` if the segment is
+    /// malformed or cannot be stored. The partially reassembled message is discarded
+    /// if the segment does not fit it.
+    pub fn process(
+        &mut self,
+        now: Duration,
+        source: SocketAddr,
+        repr: &Repr<'_>,
+    ) -> Result<Option<Repr<'_>>, Error> {
+        self.release_delivered();
+
+        let segment =
+            TpRepr::from_repr(repr)?.ok_or(Error::InvalidMessageType(repr.message_type.as_u8()))?;
+        let key = ReassemblyKey {
+            message_id: repr.message_id,
+            request_id: repr.request_id,
+            source,
+        };
+        let start = segment.offset;
+        let end = start.checked_add(segment.data.len() as u32);
+        let end = match end {
+            Some(end) if end <= self.max_size => end,
+            _ => {
+                self.discard(&key);
+                return Err(Error::MessageTooLarge(end.unwrap_or(u32::MAX)));
+            }
+        };
+
+        let (index, mut reassembly) = self.slot_for(now, &key, repr)?;
+        let slot = &mut self.slots[index];
+
+        let total = if segment.more_segments {
+            reassembly.total
+        } else {
+            Some(end)
+        };
+        let consistent = match (reassembly.total, total) {
+            (Some(known), Some(total)) => known == total && end <= total,
+            (None, Some(total)) => reassembly.assembler.end() <= total,
+            _ => true,
+        };
+        if !consistent {
+            slot.release();
+            return Err(Error::InconsistentSegments);
+        }
+        if !slot.buffer.reserve(end as usize) {
+            slot.release();
+            return Err(Error::MessageTooLarge(end));
+        }
+        if !reassembly.assembler.add(start, end) {
+            slot.release();
+            return Err(Error::StorageFull);
+        }
+        slot.buffer.as_mut_slice()[start as usize..end as usize].copy_from_slice(segment.data);
+
+        reassembly.total = total;
+        let complete = total.filter(|&total| reassembly.assembler.is_complete(total));
+        reassembly.delivered = complete.is_some();
+        slot.reassembly = Some(reassembly);
+
+        let Some(total) = complete else {
+            return Ok(None);
+        };
+        Ok(Some(Repr::new(
+            reassembly.key.message_id,
+            reassembly.key.request_id,
+            reassembly.protocol_version,
+            reassembly.interface_version,
+            reassembly.message_type,
+            reassembly.return_code,
+            &slot.buffer.as_slice()[..total as usize],
+        )))
+    }
+
+    /// Releases the slot of a message, if it is being reassembled.
+    fn discard(&mut self, key: &ReassemblyKey) {
+        if let Some(slot) = self.slots.iter_mut().find(|slot| slot.key() == Some(key)) {
+            slot.release();
+        }
+    }
+
+    /// Releases slots whose messages have been returned by `process`.
+    fn release_delivered(&mut self) {
+        for slot in self.slots.iter_mut() {
+            if slot
+                .reassembly
+                .is_some_and(|reassembly| reassembly.delivered)
+            {
+                slot.release();
+            }
+        }
+    }
+
+    /// Finds the slot of a message and its reassembly, starting a new reassembly if
+    /// required.
+    fn slot_for(
+        &mut self,
+        now: Duration,
+        key: &ReassemblyKey,
+        repr: &Repr<'_>,
+    ) -> Result<(usize, Reassembly), Error> {
+        for slot in self.slots.iter_mut() {
+            if slot.key().is_some_and(|other| key.supersedes(other)) {
+                slot.release();
+            }
+        }
+
+        let existing = self.slots.iter().enumerate().find_map(|(index, slot)| {
+            slot.reassembly
+                .filter(|reassembly| reassembly.key == *key)
+                .map(|reassembly| (index, reassembly))
+        });
+        if let Some(existing) = existing {
+            return Ok(existing);
+        }
+
+        let index = self
+            .slots
+            .iter()
+            .position(|slot| slot.reassembly.is_none())
+            .ok_or(Error::StorageFull)?;
+        let reassembly = Reassembly {
+            key: *key,
+            protocol_version: repr.protocol_version,
+            interface_version: repr.interface_version,
+            message_type: repr.message_type.without_tp(),
+            return_code: repr.return_code,
+            started_at: now,
+            total: None,
+            assembler: Assembler::new(),
+            delivered: false,
+        };
+        self.slots[index].reassembly = Some(reassembly);
+        Ok((index, reassembly))
+    }
+}
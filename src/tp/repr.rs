@@ -0,0 +1,118 @@
+use crate::repr::Repr;
+use crate::tp::packet::TpPacket;
+use crate::{error::*, field};
+use core::fmt;
+
+/// A high-level representation of a SOME/IP-TP segment.
+///
+/// The segment data is borrowed from the packet, without the TP header.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TpRepr<'a> {
+    /// Offset of the segment data within the original payload in bytes (a multiple of 16)
+    pub offset: u32,
+    /// More Segments flag: further segments follow this one
+    pub more_segments: bool,
+    /// Segment data
+    pub data: &'a [u8],
+}
+
+impl<'a> TpRepr<'a> {
+    /// Parse the payload of a SOME/IP-TP message into a high-level representation.
+    ///
+    /// The reserved bits are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - The TP packet buffer to parse
+    ///
+    /// # Returns
+    ///
+    /// Returns a `TpRepr` on success, or an `Error` if the segment is malformed.
+    pub fn parse<T>(packet: &'a TpPacket<T>) -> core::result::Result<TpRepr<'a>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        packet.check_len()?;
+        let repr = TpRepr {
+            offset: packet.offset(),
+            more_segments: packet.more_segments(),
+            data: &packet.as_slice()[field::tp::HEADER_LENGTH..],
+        };
+        repr.check_segment_length()?;
+        Ok(repr)
+    }
+
+    /// Parse the TP header of a SOME/IP message, if it is a TP message.
+    ///
+    /// # Arguments
+    ///
+    /// * `repr` - The SOME/IP message
+    ///
+    /// # Returns
+    ///
+    /// Returns None if the TP flag of the message type is not set, the parsed segment if
+    /// it is, or an `Error` if the segment is malformed.
+    pub fn from_repr(repr: &Repr<'a>) -> core::result::Result<Option<TpRepr<'a>>, Error> {
+        if !repr.message_type.is_tp() {
+            return Ok(None);
+        }
+        let packet = TpPacket::new_checked(repr.data)?;
+        let repr = TpRepr {
+            offset: packet.offset(),
+            more_segments: packet.more_segments(),
+            data: packet.segment(),
+        };
+        repr.check_segment_length()?;
+        Ok(Some(repr))
+    }
+
+    /// Checks that all but the last segment carry a multiple of 16 bytes.
+    fn check_segment_length(&self) -> core::result::Result<(), Error> {
+        if self.more_segments && !self.data.len().is_multiple_of(field::tp::OFFSET_UNIT) {
+            return Err(Error::InvalidSegmentLength(self.data.len() as u32));
+        }
+        Ok(())
+    }
+
+    /// Returns the length of the buffer required to emit this representation.
+    pub fn buffer_len(&self) -> usize {
+        field::tp::segment(self.data.len()).end
+    }
+
+    /// Returns the raw offset field of this representation, with cleared reserved bits.
+    pub fn offset_field(&self) -> u32 {
+        let mut value = self.offset & field::tp::OFFSET_MASK;
+        if self.more_segments {
+            value |= field::tp::MORE_SEGMENTS_FLAG;
+        }
+        value
+    }
+
+    /// Emits the high-level representation of the TP segment into the provided packet/buffer.
+    ///
+    /// The buffer must be at least [`TpRepr::buffer_len`] bytes long.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A mutable reference to the packet where the high-level representation will be written.
+    pub fn emit<T>(&self, packet: &mut TpPacket<&mut T>)
+    where
+        T: AsRef<[u8]> + AsMut<[u8]> + ?Sized,
+    {
+        packet.set_offset_field(self.offset_field());
+        packet.segment_mut()[..self.data.len()].copy_from_slice(self.data);
+    }
+}
+
+impl<'a> fmt::Display for TpRepr<'a> {
+    /// Formats the high-level representation as a string.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SOME/IP-TP Segment: offset={}, more_segments={}, data_len={}",
+            self.offset,
+            self.more_segments,
+            self.data.len()
+        )
+    }
+}
@@ -0,0 +1,196 @@
+//! TP segmenter module
+//!
+//! This module contains the `Segmenter`, which splits a large SOME/IP message into
+//! SOME/IP-TP segments.
+
+use crate::error::Error;
+use crate::field;
+use crate::packet::Packet;
+use crate::repr::Repr;
+use crate::tp::packet::TpPacket;
+use crate::tp::repr::TpRepr;
+use crate::types::{MessageId, MessageType, RequestId};
+use core::fmt;
+
+/// Largest segment length whose TP header and data fit a 1400 byte SOME/IP payload
+///
+/// With the 16 byte SOME/IP header, a segment of this length is sent in a UDP datagram
+/// with at most 1416 bytes of payload.
+pub const MAX_UDP_SEGMENT_LENGTH: usize = 1392;
+
+/// A single SOME/IP-TP segment of a message.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Segment<'a> {
+    /// SOME/IP header fields of the segment; `data` is empty as the payload is
+    /// described by `tp`, so this is not a self-consistent `Repr`
+    header: Repr<'a>,
+    /// TP header and segment data
+    pub tp: TpRepr<'a>,
+}
+
+impl<'a> Segment<'a> {
+    /// Returns the message ID of the segmented message
+    pub fn message_id(&self) -> MessageId {
+        self.header.message_id
+    }
+
+    /// Returns the request ID of the segmented message
+    pub fn request_id(&self) -> RequestId {
+        self.header.request_id
+    }
+
+    /// Returns the message type of the segment, with the TP flag set
+    pub fn message_type(&self) -> MessageType {
+        self.header.message_type
+    }
+
+    /// Returns the value of the length field, covering the TP header and segment data
+    pub fn length(&self) -> u32 {
+        self.header.length
+    }
+
+    /// Returns the length of the buffer required to emit this segment.
+    pub fn buffer_len(&self) -> usize {
+        field::header::HEADER_LENGTH + self.tp.buffer_len()
+    }
+
+    /// Emits the segment, i.e. the SOME/IP header, the TP header and the segment data.
+    ///
+    /// The buffer must be at least [`Segment::buffer_len`] bytes long.
+    ///
+    /// # Arguments
+    ///
+    /// * `packet` - A mutable reference to the packet where the segment will be written.
+    pub fn emit<T>(&self, packet: &mut Packet<&mut T>)
+    where
+        T: AsRef<[u8]> + AsMut<[u8]> + ?Sized,
+    {
+        self.header.emit(packet);
+        self.tp
+            .emit(&mut TpPacket::new_unchecked(packet.payload_data_mut()));
+    }
+}
+
+impl<'a> fmt::Display for Segment<'a> {
+    /// Formats the segment as a string.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, {}", self.header, self.tp)
+    }
+}
+
+/// Splits a SOME/IP message into SOME/IP-TP segments.
+///
+/// The segmenter is an iterator over the segments of the message. Every segment except
+/// the last one carries exactly the maximum segment length of data.
+///
+/// # Example
+///
+/// ```rust
+/// use someip_wire::prelude::*;
+/// use someip_wire::tp::Segmenter;
+///
+/// let payload = [0xAB; 40];
+/// let repr = Repr::new(
+///     MessageId { service_id: 0x1234, method_id: 0x8001 },
+///     RequestId { client_id: ClientId::from_u16(0x0001), session_id: 0x0001 },
+///     0x01,
+///     0x01,
+///     MessageType::Notification,
+///     ReturnCode::E_OK,
+///     &payload,
+/// );
+///
+/// let segmenter = Segmenter::new(repr, 32).unwrap();
+/// assert_eq!(segmenter.len(), 2);
+///
+/// let mut buffer = [0u8; 52];
+/// for segment in segmenter {
+///     let mut packet = Packet::new_unchecked(&mut buffer[..segment.buffer_len()]);
+///     segment.emit(&mut packet);
+///     assert_eq!(packet.message_type(), 0x22); // TP Notification
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Segmenter<'a> {
+    repr: Repr<'a>,
+    max_segment_length: usize,
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> Segmenter<'a> {
+    /// Create a new segmenter.
+    ///
+    /// # Arguments
+    ///
+    /// * `repr` - The message to split; the TP flag of its message type is set on the segments
+    /// * `max_segment_length` - Maximum length of the data of a segment, a non-zero
+    ///   multiple of 16 such as [`MAX_UDP_SEGMENT_LENGTH`]
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Segmenter`, `Error::InvalidMessageType` if the message type has no
+    /// variant with the TP flag set, or `Error::InvalidSegmentLength` if the maximum
+    /// segment length is not a non-zero multiple of 16.
+    pub fn new(repr: Repr<'a>, max_segment_length: usize) -> Result<Self, Error> {
+        if !repr.message_type.with_tp().is_tp() {
+            return Err(Error::InvalidMessageType(repr.message_type.as_u8()));
+        }
+        if max_segment_length == 0 || !max_segment_length.is_multiple_of(field::tp::OFFSET_UNIT) {
+            return Err(Error::InvalidSegmentLength(max_segment_length as u32));
+        }
+        Ok(Segmenter {
+            repr,
+            max_segment_length,
+            offset: 0,
+            done: false,
+        })
+    }
+
+    /// Returns the maximum length of the data of a segment
+    pub fn max_segment_length(&self) -> usize {
+        self.max_segment_length
+    }
+}
+
+impl<'a> Iterator for Segmenter<'a> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let data = self.repr.data;
+        let start = self.offset;
+        let end = data.len().min(start + self.max_segment_length);
+        self.offset = end;
+        self.done = end == data.len();
+
+        let tp = TpRepr {
+            offset: start as u32,
+            more_segments: !self.done,
+            data: &data[start..end],
+        };
+        let header = Repr {
+            length: 8 + tp.buffer_len() as u32,
+            message_type: self.repr.message_type.with_tp(),
+            data: &[],
+            ..self.repr
+        };
+        Some(Segment { header, tp })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = if self.done {
+            0
+        } else {
+            (self.repr.data.len() - self.offset)
+                .div_ceil(self.max_segment_length)
+                .max(1)
+        };
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Segmenter<'_> {}
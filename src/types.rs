@@ -383,6 +383,34 @@ impl MessageType {
                 | MessageType::TPError
         )
     }
+
+    /// Returns the message type with the TP flag set
+    ///
+    /// Used for the segments of messages sent with SOME/IP-TP.
+    pub const fn with_tp(&self) -> Self {
+        match self {
+            MessageType::Request => MessageType::TPRequest,
+            MessageType::RequestNoReturn => MessageType::TPRequestNoReturn,
+            MessageType::Notification => MessageType::TPNotification,
+            MessageType::Response => MessageType::TPResponse,
+            MessageType::Error => MessageType::TPError,
+            other => *other,
+        }
+    }
+
+    /// Returns the message type with the TP flag cleared
+    ///
+    /// Used for messages reassembled from SOME/IP-TP segments.
+    pub const fn without_tp(&self) -> Self {
+        match self {
+            MessageType::TPRequest => MessageType::Request,
+            MessageType::TPRequestNoReturn => MessageType::RequestNoReturn,
+            MessageType::TPNotification => MessageType::Notification,
+            MessageType::TPResponse => MessageType::Response,
+            MessageType::TPError => MessageType::Error,
+            other => *other,
+        }
+    }
 }
 
 // Convenience: convert to u8